@@ -1,6 +1,25 @@
-use crate::domain::{Task, TaskId, TaskState};
-use crate::infrastructure::TaskStorage;
-use chrono;
+use crate::domain::{
+    daily_streak, group_tasks, local_datetime, parse_task, parse_task_patch, CountsChanged,
+    GroupMode, Preferences, SortMode, Subtask, Task, TaskEvent, TaskId, TaskPatch, TaskPriority,
+    TaskRepository, TaskState, TaskStats, ThemeVariant, WeeklySummary,
+};
+use crate::infrastructure::{
+    is_valid_profile_name, open_repository, FileWatcher, TaskStorage, DEFAULT_PROFILE,
+};
+use chrono::{DateTime, Local};
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// File changes this soon after our own write are taken to be that write
+const OWN_WRITE_GRACE: Duration = Duration::from_millis(1000);
+
+/// Weighted pending load at which `storm_intensity` reaches 1.0, about a
+/// dozen ordinary tasks
+const STORM_FULL_WEIGHT: f32 = 12.0;
+
+/// How many actions can be undone unless `set_history_limit` says otherwise
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
 
 #[derive(Clone)]
 enum TaskAction {
@@ -8,101 +27,364 @@ enum TaskAction {
     Remove(Task),
     UpdateContent(TaskId, gpui::SharedString), // Stores OLD content
     Complete(TaskId),
+    UpdateNotes(TaskId, Option<String>), // Stores OLD notes
+    UpdateCompletionNote(TaskId, Option<String>), // Stores the OLD completion note
+    Reopen(TaskId),                      // A Done task moved back to Pending
+    Reorder { id: TaskId, from: usize, to: usize }, // Indices swapped in `tasks`
+    AddSubtask(TaskId, u64),
+    RemoveSubtask(TaskId, usize, Subtask), // Stores the removed step and its position
+    ToggleSubtask(TaskId, u64),
+    TogglePin(TaskId),
+    UpdatePriority(TaskId, TaskPriority), // Stores the OLD priority
+    UpdateDueDate(TaskId, Option<DateTime<Local>>, bool), // Stores the OLD due date and time flag
+    Batch(Vec<TaskAction>),               // Undone and redone as one step
+    ClearCompleted(Vec<Task>),            // Every Done task swept out at once
+    Update(TaskId, TaskPatch),            // Stores the OLD values of the fields that changed
+}
+
+/// What an undo or redo just did, so it can be told to the user
+#[derive(Debug, Clone, PartialEq)]
+pub enum UndoOutcome {
+    /// A task was taken back out of the list
+    Removed(gpui::SharedString),
+    /// A removed task is back in the list
+    ReAdded(gpui::SharedString),
+    /// A done task is pending again
+    Uncompleted(gpui::SharedString),
+    /// A task is done again
+    Completed(gpui::SharedString),
+    /// A task's title changed back
+    RestoredContent(gpui::SharedString),
+    /// Some other detail of a task changed, like its notes, due date or steps
+    Changed(gpui::SharedString),
+    /// Several changes at once, such as a batch action or clearing the done tasks
+    Batch(usize),
+}
+
+impl UndoOutcome {
+    /// A short phrase for a toast, e.g. "reopened 'Buy milk'"
+    pub fn describe(&self) -> String {
+        match self {
+            UndoOutcome::Removed(content) => format!("removed '{}'", content),
+            UndoOutcome::ReAdded(content) => format!("restored '{}'", content),
+            UndoOutcome::Uncompleted(content) => format!("reopened '{}'", content),
+            UndoOutcome::Completed(content) => format!("completed '{}'", content),
+            UndoOutcome::RestoredContent(content) => format!("renamed to '{}'", content),
+            UndoOutcome::Changed(content) => format!("changed '{}'", content),
+            UndoOutcome::Batch(1) => "1 change".to_string(),
+            UndoOutcome::Batch(count) => format!("{} changes", count),
+        }
+    }
 }
 
 /// Service for managing tasks
 /// This represents the application's use cases for task management
 pub struct TaskService {
     tasks: Vec<Task>,
-    storage: TaskStorage,
+    storage: Box<dyn TaskRepository>,
+    /// Name of the task list currently loaded
+    profile: String,
     history: Vec<TaskAction>,
+    redo_history: Vec<TaskAction>,
+    /// Oldest actions beyond this many are dropped and can no longer be undone
+    history_limit: usize,
+    preferences: Preferences,
+    /// Set when tasks changed since the last write
+    dirty: Cell<bool>,
+    revision: Cell<u64>,
+    /// Save synchronously on every change rather than waiting for `flush()`
+    sync_saves: bool,
+    /// Watches the storage file for edits made outside the app
+    watcher: Option<FileWatcher>,
+    /// When the latest write to storage began
+    last_write: Cell<Option<Instant>>,
+    /// Events not yet collected by `take_events()`
+    events: Vec<TaskEvent>,
+    /// Whether every task was overcome when events were last collected
+    was_overcome: bool,
+    /// Pending count when events were last collected, None before the first time
+    last_pending: Option<usize>,
+    /// Counts when `take_counts_change()` last reported them
+    last_counts: Option<CountsChanged>,
+    /// Whether storage had never been saved to when this service was created
+    first_run: bool,
 }
 
 impl TaskService {
     pub fn new() -> Self {
-        let storage = TaskStorage::new();
-        let tasks = storage.load().unwrap_or_default();
+        let profile = TaskStorage::active_profile();
+        let mut service = Self::with_storage(open_repository(&profile));
+        service.profile = profile;
+        service
+    }
+
+    /// Create backed by the given storage, e.g. `TaskStorage::with_path` for a
+    /// scratch file or `InMemoryRepository` to skip disk I/O entirely
+    pub fn with_storage(storage: Box<dyn TaskRepository>) -> Self {
+        let first_run = storage.is_fresh();
+        let (tasks, preferences) = storage.load().unwrap_or_default();
 
-        Self {
+        let mut service = Self {
             tasks,
             storage,
+            profile: DEFAULT_PROFILE.to_string(),
             history: Vec::new(),
-        }
+            redo_history: Vec::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            preferences,
+            dirty: Cell::new(false),
+            revision: Cell::new(0),
+            sync_saves: false,
+            watcher: None,
+            last_write: Cell::new(None),
+            events: Vec::new(),
+            was_overcome: false,
+            last_pending: None,
+            last_counts: None,
+            first_run,
+        };
+        service.validate_and_repair();
+        service.compact();
+        service.forget_events();
+        service
     }
 
-    /// Create with demo tasks (for first time use)
+    /// Create with demo tasks on a first run
+    /// A list that was saved empty stays empty, and setting `WALOYO_NO_DEMO=1`
+    /// skips the demo tasks altogether.
     pub fn new_with_defaults() -> Self {
-        let storage = TaskStorage::new();
-        let tasks = storage.load().unwrap_or_default();
-
-        // Only add demo tasks if storage is empty
-        if tasks.is_empty() {
-            let mut service = Self {
-                tasks,
-                storage,
-                history: Vec::new(),
-            };
-            service.add_task("Learn GPUI fundamentals !m");
-            service.add_task("Build Waloyo task manager !h @today");
-            service.add_task("Implement rain drop animation @tomorrow");
-            service.add_task("Add wind swaying effect !l");
-            service.add_task("Create clear sky celebration !h");
+        let mut service = Self::new();
+
+        let no_demo = std::env::var("WALOYO_NO_DEMO")
+            .is_ok_and(|v| !v.is_empty() && v != "0" && v != "false");
+        if service.should_seed_demo() && !no_demo {
+            service.seed_demo_tasks();
             // Clear history after initial defaults to avoid undoing them
             service.history.clear();
-            return service;
         }
 
-        Self {
-            tasks,
-            storage,
-            history: Vec::new(),
-        }
+        service
+    }
+
+    /// Whether this is a first run with nothing in the list yet, the only time
+    /// the demo tasks are added unasked
+    fn should_seed_demo(&self) -> bool {
+        self.first_run && self.tasks.is_empty()
+    }
+
+    /// Add a handful of example tasks showing off priorities and due dates
+    pub fn seed_demo_tasks(&mut self) {
+        self.add_task("Learn GPUI fundamentals !m");
+        self.add_task("Build Waloyo task manager !h @today");
+        self.add_task("Implement rain drop animation @tomorrow");
+        self.add_task("Add wind swaying effect !l");
+        self.add_task("Create clear sky celebration !h");
+    }
+
+    /// Record a new action, discarding any undone actions that could be redone
+    /// Once the history is full the oldest action is forgotten.
+    fn record(&mut self, action: TaskAction) {
+        self.history.push(action);
+        self.redo_history.clear();
+        self.trim_history();
     }
 
+    fn trim_history(&mut self) {
+        let excess = self.history.len().saturating_sub(self.history_limit);
+        self.history.drain(..excess);
+    }
+
+    /// Note that the tasks changed; the write happens on the next `flush()`
     fn save(&self) {
-        if let Err(e) = self.storage.save(&self.tasks) {
+        self.revision.set(self.revision.get() + 1);
+        self.dirty.set(true);
+        if self.sync_saves {
+            self.flush();
+        }
+    }
+
+    /// Collect what happened since the last call
+    /// `AllOvercome` is added when the list went from having work left to all done,
+    /// `PendingChanged` whenever the pending count differs from the previous call.
+    pub fn take_events(&mut self) -> Vec<TaskEvent> {
+        let overcome = self.all_overcome();
+        if overcome && !self.was_overcome {
+            self.events.push(TaskEvent::AllOvercome);
+        }
+        self.was_overcome = overcome;
+        let pending = self.pending_count();
+        if self.last_pending != Some(pending) {
+            self.events.push(TaskEvent::PendingChanged(pending));
+            self.last_pending = Some(pending);
+        }
+        std::mem::take(&mut self.events)
+    }
+
+    /// Send subscribers of the service entity what happened since the last call
+    /// Call after changing the tasks, inside the `update` that changed them,
+    /// and every so often besides so overdue counts keep up with the clock.
+    pub fn emit_events(&mut self, cx: &mut gpui::Context<Self>) {
+        for event in self.take_events() {
+            cx.emit(event);
+        }
+        if let Some(counts) = self.take_counts_change(Local::now()) {
+            cx.emit(counts);
+        }
+    }
+
+    /// The pending, done and overdue counts, if they differ from the last call
+    /// The first call always reports them. Overdue counts change with the
+    /// clock as well as with edits, so this needs calling from time to time,
+    /// not only after edits.
+    pub fn take_counts_change(&mut self, now: DateTime<Local>) -> Option<CountsChanged> {
+        let counts = CountsChanged {
+            pending: self.pending_count(),
+            completed: self.completed_count(),
+            overdue: self.overdue_count(now),
+        };
+        (self.last_counts.replace(counts) != Some(counts)).then_some(counts)
+    }
+
+    /// Start afresh from whatever was loaded, which nobody did just now
+    /// The pending count is kept, so observers still hear if loading changed it.
+    fn forget_events(&mut self) {
+        self.events.clear();
+        self.was_overcome = self.all_overcome();
+    }
+
+    /// Write unsaved changes to storage
+    pub fn flush(&self) {
+        if !self.dirty.replace(false) {
+            return;
+        }
+        self.last_write.set(Some(Instant::now()));
+        if let Err(e) = self.storage.save(&self.tasks, &self.preferences) {
             eprintln!("Failed to save tasks: {}", e);
         }
     }
 
+    /// Start noticing edits other programs make to the storage file
+    /// Without a watchable file or a working watcher backend this does nothing.
+    pub fn watch_for_external_changes(&mut self) {
+        self.watcher = None;
+        let Some(path) = self.storage.watch_path() else {
+            return;
+        };
+        match FileWatcher::watch(path) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => eprintln!("Not watching for external changes: {}", e),
+        }
+    }
+
+    /// Reload the tasks if another program changed the storage file, returning
+    /// true if they were reloaded
+    /// Changes right after our own write are ignored, as are changes that arrive
+    /// while we have unsaved edits (the next save wins).
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(changed_at) = self.watcher.as_ref().and_then(|w| w.take_change()) else {
+            return false;
+        };
+        let is_own_write = self.last_write.get().is_some_and(|written| {
+            changed_at
+                .checked_duration_since(written)
+                .is_some_and(|delay| delay < OWN_WRITE_GRACE)
+        });
+        if is_own_write {
+            return false;
+        }
+        if self.dirty.get() {
+            eprintln!("Storage changed on disk while edits were unsaved; keeping the edits");
+            return false;
+        }
+
+        match self.storage.load() {
+            Ok((tasks, preferences)) => {
+                self.tasks = tasks;
+                self.preferences = preferences;
+                // Undo entries may refer to tasks that no longer exist
+                self.history.clear();
+                self.redo_history.clear();
+                self.validate_and_repair();
+                self.forget_events();
+                true
+            }
+            Err(e) => {
+                eprintln!("Failed to reload changed storage: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Whether there are changes not yet written to storage
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// Counter bumped on every change, so a scheduled flush can tell whether
+    /// newer changes arrived while it was waiting
+    pub fn revision(&self) -> u64 {
+        self.revision.get()
+    }
+
+    /// Keep at most `limit` actions to undo, forgetting the oldest ones now if needed
+    #[allow(dead_code)]
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        self.trim_history();
+    }
+
+    /// Write on every change instead of waiting for `flush()`
+    #[allow(dead_code)]
+    pub fn set_sync_saves(&mut self, sync_saves: bool) {
+        self.sync_saves = sync_saves;
+        self.flush();
+    }
+
     /// Add a new task with smart parsing for metadata
+    /// Overlong titles are shortened, keeping the full text in the notes.
     pub fn add_task(&mut self, content: impl Into<gpui::SharedString>) -> TaskId {
-        let content_str = content.into();
-        let mut task = Task::new(content_str.clone());
-        let mut cleaned_content = content_str.to_string();
-
-        // Simple parsing for priority: !h, !m, !l
-        if cleaned_content.contains("!h") {
-            task.priority = crate::domain::TaskPriority::High;
-            cleaned_content = cleaned_content.replace("!h", "").trim().to_string();
-        } else if cleaned_content.contains("!m") {
-            task.priority = crate::domain::TaskPriority::Medium;
-            cleaned_content = cleaned_content.replace("!m", "").trim().to_string();
-        } else if cleaned_content.contains("!l") {
-            task.priority = crate::domain::TaskPriority::Low;
-            cleaned_content = cleaned_content.replace("!l", "").trim().to_string();
-        }
-
-        // Simple parsing for due date: @today, @tomorrow
-        let now = chrono::Local::now();
-        if cleaned_content.contains("@today") {
-            task.due_date = Some(now);
-            cleaned_content = cleaned_content.replace("@today", "").trim().to_string();
-        } else if cleaned_content.contains("@tomorrow") {
-            task.due_date = Some(now + chrono::Duration::days(1));
-            cleaned_content = cleaned_content.replace("@tomorrow", "").trim().to_string();
-        }
-
-        task.content = gpui::SharedString::from(cleaned_content);
+        let content: gpui::SharedString = content.into();
+        let mut task = parse_task(&content, Local::now());
+        task.shorten_long_content();
 
         let id = task.id;
         self.tasks.push(task);
-        self.history.push(TaskAction::Add(id));
+        self.record(TaskAction::Add(id));
         self.save();
         id
     }
 
+    /// Add a pending copy of a task right after it, with its steps unchecked
+    pub fn duplicate_task(&mut self, id: TaskId) -> Option<TaskId> {
+        let pos = self.tasks.iter().position(|t| t.id == id)?;
+        let original = &self.tasks[pos];
+        let mut copy = Task::new(original.content.clone());
+        copy.notes = original.notes.clone();
+        copy.priority = original.priority;
+        copy.set_due(original.due_date, original.due_has_time);
+        copy.tags = original.tags.clone();
+        copy.recurrence = original.recurrence;
+        copy.pinned = original.pinned;
+        copy.effort = original.effort;
+        copy.reminder_lead = original.reminder_lead;
+        copy.subtasks = original
+            .subtasks
+            .iter()
+            .map(|s| Subtask {
+                done: false,
+                ..s.clone()
+            })
+            .collect();
+
+        let copy_id = copy.id;
+        self.tasks.insert(pos + 1, copy);
+        self.record(TaskAction::Add(copy_id));
+        self.save();
+        Some(copy_id)
+    }
+
     /// Update task content
+    #[allow(dead_code)]
     pub fn update_task_content(
         &mut self,
         id: TaskId,
@@ -112,10 +394,9 @@ impl TaskService {
         if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
             let old_content = task.content.clone();
             if old_content != content {
-                self.history
-                    .push(TaskAction::UpdateContent(id, old_content));
                 task.content = content;
-                task.updated_at = std::time::Instant::now();
+                task.updated_at = chrono::Local::now();
+                self.record(TaskAction::UpdateContent(id, old_content));
                 self.save();
             }
             true
@@ -124,11 +405,75 @@ impl TaskService {
         }
     }
 
-    /// Update task notes
+    /// Change several fields at once as a single undoable edit
+    /// Quick-add tokens (`!h`, `@today`, `~daily`, `#tag`) in new content are parsed
+    /// out and win over the other fields; parsed tags are added to the existing ones.
+    /// Blank content and blank notes are treated like in the single-field updates.
+    pub fn update_task(&mut self, id: TaskId, mut patch: TaskPatch) -> bool {
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) else {
+            return false;
+        };
+
+        if let Some(content) = patch.content.take() {
+            let parsed = parse_task_patch(&content, Local::now());
+            patch.content = parsed.content.filter(|c| !c.trim().is_empty());
+            patch.priority = parsed.priority.or(patch.priority);
+            if parsed.due_date.is_some() {
+                patch.due_date = parsed.due_date;
+                patch.due_has_time = parsed.due_has_time;
+            }
+            patch.recurrence = parsed.recurrence.or(patch.recurrence);
+            patch.reminder_lead = parsed.reminder_lead.or(patch.reminder_lead);
+            if let Some(parsed_tags) = parsed.tags {
+                let mut tags = patch.tags.take().unwrap_or_else(|| task.tags.clone());
+                for tag in parsed_tags {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                }
+                patch.tags = Some(tags);
+            }
+        }
+        patch.notes = patch
+            .notes
+            .map(|notes| notes.filter(|n| !n.trim().is_empty()));
+
+        let inverse = task.apply(patch);
+        if !inverse.is_empty() {
+            self.record(TaskAction::Update(id, inverse));
+            self.save();
+        }
+        true
+    }
+
+    /// Update task notes; blank notes clear the field
     pub fn update_task_notes(&mut self, id: TaskId, notes: Option<String>) -> bool {
+        let notes = notes.filter(|n| !n.trim().is_empty());
         if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-            task.notes = notes;
-            task.updated_at = std::time::Instant::now();
+            if task.notes != notes {
+                let old_notes = std::mem::replace(&mut task.notes, notes);
+                task.updated_at = chrono::Local::now();
+                self.record(TaskAction::UpdateNotes(id, old_notes));
+                self.save();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Update task due date and whether it has a time of day
+    pub fn update_task_due_date(
+        &mut self,
+        id: TaskId,
+        due_date: Option<chrono::DateTime<chrono::Local>>,
+        has_time: bool,
+    ) -> bool {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            let old = (task.due_date, task.due_has_time);
+            task.set_due(due_date, has_time);
+            task.updated_at = chrono::Local::now();
+            self.record(TaskAction::UpdateDueDate(id, old.0, old.1));
             self.save();
             true
         } else {
@@ -136,6 +481,220 @@ impl TaskService {
         }
     }
 
+    /// Push a pending task's due date back by `duration` as one undoable step
+    /// Overdue tasks are pushed from now rather than their stale date, and tasks
+    /// without a due date become due `duration` from now. Date-only due dates move
+    /// by whole days and stay date-only.
+    pub fn snooze_task(&mut self, id: TaskId, duration: chrono::Duration) -> bool {
+        let now = Local::now();
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == id && t.is_pending()) else {
+            return false;
+        };
+        let has_time = task.due_date.is_none() || task.has_due_time();
+        let snoozed = match task.due_date {
+            None => Some(now + duration),
+            Some(due) if has_time => Some(due.max(now) + duration),
+            Some(due) => due
+                .date_naive()
+                .max(now.date_naive())
+                .checked_add_signed(duration)
+                .and_then(|day| local_datetime(day, chrono::NaiveTime::MIN)),
+        };
+        let Some(snoozed) = snoozed else {
+            return false;
+        };
+
+        let old = (task.due_date, task.due_has_time);
+        task.set_due(Some(snoozed), has_time);
+        task.updated_at = now;
+        self.record(TaskAction::UpdateDueDate(id, old.0, old.1));
+        self.save();
+        true
+    }
+
+    /// Find a task by id
+    pub fn get_task(&self, id: TaskId) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Find the pending tasks the query matches, best match first, see
+    /// `Task::search_score`. Equally good matches keep their order in the list's
+    /// sort mode, so an empty query gives the whole pending list as shown.
+    pub fn search(&self, query: &str) -> Vec<&Task> {
+        let mut scored: Vec<(i32, &Task)> = self
+            .sorted_pending(self.sort_mode(), Local::now())
+            .into_iter()
+            .filter_map(|t| t.search_score(query).map(|score| (score, t)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, task)| task).collect()
+    }
+
+    /// Get the tasks not yet done (including completing ones), ordered by `mode`
+    /// Outside manual ordering, tasks overdue at `now` rise above the rest.
+    pub fn sorted_pending(&self, mode: SortMode, now: DateTime<Local>) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.tasks.iter().filter(|t| !t.is_done()).collect();
+        match mode {
+            SortMode::Manual => {}
+            SortMode::PriorityDesc => tasks.sort_by(|a, b| {
+                b.priority
+                    .cmp(&a.priority)
+                    .then(a.created_at.cmp(&b.created_at))
+            }),
+            SortMode::DueDateAsc => tasks.sort_by(|a, b| {
+                let by_due = match (a.due_date, b.due_date) {
+                    (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+                    (Some(_), None) => std::cmp::Ordering::Less,
+                    (None, Some(_)) => std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                };
+                by_due.then(a.created_at.cmp(&b.created_at))
+            }),
+            SortMode::CreatedAsc => tasks.sort_by_key(|t| t.created_at),
+        }
+        if mode != SortMode::Manual {
+            // Stable, so the mode's ordering holds within each group
+            tasks.sort_by_key(|t| !t.is_overdue(now));
+        }
+        // Pinned tasks stay on top in every mode, keeping the order above among themselves
+        tasks.sort_by_key(|t| !t.pinned);
+        tasks
+    }
+
+    /// The persisted ordering of the pending list
+    pub fn sort_mode(&self) -> SortMode {
+        self.preferences.sort_mode
+    }
+
+    /// Change the ordering of the pending list
+    pub fn set_sort_mode(&mut self, mode: SortMode) {
+        self.preferences.sort_mode = mode;
+        self.save();
+    }
+
+    /// Pending tasks in the current sort order, split into headed sections
+    /// By tag, a task with several tags appears in each of their sections.
+    pub fn grouped_pending(
+        &self,
+        mode: GroupMode,
+        now: DateTime<Local>,
+    ) -> Vec<(gpui::SharedString, Vec<&Task>)> {
+        group_tasks(self.sorted_pending(self.sort_mode(), now), mode, now)
+    }
+
+    /// The persisted grouping of the pending list
+    pub fn group_mode(&self) -> GroupMode {
+        self.preferences.group_mode
+    }
+
+    /// Change the grouping of the pending list
+    pub fn set_group_mode(&mut self, mode: GroupMode) {
+        self.preferences.group_mode = mode;
+        self.save();
+    }
+
+    /// The persisted palette variant
+    pub fn theme_variant(&self) -> ThemeVariant {
+        self.preferences.theme
+    }
+
+    /// Change the palette variant
+    pub fn set_theme_variant(&mut self, theme: ThemeVariant) {
+        self.preferences.theme = theme;
+        self.save();
+    }
+
+    /// Whether animations are turned off
+    pub fn reduced_motion(&self) -> bool {
+        self.preferences.reduced_motion
+    }
+
+    /// Turn animations off or back on
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.preferences.reduced_motion = reduced_motion;
+        self.save();
+    }
+
+    /// Whether the Overcome section is folded, if the user has chosen
+    pub fn completed_collapsed(&self) -> Option<bool> {
+        self.preferences.completed_collapsed
+    }
+
+    /// Remember whether the Overcome section is folded
+    pub fn set_completed_collapsed(&mut self, collapsed: bool) {
+        self.preferences.completed_collapsed = Some(collapsed);
+        self.save();
+    }
+
+    /// Whether the Overcome section lists the oldest completion first
+    pub fn completed_oldest_first(&self) -> bool {
+        self.preferences.completed_oldest_first
+    }
+
+    /// Remember which way the Overcome section is ordered
+    pub fn set_completed_oldest_first(&mut self, oldest_first: bool) {
+        self.preferences.completed_oldest_first = oldest_first;
+        self.save();
+    }
+
+    /// Whether only the top task is shown
+    pub fn focus_mode(&self) -> bool {
+        self.preferences.focus_mode
+    }
+
+    /// Remember whether only the top task is shown
+    pub fn set_focus_mode(&mut self, focus_mode: bool) {
+        self.preferences.focus_mode = focus_mode;
+        self.save();
+    }
+
+    /// Colors chosen for tags, as `#rrggbb` keyed by lowercase tag name
+    pub fn tag_colors(&self) -> &HashMap<String, String> {
+        &self.preferences.tag_colors
+    }
+
+    /// The color chosen for `tag`, if any
+    #[allow(dead_code)]
+    pub fn tag_color(&self, tag: &str) -> Option<&str> {
+        self.preferences
+            .tag_colors
+            .get(&tag.trim_start_matches('#').to_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Choose a `#rrggbb` color for `tag`, or clear it with None
+    #[allow(dead_code)]
+    pub fn set_tag_color(&mut self, tag: &str, color: Option<&str>) -> Result<(), String> {
+        let tag = tag.trim_start_matches('#').to_lowercase();
+        match color {
+            Some(color) => {
+                let is_hex = color.len() == 7
+                    && color.starts_with('#')
+                    && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+                if !is_hex {
+                    return Err(format!("Invalid tag color '{}', expected #rrggbb", color));
+                }
+                self.preferences
+                    .tag_colors
+                    .insert(tag, color.to_lowercase());
+            }
+            None => {
+                self.preferences.tag_colors.remove(&tag);
+            }
+        }
+        self.save();
+        Ok(())
+    }
+
+    /// The one task to work on next: highest priority first, then soonest due
+    /// A task still completing stays on top until its rain drop has fallen.
+    pub fn top_task(&self, now: DateTime<Local>) -> Option<&Task> {
+        let mut tasks = self.sorted_pending(SortMode::DueDateAsc, now);
+        // Stable, so the due date order holds within each priority
+        tasks.sort_by(|a, b| b.pinned.cmp(&a.pinned).then(b.priority.cmp(&a.priority)));
+        tasks.into_iter().next()
+    }
+
     /// Get all pending tasks
     #[allow(dead_code)]
     pub fn pending_tasks(&self) -> impl Iterator<Item = &Task> {
@@ -143,16 +702,169 @@ impl TaskService {
     }
 
     /// Get all completed tasks
-    #[allow(dead_code)]
     pub fn completed_tasks(&self) -> impl Iterator<Item = &Task> {
         self.tasks.iter().filter(|t| t.state == TaskState::Done)
     }
 
+    /// Completed tasks ordered by when they were completed
+    /// Completing a task stamps `updated_at`, which dates it here; ties keep
+    /// their stored order.
+    pub fn completed_sorted(&self, newest_first: bool) -> Vec<&Task> {
+        let mut tasks: Vec<&Task> = self.completed_tasks().collect();
+        if newest_first {
+            tasks.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        } else {
+            tasks.sort_by_key(|t| t.updated_at);
+        }
+        tasks
+    }
+
     /// Get all tasks (for rendering)
     pub fn all_tasks(&self) -> &[Task] {
         &self.tasks
     }
 
+    /// Import tasks from a Markdown checklist, each as its own undoable add
+    #[allow(dead_code)]
+    pub fn import_markdown(&mut self, content: &str) -> usize {
+        let imported = TaskStorage::import_markdown(content);
+        let count = imported.len();
+        for task in imported {
+            let id = task.id;
+            self.tasks.push(task);
+            self.record(TaskAction::Add(id));
+        }
+        if count > 0 {
+            self.save();
+        }
+        count
+    }
+
+    /// The whole in-memory state, unsaved changes included, as pretty JSON in the
+    /// storage format, for attaching to bug reports
+    pub fn export_state_json(&self) -> String {
+        TaskStorage::export_state_json(&self.tasks, &self.preferences, false)
+    }
+
+    /// Like `export_state_json`, with the text of every task masked by
+    /// placeholders of the same length, to share the shape without the contents
+    pub fn export_state_json_redacted(&self) -> String {
+        TaskStorage::export_state_json(&self.tasks, &self.preferences, true)
+    }
+
+    /// Render every task as a Markdown checklist
+    pub fn export_markdown(&self) -> String {
+        TaskStorage::export_markdown(&self.tasks)
+    }
+
+    /// Replace every task with those from a backup file
+    /// History is cleared, since the actions in it referred to the old task set.
+    #[allow(dead_code)]
+    pub fn restore_from(&mut self, path: &std::path::Path) -> Result<usize, String> {
+        self.tasks = self.storage.restore_from(path)?;
+        self.history.clear();
+        self.redo_history.clear();
+        self.save();
+        Ok(self.tasks.len())
+    }
+
+    /// Name of the active profile
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Every profile that can be switched to
+    pub fn profiles() -> Vec<String> {
+        TaskStorage::profiles()
+    }
+
+    /// Save the current list and load another profile's tasks
+    /// The theme and motion settings carry over, since they apply to the whole app
+    /// rather than one list.
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), String> {
+        if name == self.profile {
+            return Ok(());
+        }
+        if !is_valid_profile_name(name) {
+            return Err(format!("Invalid profile name: {}", name));
+        }
+
+        self.flush();
+        let storage = open_repository(name);
+        let (tasks, mut preferences) = storage.load()?;
+        preferences.theme = self.preferences.theme;
+        preferences.reduced_motion = self.preferences.reduced_motion;
+
+        self.storage = storage;
+        if self.watcher.is_some() {
+            self.watch_for_external_changes();
+        }
+        self.tasks = tasks;
+        self.preferences = preferences;
+        self.profile = name.to_string();
+        self.history.clear();
+        self.redo_history.clear();
+        self.validate_and_repair();
+        self.forget_events();
+
+        TaskStorage::set_active_profile(name)
+    }
+
+    /// Render every task as CSV for spreadsheets
+    pub fn export_csv(&self) -> String {
+        TaskStorage::export_csv(&self.tasks)
+    }
+
+    /// Import tasks from CSV, each as its own undoable add
+    /// Nothing is imported if any row is malformed.
+    #[allow(dead_code)]
+    pub fn import_csv(&mut self, content: &str) -> Result<usize, String> {
+        let imported = TaskStorage::import_csv(content)?;
+        let count = imported.len();
+        for mut task in imported {
+            // Keep ids unique when importing alongside existing tasks
+            while self.tasks.iter().any(|t| t.id == task.id) {
+                task.id = TaskId::new();
+            }
+            let id = task.id;
+            self.tasks.push(task);
+            self.record(TaskAction::Add(id));
+        }
+        if count > 0 {
+            self.save();
+        }
+        Ok(count)
+    }
+
+    /// Recap the tasks overcome in the week containing `week_of`
+    /// Archived tasks aren't included, since they've left the list.
+    pub fn weekly_summary(&self, week_of: chrono::NaiveDate) -> WeeklySummary {
+        WeeklySummary::new(&self.tasks, week_of)
+    }
+
+    /// Render tasks with a due date as an iCalendar feed
+    pub fn export_ics(&self) -> String {
+        TaskStorage::export_ics(&self.tasks)
+    }
+
+    /// Sweep every Done task out of the list into the archive file
+    /// Undoing brings them all back (they also stay in the archive).
+    pub fn clear_completed(&mut self) -> Vec<Task> {
+        let (cleared, remaining): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|t| t.is_done());
+        self.tasks = remaining;
+
+        if !cleared.is_empty() {
+            if let Err(e) = self.storage.archive(&cleared) {
+                eprintln!("Failed to archive completed tasks: {}", e);
+            }
+            self.record(TaskAction::ClearCompleted(cleared.clone()));
+            self.save();
+        }
+        cleared
+    }
+
     /// Begin completing a task (starts animation)
     pub fn begin_completing(&mut self, id: TaskId) -> bool {
         if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
@@ -164,14 +876,196 @@ impl TaskService {
     }
 
     /// Finish completing a task (after animation)
+    /// Recurring tasks leave a fresh pending copy behind for their next occurrence.
     pub fn finish_completing(&mut self, id: TaskId) -> bool {
-        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-            task.complete();
-            self.history.push(TaskAction::Complete(id));
+        if !self.tasks.iter().any(|t| t.id == id && t.is_completing()) {
+            return false;
+        }
+        match self.mark_done(id) {
+            Some(action) => {
+                self.record(action);
+                self.save();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Complete a task and keep a note on how it was overcome, as one undoable step
+    /// Tasks still pending or mid-animation are completed straight away; for tasks
+    /// already done only the note changes. A blank note clears it.
+    pub fn complete_with_note(&mut self, id: TaskId, note: Option<String>) -> bool {
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let completed = self.mark_done(id);
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) else {
+            return false;
+        };
+        let noted = (task.completion_note != note).then(|| {
+            let old_note = std::mem::replace(&mut task.completion_note, note);
+            task.updated_at = chrono::Local::now();
+            TaskAction::UpdateCompletionNote(id, old_note)
+        });
+
+        let action = match (completed, noted) {
+            (Some(completed), Some(noted)) => TaskAction::Batch(vec![completed, noted]),
+            (Some(action), None) | (None, Some(action)) => action,
+            (None, None) => return true,
+        };
+        self.record(action);
+        self.save();
+        true
+    }
+
+    /// Complete several tasks at once, without the animation, as one undoable step
+    /// Returns how many tasks were completed; ids of done or missing tasks are skipped.
+    pub fn complete_many(&mut self, ids: &[TaskId]) -> usize {
+        let actions: Vec<_> = ids.iter().filter_map(|id| self.mark_done(*id)).collect();
+        let count = actions.len();
+        if count > 0 {
+            self.record(TaskAction::Batch(actions));
             self.save();
-            true
-        } else {
-            false
+        }
+        count
+    }
+
+    /// Remove several tasks at once as one undoable step, returning how many were removed
+    pub fn remove_many(&mut self, ids: &[TaskId]) -> usize {
+        let mut actions = Vec::new();
+        for id in ids {
+            if let Some(pos) = self.tasks.iter().position(|t| t.id == *id) {
+                self.events.push(TaskEvent::Deleted(*id));
+                actions.push(TaskAction::Remove(self.tasks.remove(pos)));
+            }
+        }
+        let count = actions.len();
+        if count > 0 {
+            self.record(TaskAction::Batch(actions));
+            self.save();
+        }
+        count
+    }
+
+    /// Mark a task done, returning the action that records it
+    /// Recurring tasks leave a fresh pending copy behind for their next occurrence.
+    fn mark_done(&mut self, id: TaskId) -> Option<TaskAction> {
+        let task = self.tasks.iter_mut().find(|t| t.id == id && !t.is_done())?;
+        task.complete();
+        self.events.push(TaskEvent::Completed(id));
+        let next = task.recurrence.map(|recurrence| {
+            let mut next = Task::new(task.content.clone());
+            next.priority = task.priority;
+            next.tags = task.tags.clone();
+            next.pinned = task.pinned;
+            next.effort = task.effort;
+            next.reminder_lead = task.reminder_lead;
+            next.recurrence = Some(recurrence);
+            next.set_due(
+                recurrence.next_due(task.due_date, chrono::Local::now()),
+                task.due_has_time,
+            );
+            next
+        });
+
+        Some(match next {
+            Some(next) => {
+                let next_id = next.id;
+                self.tasks.push(next);
+                TaskAction::Batch(vec![TaskAction::Complete(id), TaskAction::Add(next_id)])
+            }
+            None => TaskAction::Complete(id),
+        })
+    }
+
+    /// Move every pending task ahead of the done ones, keeping the order within each
+    /// Run once after loading, so pending tasks sit together at the front of the
+    /// list. The two are shown in separate sections, so nothing moves on screen.
+    /// Returns whether any task moved.
+    pub fn compact(&mut self) -> bool {
+        let Some(first_done) = self.tasks.iter().position(|t| t.is_done()) else {
+            return false;
+        };
+        if self.tasks[first_done..].iter().all(|t| t.is_done()) {
+            return false;
+        }
+
+        let (mut pending, done): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|t| !t.is_done());
+        pending.extend(done);
+        self.tasks = pending;
+        true
+    }
+
+    /// Fix state that a hand-edited file or an interrupted session can leave
+    /// behind, returning a line for each repair made
+    /// Run after every load. A task sharing its id with an earlier one gets a
+    /// fresh id, and a task still completing, whose rain drop can't be falling
+    /// in a new session, goes back to pending. Repairs are logged and saved.
+    /// Due dates need no repair here: timed ones are saved as instants, and
+    /// date-only ones keep the day they were written in whatever the timezone,
+    /// since storage saves them with their offset and `due_has_time`.
+    pub fn validate_and_repair(&mut self) -> Vec<String> {
+        let mut repairs = Vec::new();
+
+        if let Some(max_id) = self.tasks.iter().map(|t| t.id.0).max() {
+            TaskId::bump_past(max_id);
+        }
+        let mut seen = HashSet::new();
+        for task in &mut self.tasks {
+            if !seen.insert(task.id) {
+                let old_id = task.id;
+                task.id = TaskId::new();
+                repairs.push(format!(
+                    "'{}' shared id {} with another task; it now has id {}",
+                    task.content, old_id.0, task.id.0
+                ));
+            }
+        }
+
+        for task in &mut self.tasks {
+            if task.is_completing() {
+                task.state = TaskState::Pending;
+                repairs.push(format!(
+                    "'{}' was left mid-completion; it's pending again",
+                    task.content
+                ));
+            }
+        }
+
+        for repair in &repairs {
+            eprintln!("Repaired task list: {}", repair);
+        }
+        if !repairs.is_empty() {
+            self.save();
+        }
+        repairs
+    }
+
+    /// Finish every task whose completion animation hasn't run its course
+    /// Used when quitting mid-animation, so the task ends up done.
+    pub fn finish_all_completing(&mut self) -> usize {
+        let completing: Vec<TaskId> = self
+            .tasks
+            .iter()
+            .filter(|t| t.is_completing())
+            .map(|t| t.id)
+            .collect();
+        for id in &completing {
+            self.finish_completing(*id);
+        }
+        completing.len()
+    }
+
+    /// Move a done task back to pending, as an undoable action
+    pub fn reopen_task(&mut self, id: TaskId) -> bool {
+        match self.tasks.iter_mut().find(|t| t.id == id && t.is_done()) {
+            Some(task) => {
+                task.reopen();
+                self.record(TaskAction::Reopen(id));
+                self.save();
+                true
+            }
+            None => false,
         }
     }
 
@@ -179,7 +1073,8 @@ impl TaskService {
     pub fn remove_task(&mut self, id: TaskId) -> Option<Task> {
         if let Some(pos) = self.tasks.iter().position(|t| t.id == id) {
             let task = self.tasks.remove(pos);
-            self.history.push(TaskAction::Remove(task.clone()));
+            self.events.push(TaskEvent::Deleted(id));
+            self.record(TaskAction::Remove(task.clone()));
             self.save();
             Some(task)
         } else {
@@ -187,31 +1082,75 @@ impl TaskService {
         }
     }
 
-    /// Undo last action
-    pub fn undo(&mut self) -> bool {
-        if let Some(action) = self.history.pop() {
-            match action {
-                TaskAction::Add(id) => {
-                    if let Some(pos) = self.tasks.iter().position(|t| t.id == id) {
-                        self.tasks.remove(pos);
-                    }
-                }
-                TaskAction::Remove(task) => {
-                    self.tasks.push(task);
-                }
-                TaskAction::UpdateContent(id, old_content) => {
-                    if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-                        task.content = old_content;
-                        task.updated_at = std::time::Instant::now();
-                    }
-                }
-                TaskAction::Complete(id) => {
-                    if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-                        task.state = TaskState::Pending;
-                        task.updated_at = std::time::Instant::now();
-                    }
-                }
+    /// Add a checklist step to a task, returning the step's id
+    pub fn add_subtask(&mut self, id: TaskId, content: impl Into<String>) -> Option<u64> {
+        let content = content.into().trim().to_string();
+        if content.is_empty() {
+            return None;
+        }
+        let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+        let subtask_id = task.subtasks.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+        task.subtasks.push(Subtask {
+            id: subtask_id,
+            content,
+            done: false,
+        });
+        task.updated_at = chrono::Local::now();
+        self.record(TaskAction::AddSubtask(id, subtask_id));
+        self.save();
+        Some(subtask_id)
+    }
+
+    /// Flip a checklist step between done and not done
+    pub fn toggle_subtask(&mut self, id: TaskId, subtask_id: u64) -> bool {
+        if self.flip_subtask(id, subtask_id) {
+            self.record(TaskAction::ToggleSubtask(id, subtask_id));
+            self.save();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Remove a checklist step
+    pub fn remove_subtask(&mut self, id: TaskId, subtask_id: u64) -> bool {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            if let Some(pos) = task.subtasks.iter().position(|s| s.id == subtask_id) {
+                let subtask = task.subtasks.remove(pos);
+                task.updated_at = chrono::Local::now();
+                self.record(TaskAction::RemoveSubtask(id, pos, subtask));
+                self.save();
+                return true;
             }
+        }
+        false
+    }
+
+    /// Raise a pending task's priority one step, wrapping from Urgent back to Low
+    pub fn cycle_priority(&mut self, id: TaskId) -> bool {
+        self.step_priority(id, TaskPriority::next)
+    }
+
+    /// Lower a pending task's priority one step, wrapping from Low round to Urgent
+    pub fn cycle_priority_back(&mut self, id: TaskId) -> bool {
+        self.step_priority(id, TaskPriority::previous)
+    }
+
+    fn step_priority(&mut self, id: TaskId, step: fn(TaskPriority) -> TaskPriority) -> bool {
+        let Some(task) = self.tasks.iter_mut().find(|t| t.id == id && t.is_pending()) else {
+            return false;
+        };
+        let old_priority = std::mem::replace(&mut task.priority, step(task.priority));
+        task.updated_at = chrono::Local::now();
+        self.record(TaskAction::UpdatePriority(id, old_priority));
+        self.save();
+        true
+    }
+
+    /// Pin a task to the top of the pending list, or unpin it
+    pub fn toggle_pin(&mut self, id: TaskId) -> bool {
+        if self.flip_pin(id) {
+            self.record(TaskAction::TogglePin(id));
             self.save();
             true
         } else {
@@ -219,24 +1158,674 @@ impl TaskService {
         }
     }
 
+    fn flip_pin(&mut self, id: TaskId) -> bool {
+        if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
+            task.pinned = !task.pinned;
+            task.updated_at = chrono::Local::now();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn flip_subtask(&mut self, id: TaskId, subtask_id: u64) -> bool {
+        let subtask = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .and_then(|t| t.subtasks.iter_mut().find(|s| s.id == subtask_id));
+        if let Some(subtask) = subtask {
+            subtask.done = !subtask.done;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move a task above the previous not-yet-done task
+    pub fn move_task_up(&mut self, id: TaskId) -> bool {
+        if let Some(from) = self.tasks.iter().position(|t| t.id == id) {
+            let to = self.tasks[..from].iter().rposition(|t| !t.is_done());
+            self.swap_tasks(id, from, to)
+        } else {
+            false
+        }
+    }
+
+    /// Move a task below the next not-yet-done task
+    pub fn move_task_down(&mut self, id: TaskId) -> bool {
+        if let Some(from) = self.tasks.iter().position(|t| t.id == id) {
+            let to = self.tasks[from + 1..]
+                .iter()
+                .position(|t| !t.is_done())
+                .map(|offset| from + 1 + offset);
+            self.swap_tasks(id, from, to)
+        } else {
+            false
+        }
+    }
+
+    fn swap_tasks(&mut self, id: TaskId, from: usize, to: Option<usize>) -> bool {
+        if let Some(to) = to {
+            self.tasks.swap(from, to);
+            self.record(TaskAction::Reorder { id, from, to });
+            self.save();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Undo last action
+    /// Returns what changed, or None if there was nothing to undo or the action
+    /// no longer applies.
+    pub fn undo(&mut self) -> Option<UndoOutcome> {
+        let action = self.history.pop()?;
+        let inverse = self.revert(action);
+        self.save();
+        let inverse = inverse?;
+        let outcome = self.outcome_of(&inverse);
+        self.redo_history.push(inverse);
+        Some(outcome)
+    }
+
+    /// Redo the last undone action
+    /// Returns what changed, like `undo`.
+    pub fn redo(&mut self) -> Option<UndoOutcome> {
+        let action = self.redo_history.pop()?;
+        let inverse = self.revert(action);
+        self.save();
+        let inverse = inverse?;
+        let outcome = self.outcome_of(&inverse);
+        self.history.push(inverse);
+        Some(outcome)
+    }
+
+    /// Describe the change just made, from the action that would reverse it
+    fn outcome_of(&self, inverse: &TaskAction) -> UndoOutcome {
+        let content = |id: &TaskId| {
+            self.get_task(*id)
+                .map(|t| t.content.clone())
+                .unwrap_or_default()
+        };
+        match inverse {
+            TaskAction::Remove(task) => UndoOutcome::Removed(task.content.clone()),
+            TaskAction::Add(id) => UndoOutcome::ReAdded(content(id)),
+            TaskAction::Reopen(id) => UndoOutcome::Uncompleted(content(id)),
+            TaskAction::Complete(id) => UndoOutcome::Completed(content(id)),
+            TaskAction::UpdateContent(id, _) => UndoOutcome::RestoredContent(content(id)),
+            TaskAction::Batch(actions) if actions.len() == 1 => self.outcome_of(&actions[0]),
+            TaskAction::Batch(actions) => UndoOutcome::Batch(actions.len()),
+            TaskAction::ClearCompleted(tasks) => UndoOutcome::Batch(tasks.len()),
+            TaskAction::UpdateNotes(id, _)
+            | TaskAction::UpdateCompletionNote(id, _)
+            | TaskAction::Reorder { id, .. }
+            | TaskAction::AddSubtask(id, _)
+            | TaskAction::RemoveSubtask(id, _, _)
+            | TaskAction::ToggleSubtask(id, _)
+            | TaskAction::TogglePin(id)
+            | TaskAction::UpdatePriority(id, _)
+            | TaskAction::UpdateDueDate(id, ..)
+            | TaskAction::Update(id, _) => UndoOutcome::Changed(content(id)),
+        }
+    }
+
+    /// Reverse an action, returning the action that would reverse it again
+    fn revert(&mut self, action: TaskAction) -> Option<TaskAction> {
+        match action {
+            TaskAction::Add(id) => {
+                let pos = self.tasks.iter().position(|t| t.id == id)?;
+                Some(TaskAction::Remove(self.tasks.remove(pos)))
+            }
+            TaskAction::Remove(task) => {
+                let id = task.id;
+                self.tasks.push(task);
+                Some(TaskAction::Add(id))
+            }
+            TaskAction::ClearCompleted(tasks) => {
+                // Redoing removes the same tasks again, one Add inverse per task
+                let ids = tasks.iter().map(|t| TaskAction::Add(t.id)).collect();
+                self.tasks.extend(tasks);
+                Some(TaskAction::Batch(ids))
+            }
+            TaskAction::UpdateContent(id, old_content) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let new_content = std::mem::replace(&mut task.content, old_content);
+                task.updated_at = chrono::Local::now();
+                Some(TaskAction::UpdateContent(id, new_content))
+            }
+            TaskAction::Update(id, old_values) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                Some(TaskAction::Update(id, task.apply(old_values)))
+            }
+            TaskAction::UpdateNotes(id, old_notes) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let new_notes = std::mem::replace(&mut task.notes, old_notes);
+                task.updated_at = chrono::Local::now();
+                Some(TaskAction::UpdateNotes(id, new_notes))
+            }
+            TaskAction::UpdateCompletionNote(id, old_note) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let new_note = std::mem::replace(&mut task.completion_note, old_note);
+                task.updated_at = chrono::Local::now();
+                Some(TaskAction::UpdateCompletionNote(id, new_note))
+            }
+            TaskAction::Complete(id) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                task.reopen();
+                Some(TaskAction::Reopen(id))
+            }
+            TaskAction::Reopen(id) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                task.complete();
+                Some(TaskAction::Complete(id))
+            }
+            TaskAction::Reorder { id, from, to } => {
+                if from >= self.tasks.len() || self.tasks.get(to).map(|t| t.id) != Some(id) {
+                    return None;
+                }
+                self.tasks.swap(from, to);
+                Some(TaskAction::Reorder {
+                    id,
+                    from: to,
+                    to: from,
+                })
+            }
+            TaskAction::AddSubtask(id, subtask_id) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let pos = task.subtasks.iter().position(|s| s.id == subtask_id)?;
+                let subtask = task.subtasks.remove(pos);
+                Some(TaskAction::RemoveSubtask(id, pos, subtask))
+            }
+            TaskAction::RemoveSubtask(id, pos, subtask) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let subtask_id = subtask.id;
+                task.subtasks.insert(pos.min(task.subtasks.len()), subtask);
+                Some(TaskAction::AddSubtask(id, subtask_id))
+            }
+            TaskAction::ToggleSubtask(id, subtask_id) => self
+                .flip_subtask(id, subtask_id)
+                .then_some(TaskAction::ToggleSubtask(id, subtask_id)),
+            TaskAction::UpdateDueDate(id, due_date, has_time) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let old = (task.due_date, task.due_has_time);
+                task.set_due(due_date, has_time);
+                Some(TaskAction::UpdateDueDate(id, old.0, old.1))
+            }
+            TaskAction::UpdatePriority(id, priority) => {
+                let task = self.tasks.iter_mut().find(|t| t.id == id)?;
+                let old_priority = std::mem::replace(&mut task.priority, priority);
+                Some(TaskAction::UpdatePriority(id, old_priority))
+            }
+            TaskAction::TogglePin(id) => self.flip_pin(id).then_some(TaskAction::TogglePin(id)),
+            TaskAction::Batch(actions) => {
+                let inverses = actions
+                    .into_iter()
+                    .rev()
+                    .filter_map(|action| self.revert(action))
+                    .collect();
+                Some(TaskAction::Batch(inverses))
+            }
+        }
+    }
+
     /// Get count of pending tasks
     pub fn pending_count(&self) -> usize {
         self.tasks.iter().filter(|t| t.is_pending()).count()
     }
 
+    /// Estimated minutes of work left across the pending tasks
+    pub fn remaining_effort(&self) -> u32 {
+        self.tasks
+            .iter()
+            .filter(|t| t.is_pending())
+            .filter_map(|t| t.effort)
+            .fold(0, u32::saturating_add)
+    }
+
+    /// Get count of pending tasks past their due date at `now`
+    pub fn overdue_count(&self, now: DateTime<Local>) -> usize {
+        self.tasks
+            .iter()
+            .filter(|t| t.is_pending() && t.is_overdue(now))
+            .count()
+    }
+
+    /// Get pending tasks due on the calendar day of `now`
+    pub fn due_today(&self, now: DateTime<Local>) -> Vec<&Task> {
+        self.tasks
+            .iter()
+            .filter(|t| t.is_pending() && t.is_due_on(now.date_naive()))
+            .collect()
+    }
+
+    /// Get count of pending tasks due on the calendar day of `now`
+    pub fn due_today_count(&self, now: DateTime<Local>) -> usize {
+        self.due_today(now).len()
+    }
+
+    /// Pending tasks whose reminder time has come by `now` and that haven't
+    /// been returned here for it yet
+    /// A task with a `reminder_lead` comes back once ahead of its due date and
+    /// again when it comes due; one already due by the time its early reminder
+    /// is checked only gets the second. Reminder times are worked out afresh
+    /// each call, so a snoozed or edited due date drops the old reminder and
+    /// earns a new one. Each task's `notified_at` is saved, so reminders
+    /// already sent stay sent across restarts.
+    pub fn take_due_reminders(&mut self, now: DateTime<Local>) -> Vec<Task> {
+        let mut due = Vec::new();
+        for task in self.tasks.iter_mut().filter(|t| t.is_pending()) {
+            let moment = if task.is_due_by(now) {
+                task.due_date
+            } else {
+                task.reminder_at().filter(|at| *at <= now)
+            };
+            if moment.is_some() && task.notified_at != moment {
+                task.notified_at = moment;
+                due.push(task.clone());
+            }
+        }
+        if !due.is_empty() {
+            self.save();
+        }
+        due
+    }
+
+    /// Summarize the list as of `now`, including the current completion streak
+    /// Completion dates come from done tasks' `updated_at`.
+    pub fn stats(&self, now: DateTime<Local>) -> TaskStats {
+        let today = now.date_naive();
+        let completion_dates: Vec<_> = self
+            .tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .map(|t| t.updated_at.date_naive())
+            .collect();
+
+        TaskStats {
+            total: self.tasks.len(),
+            pending: self.pending_count(),
+            completed: completion_dates.len(),
+            overdue: self.overdue_count(now),
+            completed_today: completion_dates.iter().filter(|d| **d == today).count(),
+            daily_streak: daily_streak(&completion_dates, today),
+        }
+    }
+
     /// Get count of completed tasks  
     pub fn completed_count(&self) -> usize {
         self.tasks.iter().filter(|t| t.is_done()).count()
     }
 
+    /// How stormy the pending load is, from 0.0 with nothing pending to 1.0
+    /// Each pending task weighs by its priority and twice as much once it's
+    /// overdue; `STORM_FULL_WEIGHT` of it makes a full storm.
+    pub fn storm_intensity(&self, now: DateTime<Local>) -> f32 {
+        let weight: f32 = self
+            .tasks
+            .iter()
+            .filter(|t| t.is_pending())
+            .map(|t| {
+                let weight = match t.priority {
+                    TaskPriority::Low => 0.5,
+                    TaskPriority::Medium => 1.0,
+                    TaskPriority::High => 1.5,
+                    TaskPriority::Urgent => 2.5,
+                };
+                if t.is_overdue(now) {
+                    weight * 2.0
+                } else {
+                    weight
+                }
+            })
+            .sum();
+        (weight / STORM_FULL_WEIGHT).min(1.0)
+    }
+
+    /// Share of tasks overcome, from 0.0 to 1.0; 0.0 for an empty list
+    pub fn completion_ratio(&self) -> f32 {
+        if self.tasks.is_empty() {
+            return 0.0;
+        }
+        self.completed_count() as f32 / self.tasks.len() as f32
+    }
+
     /// Check if all tasks are completed (clear sky!)
     pub fn all_overcome(&self) -> bool {
         !self.tasks.is_empty() && self.tasks.iter().all(|t| t.is_done())
     }
 }
 
+impl Drop for TaskService {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl gpui::EventEmitter<TaskEvent> for TaskService {}
+
+/// Counts for a parent view embedding the list, sent after the first change
+/// or clock tick that alters them; the first one carries the starting counts
+impl gpui::EventEmitter<CountsChanged> for TaskService {}
+
 impl Default for TaskService {
     fn default() -> Self {
         Self::new_with_defaults()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::InMemoryRepository;
+    use std::path::{Path, PathBuf};
+
+    fn service() -> TaskService {
+        TaskService::with_storage(Box::new(InMemoryRepository::default()))
+    }
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("waloyo-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("tasks.json")
+    }
+
+    fn open(path: &Path) -> TaskService {
+        TaskService::with_storage(Box::new(TaskStorage::with_path(path.to_path_buf())))
+    }
+
+    #[test]
+    fn undoing_an_add_removes_the_task_and_redo_brings_it_back() {
+        let mut service = service();
+        let id = service.add_task("Buy milk !h");
+        assert_eq!(service.get_task(id).unwrap().content.as_ref(), "Buy milk");
+        assert_eq!(service.get_task(id).unwrap().priority, TaskPriority::High);
+
+        assert_eq!(
+            service.undo(),
+            Some(UndoOutcome::Removed("Buy milk".into()))
+        );
+        assert!(service.get_task(id).is_none());
+
+        assert_eq!(
+            service.redo(),
+            Some(UndoOutcome::ReAdded("Buy milk".into()))
+        );
+        assert!(service.get_task(id).is_some());
+        assert!(service.undo().is_some());
+        assert_eq!(service.undo(), None);
+    }
+
+    #[test]
+    fn completing_takes_the_animation_and_undo_reopens() {
+        let mut service = service();
+        let id = service.add_task("Water plants");
+
+        assert!(service.begin_completing(id));
+        assert!(service.get_task(id).unwrap().is_completing());
+        assert_eq!(service.pending_count(), 0);
+        assert!(service.finish_completing(id));
+        assert!(service.get_task(id).unwrap().is_done());
+        assert!(!service.finish_completing(id));
+
+        assert_eq!(
+            service.undo(),
+            Some(UndoOutcome::Uncompleted("Water plants".into()))
+        );
+        assert!(service.get_task(id).unwrap().is_pending());
+        assert_eq!(
+            service.redo(),
+            Some(UndoOutcome::Completed("Water plants".into()))
+        );
+        assert!(service.get_task(id).unwrap().is_done());
+    }
+
+    #[test]
+    fn a_completion_interrupted_before_quitting_is_pending_after_reload() {
+        let path = scratch_file("interrupted-completion");
+        let mut service = open(&path);
+        let id = service.add_task("Send invoice");
+        service.begin_completing(id);
+        service.flush();
+        drop(service);
+
+        let reloaded = open(&path);
+        assert!(reloaded.get_task(id).unwrap().is_pending());
+        assert!(reloaded.is_dirty());
+    }
+
+    #[test]
+    fn quitting_mid_animation_finishes_the_completion() {
+        let path = scratch_file("quit-mid-completion");
+        let mut service = open(&path);
+        let id = service.add_task("Send invoice");
+        service.begin_completing(id);
+        assert_eq!(service.finish_all_completing(), 1);
+        service.flush();
+        drop(service);
+
+        assert!(open(&path).get_task(id).unwrap().is_done());
+    }
+
+    #[test]
+    fn new_ids_follow_the_highest_loaded_one() {
+        // Far above any id other tests reach, so the counter only gets past it
+        // if loading bumped it, even with tests running alongside
+        let tasks: Vec<Task> = [1, 2, 100_000].map(|id| task_with_id("Saved", id)).into();
+        let mut service =
+            TaskService::with_storage(Box::new(InMemoryRepository::with_tasks(tasks)));
+        let id = service.add_task("New");
+        assert!(id.0 > 100_000);
+        assert_eq!(service.all_tasks().iter().filter(|t| t.id == id).count(), 1);
+    }
+
+    #[test]
+    fn history_past_the_limit_forgets_the_oldest_actions() {
+        let mut service = service();
+        service.set_history_limit(3);
+        let ids: Vec<TaskId> = (1..=5)
+            .map(|n| service.add_task(format!("Task {}", n)))
+            .collect();
+
+        for id in ids[2..].iter().rev() {
+            assert!(service.undo().is_some());
+            assert!(service.get_task(*id).is_none());
+        }
+        assert_eq!(service.undo(), None);
+        assert!(service.get_task(ids[0]).is_some());
+        assert!(service.get_task(ids[1]).is_some());
+    }
+
+    #[test]
+    fn lowering_the_limit_trims_history_already_recorded() {
+        let mut service = service();
+        let first = service.add_task("First");
+        let second = service.add_task("Second");
+        service.set_history_limit(1);
+
+        assert!(service.undo().is_some());
+        assert!(service.get_task(second).is_none());
+        assert_eq!(service.undo(), None);
+        assert!(service.get_task(first).is_some());
+    }
+
+    #[test]
+    fn counts_are_reported_when_edits_or_the_clock_change_them() {
+        let mut service = service();
+        let now = Local::now();
+        let first = service.take_counts_change(now).unwrap();
+        assert_eq!(first.pending, 0);
+        assert_eq!(service.take_counts_change(now), None);
+
+        let id = service.add_task("Pay rent");
+        service.update_task_due_date(id, Some(now + chrono::Duration::hours(1)), true);
+        let added = service.take_counts_change(now).unwrap();
+        assert_eq!((added.pending, added.overdue), (1, 0));
+
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(service.take_counts_change(later).unwrap().overdue, 1);
+        assert_eq!(service.take_counts_change(later), None);
+    }
+
+    #[test]
+    fn search_finds_only_matching_pending_tasks() {
+        let mut service = service();
+        service.add_task("Build log tool");
+        service.add_task("Water plants");
+        let done = service.add_task("Blot the ink");
+        service.begin_completing(done);
+        service.finish_completing(done);
+
+        let contents = |tasks: Vec<&Task>| -> Vec<String> {
+            tasks.iter().map(|t| t.content.to_string()).collect()
+        };
+        assert_eq!(contents(service.search("blt")), ["Build log tool"]);
+        assert_eq!(
+            contents(service.search("")),
+            ["Build log tool", "Water plants"]
+        );
+        assert!(service.search("xyz").is_empty());
+    }
+
+    #[test]
+    fn a_deferred_due_date_can_be_undone() {
+        let mut service = service();
+        let id = service.add_task("Renew passport");
+        let tomorrow = Local::now() + chrono::Duration::days(1);
+        assert!(service.update_task_due_date(id, Some(tomorrow), false));
+
+        assert_eq!(
+            service.undo(),
+            Some(UndoOutcome::Changed("Renew passport".into()))
+        );
+        assert_eq!(service.get_task(id).unwrap().due_date, None);
+        assert!(service.redo().is_some());
+        assert_eq!(service.get_task(id).unwrap().due_date, Some(tomorrow));
+    }
+
+    #[test]
+    fn reminders_already_sent_stay_sent_after_a_restart() {
+        let path = scratch_file("reminders-sent");
+        let now = Local::now();
+        let mut service = open(&path);
+        let id = service.add_task("Take out the bins");
+        service.update_task_due_date(id, Some(now - chrono::Duration::minutes(5)), true);
+        assert_eq!(service.take_due_reminders(now).len(), 1);
+        assert!(service.take_due_reminders(now).is_empty());
+        service.flush();
+        drop(service);
+
+        let mut reloaded = open(&path);
+        assert!(reloaded.take_due_reminders(now).is_empty());
+        reloaded.snooze_task(id, chrono::Duration::hours(1));
+        let later = now + chrono::Duration::hours(2);
+        assert_eq!(reloaded.take_due_reminders(later).len(), 1);
+    }
+
+    #[test]
+    fn compacting_puts_pending_first_and_keeps_each_group_in_order() {
+        let mut tasks: Vec<Task> = ["a", "b", "c", "d", "e"].map(Task::new).into();
+        tasks[0].complete();
+        tasks[2].complete();
+        let mut service =
+            TaskService::with_storage(Box::new(InMemoryRepository::with_tasks(tasks)));
+
+        let order: Vec<_> = service
+            .all_tasks()
+            .iter()
+            .map(|t| t.content.to_string())
+            .collect();
+        assert_eq!(order, ["b", "d", "e", "a", "c"]);
+        assert!(!service.compact());
+    }
+
+    fn task_with_id(content: &str, id: u64) -> Task {
+        let mut task = Task::new(content.to_string());
+        task.id = TaskId(id);
+        task
+    }
+
+    #[test]
+    fn repair_gives_duplicate_ids_fresh_ones_above_the_highest() {
+        let mut service = service();
+        service.tasks = vec![
+            task_with_id("First", 3),
+            task_with_id("Copy", 3),
+            task_with_id("Other", 7),
+        ];
+
+        let repairs = service.validate_and_repair();
+        let ids: Vec<u64> = service.tasks.iter().map(|t| t.id.0).collect();
+        assert_eq!(ids[0], 3);
+        assert!(ids[1] > 7);
+        assert_eq!(ids[2], 7);
+        assert_eq!(
+            repairs,
+            [format!(
+                "'Copy' shared id 3 with another task; it now has id {}",
+                ids[1]
+            )]
+        );
+        assert!(service.is_dirty());
+    }
+
+    #[test]
+    fn repair_puts_tasks_left_completing_back_to_pending() {
+        let mut service = service();
+        let mut stuck = task_with_id("Stuck", 1);
+        stuck.begin_completing();
+        service.tasks = vec![stuck, task_with_id("Fine", 2)];
+
+        let repairs = service.validate_and_repair();
+        assert!(service.tasks.iter().all(|t| t.is_pending()));
+        assert_eq!(
+            repairs,
+            ["'Stuck' was left mid-completion; it's pending again"]
+        );
+    }
+
+    #[test]
+    fn a_clean_list_needs_no_repair() {
+        let mut service = service();
+        service.tasks = vec![task_with_id("One", 1), task_with_id("Two", 2)];
+        assert!(service.validate_and_repair().is_empty());
+        assert!(!service.is_dirty());
+    }
+
+    #[test]
+    fn demo_tasks_are_only_for_storage_never_saved_to() {
+        let first_run = TaskService::with_storage(Box::new(InMemoryRepository::fresh()));
+        assert!(first_run.should_seed_demo());
+        let saved_empty = TaskService::with_storage(Box::new(InMemoryRepository::default()));
+        assert!(!saved_empty.should_seed_demo());
+        let saved_tasks = TaskService::with_storage(Box::new(InMemoryRepository::with_tasks(
+            vec![Task::new("Kept")],
+        )));
+        assert!(!saved_tasks.should_seed_demo());
+    }
+
+    #[test]
+    fn an_existing_empty_task_file_gets_no_demo_tasks() {
+        let path = scratch_file("demo-seeding");
+        assert!(open(&path).should_seed_demo());
+
+        TaskStorage::with_path(path.clone())
+            .save(&[], &Preferences::default())
+            .unwrap();
+        assert!(!open(&path).should_seed_demo());
+    }
+
+    #[test]
+    fn changes_reach_storage_on_flush() {
+        let mut service = service();
+        let id = service.add_task("Call the bank");
+        assert!(service.is_dirty());
+        assert!(service.storage.load().unwrap().0.is_empty());
+
+        service.flush();
+        assert!(!service.is_dirty());
+        let (saved, _) = service.storage.load().unwrap();
+        assert_eq!(saved.iter().map(|t| t.id).collect::<Vec<_>>(), vec![id]);
+    }
+}
@@ -0,0 +1,159 @@
+// Command-line modes that run without opening a window
+//
+// They go through the same parser and TaskService as the app, so a task added
+// here is in the list the next time the window opens.
+
+use crate::application::TaskService;
+use crate::domain::{
+    humanize_due, parse_task_input, ParsedTask, Recurrence, Task, TaskId, TaskPriority,
+};
+
+const USAGE: &str = "Usage:
+  waloyo                     open the app
+  waloyo add \"<text>\"        add a task, quick-add syntax included
+  waloyo list                list pending tasks, then overcome ones
+  waloyo done <id>           complete a task
+  waloyo rm <id>             delete a task
+  waloyo --parse \"<text>\"    print what quick-add makes of the text, as JSON";
+
+/// Exit code for an unknown task id or a task that can't be changed
+const EXIT_FAILURE: i32 = 1;
+/// Exit code for arguments that don't make a command
+const EXIT_USAGE: i32 = 2;
+
+/// Run the command in `args`, returning the process exit code
+/// None means there is no command and the app should open as usual.
+pub fn run(args: &[String]) -> Option<i32> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let code = match args.as_slice() {
+        [] => return None,
+        ["--parse", text] => {
+            let parsed = parse_task_input(text, chrono::Local::now());
+            println!("{}", parsed_task_json(&parsed));
+            0
+        }
+        ["add", text] => add(text),
+        ["list"] => list(),
+        ["done", id] => with_task_id(id, done),
+        ["rm", id] => with_task_id(id, remove),
+        ["help" | "--help" | "-h"] => {
+            println!("{}", USAGE);
+            0
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            EXIT_USAGE
+        }
+    };
+    Some(code)
+}
+
+/// What `--parse` prints for quick-add input, with null for anything not given
+fn parsed_task_json(parsed: &ParsedTask) -> serde_json::Value {
+    serde_json::json!({
+        "content": parsed.content,
+        "priority": parsed.priority.map(priority_name),
+        "due": parsed.due_date,
+        "recurrence": parsed.recurrence.map(|recurrence| match recurrence {
+            Recurrence::Daily => "daily",
+            Recurrence::Weekly => "weekly",
+            Recurrence::Monthly => "monthly",
+        }),
+        "tags": parsed.tags.iter().map(|t| t.to_string()).collect::<Vec<_>>(),
+        "effort": parsed.effort,
+        "reminder_lead": parsed.reminder_lead,
+    })
+}
+
+fn priority_name(priority: TaskPriority) -> &'static str {
+    match priority {
+        TaskPriority::Low => "low",
+        TaskPriority::Medium => "medium",
+        TaskPriority::High => "high",
+        TaskPriority::Urgent => "urgent",
+    }
+}
+
+/// One line of `list`: id, checkbox, content and whatever metadata is set
+fn task_line(task: &Task) -> String {
+    let mut line = format!(
+        "{:>4}  [{}] {}",
+        task.id.0,
+        if task.is_done() { "x" } else { " " },
+        task.content.replace('\n', " / ")
+    );
+    if task.priority != TaskPriority::Low {
+        line.push_str(&format!("  !{}", priority_name(task.priority)));
+    }
+    if let Some(due) = task.due_date.filter(|_| !task.is_done()) {
+        line.push_str(&format!(
+            "  @{}",
+            humanize_due(due, task.has_due_time(), chrono::Local::now()).0
+        ));
+    }
+    for tag in &task.tags {
+        line.push_str(&format!("  #{}", tag));
+    }
+    line
+}
+
+fn with_task_id(arg: &str, command: fn(&mut TaskService, TaskId) -> i32) -> i32 {
+    let Ok(id) = arg.parse::<u64>() else {
+        eprintln!("Not a task id: {}", arg);
+        return EXIT_USAGE;
+    };
+    let mut service = TaskService::new();
+    if service.get_task(TaskId(id)).is_none() {
+        eprintln!("No task with id {}", id);
+        return EXIT_FAILURE;
+    }
+    let code = command(&mut service, TaskId(id));
+    service.flush();
+    code
+}
+
+fn add(text: &str) -> i32 {
+    if text.trim().is_empty() {
+        eprintln!("Nothing to add");
+        return EXIT_USAGE;
+    }
+    let mut service = TaskService::new();
+    let id = service.add_task(text.to_string());
+    service.flush();
+    if let Some(task) = service.get_task(id) {
+        println!("{}", task_line(task));
+    }
+    0
+}
+
+fn list() -> i32 {
+    let service = TaskService::new();
+    let now = chrono::Local::now();
+    for task in service.sorted_pending(service.sort_mode(), now) {
+        println!("{}", task_line(task));
+    }
+    let newest_first = !service.completed_oldest_first();
+    for task in service.completed_sorted(newest_first) {
+        println!("{}", task_line(task));
+    }
+    0
+}
+
+fn done(service: &mut TaskService, id: TaskId) -> i32 {
+    if service.complete_many(&[id]) == 0 {
+        eprintln!("Task {} is already overcome", id.0);
+        return EXIT_FAILURE;
+    }
+    println!("Overcame task {}", id.0);
+    0
+}
+
+fn remove(service: &mut TaskService, id: TaskId) -> i32 {
+    match service.remove_task(id) {
+        Some(task) => {
+            println!("Deleted task {}: {}", id.0, task.content);
+            0
+        }
+        None => EXIT_FAILURE,
+    }
+}
@@ -0,0 +1,104 @@
+use std::ops::Range;
+
+/// Points for each query character found
+const MATCH_SCORE: i32 = 1;
+/// Extra points when a match directly follows the previous one
+const CONSECUTIVE_BONUS: i32 = 5;
+/// Extra points when a match starts a word
+const WORD_START_BONUS: i32 = 8;
+/// Points lost for each character skipped between matches
+const GAP_PENALTY: i32 = 1;
+
+/// Score how well `query` matches `text` as a subsequence, ignoring case and
+/// spaces in the query, so "blt" finds "build log tool"
+/// Consecutive and word-start matches score higher. An empty query matches
+/// everything with a score of 0; None means some query character wasn't found.
+/// Matching is greedy: each query character takes its first occurrence after
+/// the previous one, so "blt" takes the `l` in "build" rather than the one
+/// starting "log". That keeps matching to one pass over the text, at the cost
+/// of sometimes scoring below the best alignment; the score only ranks results.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    fuzzy_match(query, text).map(|(score, _)| score)
+}
+
+/// Byte ranges of `text` that `query` matched, for highlighting
+pub fn fuzzy_match_ranges(query: &str, text: &str) -> Option<Vec<Range<usize>>> {
+    fuzzy_match(query, text).map(|(_, ranges)| ranges)
+}
+
+fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<Range<usize>>)> {
+    let mut wanted = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut previous: Option<char> = None;
+
+    for (index, c) in text.char_indices() {
+        let Some(&next) = wanted.peek() else {
+            break;
+        };
+        if c.to_lowercase().next() == Some(next) {
+            score += MATCH_SCORE;
+            match ranges.last_mut() {
+                Some(last) if last.end == index => {
+                    score += CONSECUTIVE_BONUS;
+                    last.end = index + c.len_utf8();
+                }
+                _ => ranges.push(index..index + c.len_utf8()),
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += WORD_START_BONUS;
+            }
+            wanted.next();
+        } else if !ranges.is_empty() {
+            score -= GAP_PENALTY;
+        }
+        previous = Some(c);
+    }
+
+    wanted.peek().is_none().then_some((score, ranges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("  ", ""), Some(0));
+    }
+
+    #[test]
+    fn missing_characters_mean_no_match() {
+        assert_eq!(fuzzy_score("xyz", "build log tool"), None);
+        assert_eq!(fuzzy_score("tlb", "build log tool"), None);
+    }
+
+    #[test]
+    fn initials_match_as_a_subsequence() {
+        assert!(fuzzy_score("blt", "build log tool").is_some());
+        assert_eq!(
+            fuzzy_score("B LT", "build log tool"),
+            fuzzy_score("blt", "build log tool")
+        );
+    }
+
+    #[test]
+    fn matching_takes_the_leftmost_occurrence() {
+        assert_eq!(
+            fuzzy_match_ranges("blt", "build log tool"),
+            Some(vec![0..1, 3..4, 10..11])
+        );
+    }
+
+    #[test]
+    fn word_starts_and_runs_rank_higher() {
+        let run = fuzzy_score("log", "log book").unwrap();
+        let scattered = fuzzy_score("log", "a long gap").unwrap();
+        assert!(run > scattered);
+    }
+}
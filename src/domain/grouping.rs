@@ -0,0 +1,235 @@
+use super::{Task, TaskPriority};
+use chrono::{DateTime, Local};
+use gpui::SharedString;
+
+/// How the pending list is split into sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupMode {
+    /// One unbroken list
+    #[default]
+    None,
+    /// A section per tag, plus "Untagged"
+    ByTag,
+    /// A section per priority, storms first
+    ByPriority,
+    /// Overdue, Today, Tomorrow, This week, Later and No date
+    ByDueDate,
+}
+
+impl GroupMode {
+    /// The next mode when cycling through them
+    pub fn next(self) -> Self {
+        match self {
+            GroupMode::None => GroupMode::ByTag,
+            GroupMode::ByTag => GroupMode::ByPriority,
+            GroupMode::ByPriority => GroupMode::ByDueDate,
+            GroupMode::ByDueDate => GroupMode::None,
+        }
+    }
+}
+
+/// Header of the section for tasks without tags
+pub const UNTAGGED: &str = "Untagged";
+
+/// Split `tasks` into headed sections, keeping their order within each section
+/// Empty sections are left out. With `GroupMode::None` everything lands in one
+/// section with an empty header. By tag, a task with several tags appears under
+/// each of them, and tags differing only in case share a section.
+pub fn group_tasks<'a>(
+    tasks: Vec<&'a Task>,
+    mode: GroupMode,
+    now: DateTime<Local>,
+) -> Vec<(SharedString, Vec<&'a Task>)> {
+    let mut groups: Vec<(SharedString, Vec<&'a Task>)> = match mode {
+        GroupMode::None => return vec![(SharedString::default(), tasks)],
+        GroupMode::ByTag => {
+            let mut tags: Vec<SharedString> =
+                tasks.iter().flat_map(|t| t.tags.iter().cloned()).collect();
+            tags.sort_by_key(|tag| tag.to_lowercase());
+            tags.dedup_by_key(|tag| tag.to_lowercase());
+            let mut groups: Vec<_> = tags
+                .into_iter()
+                .map(|tag| {
+                    let key = tag.to_lowercase();
+                    let members = tasks
+                        .iter()
+                        .copied()
+                        .filter(|t| t.tags.iter().any(|own| own.to_lowercase() == key))
+                        .collect();
+                    (SharedString::from(format!("#{}", tag)), members)
+                })
+                .collect();
+            groups.push((
+                UNTAGGED.into(),
+                tasks
+                    .iter()
+                    .copied()
+                    .filter(|t| t.tags.is_empty())
+                    .collect(),
+            ));
+            groups
+        }
+        GroupMode::ByPriority => [
+            (TaskPriority::Urgent, "Urgent"),
+            (TaskPriority::High, "High"),
+            (TaskPriority::Medium, "Medium"),
+            (TaskPriority::Low, "Low"),
+        ]
+        .into_iter()
+        .map(|(priority, header)| {
+            let members = tasks
+                .iter()
+                .copied()
+                .filter(|t| t.priority == priority)
+                .collect();
+            (header.into(), members)
+        })
+        .collect(),
+        GroupMode::ByDueDate => {
+            const HEADERS: [&str; 6] = [
+                "Overdue",
+                "Today",
+                "Tomorrow",
+                "This week",
+                "Later",
+                "No date",
+            ];
+            let today = now.date_naive();
+            let mut groups: Vec<_> = HEADERS
+                .iter()
+                .map(|header| (SharedString::from(*header), Vec::new()))
+                .collect();
+            for task in tasks {
+                let bucket = match task.due_date {
+                    None => 5,
+                    Some(_) if task.is_overdue(now) => 0,
+                    Some(due) => match (due.date_naive() - today).num_days() {
+                        ..=0 => 1,
+                        1 => 2,
+                        2..=6 => 3,
+                        _ => 4,
+                    },
+                };
+                groups[bucket].1.push(task);
+            }
+            groups
+        }
+    };
+    groups.retain(|(_, members)| !members.is_empty());
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap()
+    }
+
+    /// Friday 14 March 2025, mid-morning
+    fn now() -> DateTime<Local> {
+        at(14, 10)
+    }
+
+    fn tagged(content: &str, tags: &[&str]) -> Task {
+        let mut task = Task::new(content.to_string());
+        task.tags = tags
+            .iter()
+            .map(|tag| SharedString::from(tag.to_string()))
+            .collect();
+        task
+    }
+
+    fn due(content: &str, due_date: DateTime<Local>, has_time: bool) -> Task {
+        let mut task = Task::new(content.to_string());
+        task.set_due(Some(due_date), has_time);
+        task
+    }
+
+    /// Section headers with the contents of their tasks
+    fn outline(groups: Vec<(SharedString, Vec<&Task>)>) -> Vec<(String, Vec<String>)> {
+        groups
+            .into_iter()
+            .map(|(header, members)| {
+                let contents = members.iter().map(|t| t.content.to_string()).collect();
+                (header.to_string(), contents)
+            })
+            .collect()
+    }
+
+    fn section(header: &str, contents: &[&str]) -> (String, Vec<String>) {
+        let contents = contents.iter().map(|c| c.to_string()).collect();
+        (header.to_string(), contents)
+    }
+
+    #[test]
+    fn tasks_appear_under_each_of_their_tags() {
+        let tasks = [
+            tagged("Write report", &["work", "writing"]),
+            tagged("Journal", &["writing"]),
+            tagged("Water plants", &[]),
+        ];
+        let groups = group_tasks(tasks.iter().collect(), GroupMode::ByTag, now());
+        assert_eq!(
+            outline(groups),
+            vec![
+                section("#work", &["Write report"]),
+                section("#writing", &["Write report", "Journal"]),
+                section(UNTAGGED, &["Water plants"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn untagged_section_is_left_out_when_every_task_has_a_tag() {
+        let tasks = [tagged("Write report", &["work"])];
+        let groups = group_tasks(tasks.iter().collect(), GroupMode::ByTag, now());
+        assert_eq!(outline(groups), vec![section("#work", &["Write report"])]);
+    }
+
+    #[test]
+    fn tags_differing_only_in_case_share_a_section() {
+        let tasks = [
+            tagged("Write report", &["Work"]),
+            tagged("Send invoice", &["work"]),
+            tagged("Book flights", &["Work"]),
+        ];
+        let groups = group_tasks(tasks.iter().collect(), GroupMode::ByTag, now());
+        assert_eq!(
+            outline(groups),
+            vec![section(
+                "#Work",
+                &["Write report", "Send invoice", "Book flights"]
+            )]
+        );
+    }
+
+    #[test]
+    fn due_dates_fall_into_buckets_counted_from_today() {
+        let tasks = [
+            due("Yesterday", at(13, 0), false),
+            due("This morning", at(14, 9), true),
+            due("Today", at(14, 0), false),
+            due("Tonight", at(14, 20), true),
+            due("Tomorrow", at(15, 0), false),
+            due("Sunday", at(16, 0), false),
+            due("Next Thursday", at(20, 0), false),
+            due("Next Friday", at(21, 0), false),
+            Task::new("Someday".to_string()),
+        ];
+        let groups = group_tasks(tasks.iter().collect(), GroupMode::ByDueDate, now());
+        assert_eq!(
+            outline(groups),
+            vec![
+                section("Overdue", &["Yesterday", "This morning"]),
+                section("Today", &["Today", "Tonight"]),
+                section("Tomorrow", &["Tomorrow"]),
+                section("This week", &["Sunday", "Next Thursday"]),
+                section("Later", &["Next Friday"]),
+                section("No date", &["Someday"]),
+            ]
+        );
+    }
+}
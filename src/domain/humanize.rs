@@ -0,0 +1,124 @@
+use chrono::{DateTime, Local};
+
+/// Write an effort in minutes as `45m`, `2h` or `1h 30m`
+pub fn format_effort(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// Describe how long ago `moment` was, e.g. "just now", "40s ago", "5m ago",
+/// "2h ago" or "3d ago"
+/// Moments after `now`, from a clock that moved back, count as just now.
+pub fn humanize_relative(moment: DateTime<Local>, now: DateTime<Local>) -> String {
+    let elapsed = now.signed_duration_since(moment);
+    if elapsed.num_days() > 0 {
+        format!("{}d ago", elapsed.num_days())
+    } else if elapsed.num_hours() > 0 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_minutes() > 0 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_seconds() >= 10 {
+        format!("{}s ago", elapsed.num_seconds())
+    } else {
+        "just now".to_string()
+    }
+}
+
+/// Describe a due moment relative to `now`, e.g. "Tomorrow 5:00 PM" or "in 3 days"
+/// Returns the label and whether the moment has passed. Due dates without a time
+/// of day only count as overdue once their day is over, matching `Task::is_overdue`.
+pub fn humanize_due(due: DateTime<Local>, has_time: bool, now: DateTime<Local>) -> (String, bool) {
+    let days = (due.date_naive() - now.date_naive()).num_days();
+
+    let day = match days {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        -1 => "Yesterday".to_string(),
+        2..=6 => format!("in {} days", days),
+        -6..=-2 => format!("{} days ago", -days),
+        -13..=-7 => "last week".to_string(),
+        _ => due.format("%m/%d").to_string(),
+    };
+    let label = if has_time {
+        format!("{} {}", day, due.format("%-I:%M %p"))
+    } else {
+        day
+    };
+
+    let is_overdue = if has_time { due < now } else { days < 0 };
+    (label, is_overdue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    /// Friday 14 March 2025, mid-morning
+    fn now() -> DateTime<Local> {
+        at(14, 10, 0)
+    }
+
+    #[test]
+    fn effort_is_written_in_the_largest_units() {
+        assert_eq!(format_effort(45), "45m");
+        assert_eq!(format_effort(120), "2h");
+        assert_eq!(format_effort(90), "1h 30m");
+    }
+
+    #[test]
+    fn time_since_is_given_in_the_largest_whole_unit() {
+        let ago =
+            |seconds: i64| humanize_relative(now() - chrono::Duration::seconds(seconds), now());
+        assert_eq!(ago(3), "just now");
+        assert_eq!(ago(40), "40s ago");
+        assert_eq!(ago(5 * 60 + 59), "5m ago");
+        assert_eq!(ago(2 * 3600 + 10), "2h ago");
+        assert_eq!(ago(3 * 86400 + 3600), "3d ago");
+    }
+
+    #[test]
+    fn moments_in_the_future_are_just_now() {
+        let later = now() + chrono::Duration::minutes(5);
+        assert_eq!(humanize_relative(later, now()), "just now");
+    }
+
+    #[test]
+    fn due_days_are_named_relative_to_today() {
+        assert_eq!(humanize_due(at(13, 0, 0), false, now()).0, "Yesterday");
+        assert_eq!(humanize_due(at(15, 0, 0), false, now()).0, "Tomorrow");
+        assert_eq!(humanize_due(at(17, 0, 0), false, now()).0, "in 3 days");
+        assert_eq!(humanize_due(at(11, 0, 0), false, now()).0, "3 days ago");
+        assert_eq!(humanize_due(at(5, 0, 0), false, now()).0, "last week");
+        assert_eq!(humanize_due(at(31, 0, 0), false, now()).0, "03/31");
+    }
+
+    #[test]
+    fn only_timed_due_dates_are_overdue_during_their_day() {
+        assert_eq!(
+            humanize_due(at(14, 9, 0), true, now()),
+            ("Today 9:00 AM".to_string(), true)
+        );
+        assert_eq!(
+            humanize_due(at(14, 0, 0), false, now()),
+            ("Today".to_string(), false)
+        );
+        assert_eq!(
+            humanize_due(at(14, 17, 30), true, now()),
+            ("Today 5:30 PM".to_string(), false)
+        );
+        assert_eq!(
+            humanize_due(at(13, 0, 0), false, now()),
+            ("Yesterday".to_string(), true)
+        );
+    }
+}
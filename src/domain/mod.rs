@@ -2,10 +2,112 @@
 // This layer contains the heart of the Waloyo application:
 // pure business rules with no dependencies on UI or infrastructure.
 
+mod fuzzy;
+mod grouping;
+mod humanize;
+mod parser;
+mod preferences;
+mod repository;
+mod review;
+mod stats;
 mod task;
 
+pub use fuzzy::*;
+pub use grouping::*;
+pub use humanize::*;
+pub use parser::*;
+pub use preferences::*;
+pub use repository::*;
+pub use review::*;
+pub use stats::*;
 pub use task::*;
 
 /// Event emitted when a new task is submitted
 #[derive(Clone)]
 pub struct TaskSubmitted(pub String);
+
+/// Decision made for a task while processing the inbox one task at a time
+#[derive(Clone)]
+pub enum ProcessingEvent {
+    /// Complete the task now
+    Do(TaskId),
+    /// Push the task's due date back
+    Defer(TaskId),
+    /// Hand the task off, leaving a note behind
+    Delegate(TaskId),
+    /// Remove the task entirely
+    Delete(TaskId),
+    /// Leave processing mode
+    Exit,
+}
+
+/// Action taken on a task from the agenda view
+#[derive(Clone)]
+pub enum AgendaEvent {
+    /// Complete the task
+    Complete(TaskId),
+}
+
+/// A task dragged between the panes of the day plan
+#[derive(Clone)]
+pub enum PlanEvent {
+    /// Make the task due today
+    Schedule(TaskId),
+    /// Clear the task's due date, sending it back to the backlog
+    Unschedule(TaskId),
+}
+
+/// Choice made in the due date picker
+#[derive(Clone)]
+pub enum DatePickerEvent {
+    /// Set the due date, with whether it has a time of day, or clear it with None
+    Picked(Option<chrono::DateTime<chrono::Local>>, bool),
+    /// Close the picker without changing anything
+    Dismissed,
+}
+
+/// Change asked for from the settings view
+#[derive(Clone)]
+pub enum SettingsEvent {
+    SetTheme(ThemeVariant),
+    SetSortMode(SortMode),
+    SetGroupMode(GroupMode),
+    SetReducedMotion(bool),
+    SetFocusMode(bool),
+    SetAnimationSpeed(AnimationSpeed),
+    SetNotifications(bool),
+    /// Go back to the task list
+    Exit,
+}
+
+/// The app settings changed and should be saved
+#[derive(Clone)]
+pub struct SettingsChanged(pub Settings);
+
+/// Something that happened to the task list, for views to react to
+/// without hooking every call that could cause it
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskEvent {
+    /// A task was overcome
+    Completed(TaskId),
+    /// A task was removed from the list
+    Deleted(TaskId),
+    /// The last pending task was overcome and the sky cleared
+    AllOvercome,
+    /// The number of pending tasks changed, to the count given
+    PendingChanged(usize),
+}
+
+/// How many tasks are pending, done and overdue, sent whenever any of them changes
+/// Lets a view embedding the task list keep its own badges or summaries up to date.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(dead_code)]
+pub struct CountsChanged {
+    pub pending: usize,
+    pub completed: usize,
+    pub overdue: usize,
+}
+
+/// Event emitted when the search query changes
+#[derive(Clone)]
+pub struct SearchQueryChanged(pub String);
@@ -0,0 +1,414 @@
+use super::{Recurrence, Task, TaskPatch, TaskPriority};
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
+use gpui::SharedString;
+
+/// Parse a `#tag` token, returning the tag name without the leading `#`
+fn parse_tag(word: &str) -> Option<SharedString> {
+    let tag = word.strip_prefix('#')?;
+    let is_valid = !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_valid.then(|| SharedString::from(tag.to_string()))
+}
+
+/// Parse a recurrence token: `~daily`, `~weekly` or `~monthly`
+fn parse_recurrence(word: &str) -> Option<Recurrence> {
+    match word.strip_prefix('~')?.to_lowercase().as_str() {
+        "daily" => Some(Recurrence::Daily),
+        "weekly" => Some(Recurrence::Weekly),
+        "monthly" => Some(Recurrence::Monthly),
+        _ => None,
+    }
+}
+
+/// Parse an effort token into minutes: `~30m`, `~2h`, `~1h30m`, or `=45` for
+/// a plain number of minutes
+fn parse_effort(word: &str) -> Option<u32> {
+    if let Some(minutes) = word.strip_prefix('=') {
+        return minutes.parse().ok().filter(|m| *m > 0);
+    }
+    parse_minutes(&word.strip_prefix('~')?.to_lowercase())
+}
+
+/// Parse a reminder token into minutes of lead time: `!remind1h`,
+/// `!remind30m` or `!remind1h30m`
+fn parse_reminder(word: &str) -> Option<u32> {
+    parse_minutes(word.to_lowercase().strip_prefix("!remind")?)
+}
+
+/// Parse a span like `30m`, `2h` or `1h30m` into minutes, which must be some
+fn parse_minutes(span: &str) -> Option<u32> {
+    let (hours, rest) = match span.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<u32>().ok()?, rest),
+        None => (0, span),
+    };
+    let minutes = match rest {
+        "" => 0,
+        rest => rest.strip_suffix('m')?.parse::<u32>().ok()?,
+    };
+    hours
+        .checked_mul(60)?
+        .checked_add(minutes)
+        .filter(|total| *total > 0)
+}
+
+/// Resolve a calendar date and time of day to a local timestamp
+/// Falls back to the later instant when the time is skipped by a DST change.
+pub fn local_datetime(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    let naive = date.and_time(time);
+    naive.and_local_timezone(Local).earliest().or_else(|| {
+        (naive + chrono::Duration::hours(1))
+            .and_local_timezone(Local)
+            .earliest()
+    })
+}
+
+/// Parse a due date token: `@today`, `@tomorrow`, `@2025-03-14`, `@friday` or `@in3days`.
+///
+/// Weekday names resolve to the next occurrence strictly after today, so `@friday`
+/// typed on a Friday means a week from now (`@today` covers the same day).
+/// The due date is at midnight of the day; a time, if any, is set by the caller.
+/// Returns None for unrecognized or malformed tokens.
+pub fn parse_due_date(token: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    local_datetime(parse_due_day(token, now.date_naive())?, NaiveTime::MIN)
+}
+
+/// The calendar day of a due date token, see `parse_due_date`
+fn parse_due_day(token: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let word = token.strip_prefix('@')?.to_lowercase();
+    let in_days = |days: u64| today.checked_add_days(chrono::Days::new(days));
+
+    match word.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return in_days(1),
+        _ => {}
+    }
+
+    if let Some(count) = word.strip_prefix("in").and_then(|rest| {
+        rest.strip_suffix("days")
+            .or_else(|| rest.strip_suffix("day"))
+    }) {
+        return in_days(count.parse().ok()?);
+    }
+
+    if let Ok(weekday) = word.parse::<chrono::Weekday>() {
+        let target = weekday.num_days_from_monday() as i64;
+        let current = today.weekday().num_days_from_monday() as i64;
+        return in_days(((target - current + 6).rem_euclid(7) + 1) as u64);
+    }
+
+    NaiveDate::parse_from_str(&word, "%Y-%m-%d").ok()
+}
+
+/// Parse a time of day following a due date: `17:00`, `9am`, `5:30pm`
+fn parse_due_time(word: &str) -> Option<NaiveTime> {
+    let word = word.to_lowercase();
+    let (clock, meridiem) = if let Some(rest) = word.strip_suffix("am") {
+        (rest, Some(false))
+    } else if let Some(rest) = word.strip_suffix("pm") {
+        (rest, Some(true))
+    } else {
+        (word.as_str(), None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => {
+            (hour.parse::<u32>().ok()?, minute.parse().ok()?)
+        }
+        Some(_) => return None,
+        // A bare number is only a time with am/pm, so `@today 3` stays in the content
+        None if meridiem.is_some() => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+
+    let hour = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// Rebuild multi-line content one line at a time, dropping lines left empty
+/// Words within a line are rejoined with single spaces; line breaks are kept.
+fn map_lines(content: &str, mut f: impl FnMut(&str) -> String) -> String {
+    content
+        .lines()
+        .map(f)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What quick-add input turns into, before it becomes a task
+/// Each field is only set when its token appears in the input.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedTask {
+    /// The input with every recognized token removed
+    pub content: String,
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<DateTime<Local>>,
+    /// Whether a time of day followed the due date
+    pub due_has_time: bool,
+    pub recurrence: Option<Recurrence>,
+    pub tags: Vec<SharedString>,
+    /// Estimated minutes of work
+    pub effort: Option<u32>,
+    /// Minutes ahead of the due date to be reminded
+    pub reminder_lead: Option<u32>,
+}
+
+/// Pull the inline metadata out of quick-add input:
+/// `!u`/`!h`/`!m`/`!l` priority, `@...` due date, `~...` recurrence, `~30m`/`=45`
+/// effort, `!remind1h` reminder lead time and `#tag` tags.
+/// Tokens are found on any line of multi-line input; the line breaks stay
+/// in the content.
+pub fn parse_task_input(input: &str, now: DateTime<Local>) -> ParsedTask {
+    let mut priority = None;
+
+    // Parsing for reminders: !remind1h, !remind30m, !remind1h30m
+    // They come out first, so the priority check below never sees them
+    let mut reminder_lead = None;
+    let mut cleaned_content = map_lines(input, |line| {
+        line.split_whitespace()
+            .filter(|word| {
+                if reminder_lead.is_none() {
+                    reminder_lead = parse_reminder(word);
+                    return reminder_lead.is_none();
+                }
+                true
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    // Simple parsing for priority: !u, !h, !m, !l
+    if cleaned_content.contains("!u") {
+        priority = Some(TaskPriority::Urgent);
+        cleaned_content = cleaned_content.replace("!u", "").trim().to_string();
+    } else if cleaned_content.contains("!h") {
+        priority = Some(TaskPriority::High);
+        cleaned_content = cleaned_content.replace("!h", "").trim().to_string();
+    } else if cleaned_content.contains("!m") {
+        priority = Some(TaskPriority::Medium);
+        cleaned_content = cleaned_content.replace("!m", "").trim().to_string();
+    } else if cleaned_content.contains("!l") {
+        priority = Some(TaskPriority::Low);
+        cleaned_content = cleaned_content.replace("!l", "").trim().to_string();
+    }
+
+    // Parsing for due date: @today, @tomorrow, @2025-03-14, @friday, @in3days,
+    // optionally followed by a time of day: @today 17:00, @tomorrow 9am
+    // Date-only due dates are stored at midnight.
+    let mut due_date = None;
+    let mut due_has_time = false;
+    cleaned_content = map_lines(&cleaned_content, |line| {
+        let mut words = line.split_whitespace().peekable();
+        let mut kept = Vec::new();
+        while let Some(word) = words.next() {
+            if due_date.is_none() {
+                if let Some(due) = parse_due_date(word, now) {
+                    due_date = match words.peek().and_then(|next| parse_due_time(next)) {
+                        Some(time) => {
+                            words.next();
+                            due_has_time = true;
+                            local_datetime(due.date_naive(), time)
+                        }
+                        None => Some(due),
+                    };
+                    continue;
+                }
+            }
+            kept.push(word);
+        }
+        kept.join(" ")
+    });
+
+    // Parsing for recurrence: ~daily, ~weekly, ~monthly
+    let mut recurrence = None;
+    cleaned_content = map_lines(&cleaned_content, |line| {
+        line.split_whitespace()
+            .filter(|word| {
+                if recurrence.is_none() {
+                    recurrence = parse_recurrence(word);
+                    return recurrence.is_none();
+                }
+                true
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    // Parsing for effort: ~30m, ~2h, ~1h30m or =45 minutes
+    let mut effort = None;
+    cleaned_content = map_lines(&cleaned_content, |line| {
+        line.split_whitespace()
+            .filter(|word| {
+                if effort.is_none() {
+                    effort = parse_effort(word);
+                    return effort.is_none();
+                }
+                true
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    // Parsing for tags: every #word token
+    let mut tags: Vec<SharedString> = Vec::new();
+    cleaned_content = map_lines(&cleaned_content, |line| {
+        line.split_whitespace()
+            .filter(|word| match parse_tag(word) {
+                Some(tag) => {
+                    if !tags.contains(&tag) {
+                        tags.push(tag);
+                    }
+                    false
+                }
+                None => true,
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+
+    ParsedTask {
+        content: cleaned_content,
+        priority,
+        due_date,
+        due_has_time,
+        recurrence,
+        tags,
+        effort,
+        reminder_lead,
+    }
+}
+
+/// Build a task from quick-add input, see `parse_task_input`
+pub fn parse_task(input: &str, now: DateTime<Local>) -> Task {
+    let parsed = parse_task_input(input, now);
+    let mut task = Task::new(parsed.content);
+    task.priority = parsed.priority.unwrap_or_default();
+    task.set_due(parsed.due_date, parsed.due_has_time);
+    task.recurrence = parsed.recurrence;
+    task.tags = parsed.tags;
+    task.effort = parsed.effort;
+    task.reminder_lead = parsed.reminder_lead;
+    task
+}
+
+/// Pull quick-add tokens out of edited content as a patch
+/// Only fields whose tokens appear are set, so editing `Call mom` to
+/// `Call mom !h` raises the priority without touching the due date.
+pub fn parse_task_patch(input: &str, now: DateTime<Local>) -> TaskPatch {
+    let parsed = parse_task_input(input, now);
+
+    TaskPatch {
+        content: Some(parsed.content.into()),
+        priority: parsed.priority,
+        due_date: parsed.due_date.map(Some),
+        due_has_time: parsed.due_date.map(|_| parsed.due_has_time),
+        notes: None,
+        recurrence: parsed.recurrence.map(Some),
+        tags: (!parsed.tags.is_empty()).then_some(parsed.tags),
+        effort: parsed.effort.map(Some),
+        reminder_lead: parsed.reminder_lead.map(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Friday 14 March 2025, mid-morning
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 14, 10, 0, 0).unwrap()
+    }
+
+    fn day(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn due_date_is_midnight_of_the_day() {
+        let due = parse_due_date("@tomorrow", now()).unwrap();
+        assert_eq!(due.date_naive(), day(2025, 3, 15));
+        assert_eq!(due.time(), NaiveTime::MIN);
+    }
+
+    #[test]
+    fn weekday_equal_to_today_means_next_week() {
+        let due = parse_due_date("@friday", now()).unwrap();
+        assert_eq!(due.date_naive(), day(2025, 3, 21));
+        assert_eq!(
+            parse_due_date("@today", now()).unwrap().date_naive(),
+            day(2025, 3, 14)
+        );
+    }
+
+    #[test]
+    fn weekday_later_this_week() {
+        let due = parse_due_date("@Sunday", now()).unwrap();
+        assert_eq!(due.date_naive(), day(2025, 3, 16));
+    }
+
+    #[test]
+    fn in_days_and_iso_dates() {
+        assert_eq!(
+            parse_due_date("@in3days", now()).unwrap().date_naive(),
+            day(2025, 3, 17)
+        );
+        assert_eq!(
+            parse_due_date("@2025-04-01", now()).unwrap().date_naive(),
+            day(2025, 4, 1)
+        );
+    }
+
+    #[test]
+    fn malformed_date_stays_in_the_content() {
+        assert_eq!(parse_due_date("@2025-13-40", now()), None);
+        let parsed = parse_task_input("Pay rent @2025-13-40", now());
+        assert_eq!(parsed.content, "Pay rent @2025-13-40");
+        assert_eq!(parsed.due_date, None);
+    }
+
+    #[test]
+    fn time_after_the_date_is_kept() {
+        let parsed = parse_task_input("Call mom @tomorrow 5:30pm", now());
+        assert_eq!(parsed.content, "Call mom");
+        let due = parsed.due_date.unwrap();
+        assert_eq!(due.date_naive(), day(2025, 3, 15));
+        assert_eq!(due.time(), NaiveTime::from_hms_opt(17, 30, 0).unwrap());
+        assert!(parsed.due_has_time);
+    }
+
+    #[test]
+    fn midnight_typed_as_a_time_is_still_a_time() {
+        let parsed = parse_task_input("Deploy @today 12am", now());
+        assert_eq!(parsed.due_date.unwrap().time(), NaiveTime::MIN);
+        assert!(parsed.due_has_time);
+        assert!(parse_task("Deploy @today 12am", now()).has_due_time());
+        assert!(!parse_task("Deploy @today", now()).has_due_time());
+    }
+
+    #[test]
+    fn effort_tokens_in_hours_and_minutes() {
+        assert_eq!(parse_effort("~30m"), Some(30));
+        assert_eq!(parse_effort("~2h"), Some(120));
+        assert_eq!(parse_effort("~1h30m"), Some(90));
+        assert_eq!(parse_effort("~1H30M"), Some(90));
+        assert_eq!(parse_effort("=45"), Some(45));
+    }
+
+    #[test]
+    fn zero_or_malformed_effort_is_rejected() {
+        assert_eq!(parse_effort("=0"), None);
+        assert_eq!(parse_effort("~0m"), None);
+        assert_eq!(parse_effort("~1h30"), None);
+        assert_eq!(parse_effort("~soon"), None);
+        let parsed = parse_task_input("Stretch =0", now());
+        assert_eq!(parsed.content, "Stretch =0");
+        assert_eq!(parsed.effort, None);
+    }
+}
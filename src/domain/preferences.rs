@@ -0,0 +1,93 @@
+use super::{GroupMode, SortMode};
+use std::collections::HashMap;
+
+/// Which palette the interface is drawn with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ThemeVariant {
+    /// The stormy night
+    #[default]
+    Dark,
+    /// The bright day, for well-lit rooms
+    Light,
+}
+
+impl ThemeVariant {
+    /// The other variant
+    pub fn toggled(self) -> Self {
+        match self {
+            ThemeVariant::Dark => ThemeVariant::Light,
+            ThemeVariant::Light => ThemeVariant::Dark,
+        }
+    }
+}
+
+/// How quickly the weather animations play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationSpeed {
+    /// Half again as long, for a calmer storm
+    Slow,
+    /// The configured durations
+    #[default]
+    Normal,
+    /// A bit over half as long
+    Fast,
+}
+
+impl AnimationSpeed {
+    /// Every speed, slowest first
+    pub const ALL: [AnimationSpeed; 3] = [
+        AnimationSpeed::Slow,
+        AnimationSpeed::Normal,
+        AnimationSpeed::Fast,
+    ];
+
+    /// Factor animation durations are multiplied by
+    pub fn duration_factor(self) -> f32 {
+        match self {
+            AnimationSpeed::Slow => 1.5,
+            AnimationSpeed::Normal => 1.0,
+            AnimationSpeed::Fast => 0.6,
+        }
+    }
+}
+
+/// App settings that don't belong to any task list, kept in `settings.json`
+/// Unlike `Preferences` these are stored apart from the tasks, so switching
+/// profiles or storage backends leaves them as they are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    /// How quickly animations play
+    pub animation_speed: AnimationSpeed,
+    /// Send desktop reminders when tasks come due
+    pub notifications: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            animation_speed: AnimationSpeed::Normal,
+            notifications: true,
+        }
+    }
+}
+
+/// User preferences persisted alongside the tasks
+#[derive(Debug, Clone, Default)]
+pub struct Preferences {
+    /// How the pending list is ordered
+    pub sort_mode: SortMode,
+    /// How the pending list is split into sections
+    pub group_mode: GroupMode,
+    /// Which palette is active
+    pub theme: ThemeVariant,
+    /// Skip the sway, rain and clear sky animations
+    pub reduced_motion: bool,
+    /// Whether the Overcome section is folded; None until the user chooses
+    pub completed_collapsed: Option<bool>,
+    /// Show only the single most pressing task
+    pub focus_mode: bool,
+    /// List the Overcome section oldest completion first instead of newest first
+    pub completed_oldest_first: bool,
+    /// Chip colors chosen for tags, as `#rrggbb` keyed by lowercase tag name
+    pub tag_colors: HashMap<String, String>,
+}
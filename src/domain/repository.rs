@@ -0,0 +1,112 @@
+use super::{Preferences, Task, TaskId};
+use std::cell::{Cell, RefCell};
+use std::path::Path;
+
+/// Where the task list and preferences are kept between sessions
+/// The application layer only talks to storage through this trait.
+pub trait TaskRepository {
+    /// Load the saved tasks and preferences (empty defaults if nothing is saved yet)
+    fn load(&self) -> Result<(Vec<Task>, Preferences), String>;
+
+    /// Replace the saved tasks and preferences
+    fn save(&self, tasks: &[Task], preferences: &Preferences) -> Result<(), String>;
+
+    /// Keep cleared tasks out of the list but not lost; backends without an archive drop them
+    fn archive(&self, _tasks: &[Task]) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Load the tasks from a backup file
+    fn restore_from(&self, _path: &Path) -> Result<Vec<Task>, String> {
+        Err("This storage cannot restore from backups".to_string())
+    }
+
+    /// Whether nothing has ever been saved here, as on a first run, as opposed
+    /// to a saved list that happens to be empty
+    fn is_fresh(&self) -> bool {
+        false
+    }
+
+    /// The file other programs may edit, if the tasks live in one worth watching
+    fn watch_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// A repository that keeps everything in memory, for tests and throwaway sessions
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Default)]
+pub struct InMemoryRepository {
+    data: RefCell<(Vec<Task>, Preferences)>,
+    archived: RefCell<Vec<Task>>,
+    /// Whether to report never having been saved to, until the first save
+    fresh: Cell<bool>,
+}
+
+#[cfg_attr(not(test), allow(dead_code))]
+impl InMemoryRepository {
+    /// Start with the given tasks already saved
+    pub fn with_tasks(tasks: Vec<Task>) -> Self {
+        Self {
+            data: RefCell::new((tasks, Preferences::default())),
+            ..Self::default()
+        }
+    }
+
+    /// Start empty, as storage that has never been saved to, like on a first run
+    pub fn fresh() -> Self {
+        Self {
+            fresh: Cell::new(true),
+            ..Self::default()
+        }
+    }
+
+    /// Tasks archived so far
+    pub fn archived(&self) -> Vec<Task> {
+        self.archived.borrow().clone()
+    }
+}
+
+impl TaskRepository for InMemoryRepository {
+    fn load(&self) -> Result<(Vec<Task>, Preferences), String> {
+        if let Some(id) = self.archived.borrow().iter().map(|t| t.id.0).max() {
+            TaskId::bump_past(id);
+        }
+        Ok(self.data.borrow().clone())
+    }
+
+    fn save(&self, tasks: &[Task], preferences: &Preferences) -> Result<(), String> {
+        *self.data.borrow_mut() = (tasks.to_vec(), preferences.clone());
+        self.fresh.set(false);
+        Ok(())
+    }
+
+    fn archive(&self, tasks: &[Task]) -> Result<(), String> {
+        let mut archived = self.archived.borrow_mut();
+        archived.retain(|old| !tasks.iter().any(|t| t.id == old.id));
+        archived.extend(tasks.iter().cloned());
+        Ok(())
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.fresh.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archiving_a_task_again_replaces_the_earlier_copy() {
+        let repository = InMemoryRepository::default();
+        let mut task = Task::new("Renew passport");
+        repository.archive(&[task.clone()]).unwrap();
+        task.content = "Renew passport and ID".into();
+        repository.archive(&[task.clone()]).unwrap();
+
+        let archived = repository.archived();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].content.as_ref(), "Renew passport and ID");
+    }
+}
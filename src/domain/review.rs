@@ -0,0 +1,248 @@
+use super::{format_effort, Task, TaskPriority};
+use chrono::{Datelike, Days, NaiveDate};
+use gpui::SharedString;
+
+/// A recap of the tasks overcome during one Monday-to-Sunday week
+#[derive(Debug, Clone)]
+pub struct WeeklySummary {
+    /// The Monday the week starts on
+    pub week_start: NaiveDate,
+    /// Tasks completed during the week, in the order they were completed
+    pub completed: Vec<Task>,
+    /// How many of them had each priority, most pressing first, leaving out
+    /// priorities with none
+    pub by_priority: Vec<(TaskPriority, usize)>,
+    /// How many carried each tag, most common first, ties in alphabetical order
+    pub by_tag: Vec<(SharedString, usize)>,
+}
+
+impl WeeklySummary {
+    /// Summarize the done tasks completed in the week containing `week_of`
+    /// A task's completion is dated by its `updated_at`.
+    pub fn new(tasks: &[Task], week_of: NaiveDate) -> Self {
+        let week_start = week_of
+            .checked_sub_days(Days::new(week_of.weekday().num_days_from_monday() as u64))
+            .unwrap_or(week_of);
+        let week_end = week_start.checked_add_days(Days::new(7));
+
+        let mut completed: Vec<Task> = tasks
+            .iter()
+            .filter(|t| t.is_done())
+            .filter(|t| {
+                let day = t.updated_at.date_naive();
+                day >= week_start && week_end.is_none_or(|end| day < end)
+            })
+            .cloned()
+            .collect();
+        completed.sort_by_key(|t| t.updated_at);
+
+        let by_priority = [
+            TaskPriority::Urgent,
+            TaskPriority::High,
+            TaskPriority::Medium,
+            TaskPriority::Low,
+        ]
+        .into_iter()
+        .map(|priority| {
+            let count = completed.iter().filter(|t| t.priority == priority).count();
+            (priority, count)
+        })
+        .filter(|&(_, count)| count > 0)
+        .collect();
+
+        let mut by_tag: Vec<(SharedString, usize)> = Vec::new();
+        for tag in completed.iter().flat_map(|t| &t.tags) {
+            match by_tag.iter_mut().find(|(t, _)| t == tag) {
+                Some((_, count)) => *count += 1,
+                None => by_tag.push((tag.clone(), 1)),
+            }
+        }
+        by_tag.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase()))
+        });
+
+        Self {
+            week_start,
+            completed,
+            by_priority,
+            by_tag,
+        }
+    }
+
+    /// The Sunday the week ends on
+    pub fn week_end(&self) -> NaiveDate {
+        self.week_start
+            .checked_add_days(Days::new(6))
+            .unwrap_or(self.week_start)
+    }
+
+    /// Format the recap as a Markdown report
+    pub fn render_markdown(&self) -> String {
+        let mut output = format!(
+            "# Weekly review: {} – {}\n\n",
+            self.week_start.format("%Y-%m-%d"),
+            self.week_end().format("%Y-%m-%d")
+        );
+
+        if self.completed.is_empty() {
+            output.push_str("Nothing overcome this week. Next week is a fresh sky.\n");
+            return output;
+        }
+
+        output.push_str(&format!(
+            "Overcame {} task{}.\n",
+            self.completed.len(),
+            if self.completed.len() == 1 { "" } else { "s" }
+        ));
+        let effort: u32 = self
+            .completed
+            .iter()
+            .filter_map(|t| t.effort)
+            .fold(0, u32::saturating_add);
+        if effort > 0 {
+            output.push_str(&format!(
+                "About {} of estimated work.\n",
+                format_effort(effort)
+            ));
+        }
+
+        output.push_str("\n## By priority\n\n");
+        for (priority, count) in &self.by_priority {
+            let label = match priority {
+                TaskPriority::Urgent => "Urgent",
+                TaskPriority::High => "High",
+                TaskPriority::Medium => "Medium",
+                TaskPriority::Low => "Low",
+            };
+            output.push_str(&format!("- {}: {}\n", label, count));
+        }
+
+        if !self.by_tag.is_empty() {
+            output.push_str("\n## By tag\n\n");
+            for (tag, count) in &self.by_tag {
+                output.push_str(&format!("- #{}: {}\n", tag, count));
+            }
+        }
+
+        output.push_str("\n## Overcome\n\n");
+        for task in &self.completed {
+            output.push_str(&format!(
+                "- [x] {} ({})",
+                task.content.replace('\n', "\n  "),
+                task.updated_at.format("%a %m/%d")
+            ));
+            if let Some(note) = &task.completion_note {
+                output.push_str(&format!(" — {}", note));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn done_on(content: &str, month: u32, day: u32, hour: u32) -> Task {
+        let mut task = Task::new(content.to_string());
+        task.complete();
+        task.updated_at = Local
+            .with_ymd_and_hms(2025, month, day, hour, 0, 0)
+            .unwrap();
+        task
+    }
+
+    /// Tasks done either side of the week of Monday 10 March 2025
+    fn tasks() -> Vec<Task> {
+        let mut report = done_on("Send report", 3, 10, 9);
+        report.priority = TaskPriority::High;
+        report.tags = vec!["work".into()];
+        report.effort = Some(30);
+
+        let mut shelves = done_on("Fix shelves", 3, 16, 22);
+        shelves.tags = vec!["home".into(), "work".into()];
+        shelves.effort = Some(60);
+        shelves.completion_note = Some("finally".to_string());
+
+        let mut pending = Task::new("Still to do");
+        pending.updated_at = Local.with_ymd_and_hms(2025, 3, 12, 9, 0, 0).unwrap();
+
+        vec![
+            shelves,
+            done_on("Late Sunday", 3, 9, 23),
+            report,
+            done_on("Next Monday", 3, 17, 0),
+            pending,
+        ]
+    }
+
+    #[test]
+    fn the_week_runs_monday_to_sunday() {
+        let summary = WeeklySummary::new(&tasks(), NaiveDate::from_ymd_opt(2025, 3, 12).unwrap());
+        assert_eq!(
+            summary.week_start,
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+        assert_eq!(
+            summary.week_end(),
+            NaiveDate::from_ymd_opt(2025, 3, 16).unwrap()
+        );
+        let completed: Vec<_> = summary
+            .completed
+            .iter()
+            .map(|t| t.content.to_string())
+            .collect();
+        assert_eq!(completed, ["Send report", "Fix shelves"]);
+        assert_eq!(
+            summary.by_priority,
+            [(TaskPriority::High, 1), (TaskPriority::Low, 1)]
+        );
+        assert_eq!(
+            summary.by_tag,
+            [
+                (SharedString::from("work"), 2),
+                (SharedString::from("home"), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_a_markdown_report() {
+        let summary = WeeklySummary::new(&tasks(), NaiveDate::from_ymd_opt(2025, 3, 16).unwrap());
+        assert_eq!(
+            summary.render_markdown(),
+            "# Weekly review: 2025-03-10 – 2025-03-16\n\
+             \n\
+             Overcame 2 tasks.\n\
+             About 1h 30m of estimated work.\n\
+             \n\
+             ## By priority\n\
+             \n\
+             - High: 1\n\
+             - Low: 1\n\
+             \n\
+             ## By tag\n\
+             \n\
+             - #work: 2\n\
+             - #home: 1\n\
+             \n\
+             ## Overcome\n\
+             \n\
+             - [x] Send report (Mon 03/10)\n\
+             - [x] Fix shelves (Sun 03/16) — finally\n"
+        );
+    }
+
+    #[test]
+    fn an_empty_week_says_so() {
+        let summary = WeeklySummary::new(&tasks(), NaiveDate::from_ymd_opt(2025, 3, 3).unwrap());
+        assert!(summary.completed.is_empty());
+        assert!(summary
+            .render_markdown()
+            .ends_with("Nothing overcome this week. Next week is a fresh sky.\n"));
+    }
+}
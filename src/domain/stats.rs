@@ -0,0 +1,84 @@
+use chrono::NaiveDate;
+
+/// A snapshot of task counts and completion momentum
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskStats {
+    /// Every task in the list
+    pub total: usize,
+    /// Tasks still waiting to be overcome
+    pub pending: usize,
+    /// Tasks overcome (not counting archived ones)
+    pub completed: usize,
+    /// Pending tasks past their due date
+    pub overdue: usize,
+    /// Tasks overcome today
+    pub completed_today: usize,
+    /// Consecutive days, up to today, with at least one completion
+    pub daily_streak: u32,
+}
+
+/// Count consecutive days with a completion, walking back from `today`
+///
+/// A streak isn't broken until the day is over: with nothing done yet today,
+/// the count starts from yesterday. Duplicate and unordered dates are fine.
+pub fn daily_streak(completion_dates: &[NaiveDate], today: NaiveDate) -> u32 {
+    let mut day = if completion_dates.contains(&today) {
+        today
+    } else {
+        match today.pred_opt() {
+            Some(yesterday) => yesterday,
+            None => return 0,
+        }
+    };
+
+    let mut streak = 0;
+    while completion_dates.contains(&day) {
+        streak += 1;
+        match day.pred_opt() {
+            Some(prev) => day = prev,
+            None => break,
+        }
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    /// Friday 14 March 2025
+    fn today() -> NaiveDate {
+        day(14)
+    }
+
+    #[test]
+    fn no_completions_is_no_streak() {
+        assert_eq!(daily_streak(&[], today()), 0);
+    }
+
+    #[test]
+    fn a_completion_today_starts_a_streak() {
+        assert_eq!(daily_streak(&[day(14)], today()), 1);
+    }
+
+    #[test]
+    fn a_streak_survives_until_today_is_over() {
+        assert_eq!(daily_streak(&[day(13), day(12)], today()), 2);
+    }
+
+    #[test]
+    fn a_missed_day_breaks_the_streak() {
+        assert_eq!(daily_streak(&[day(14), day(12), day(11)], today()), 1);
+        assert_eq!(daily_streak(&[day(12), day(11)], today()), 0);
+    }
+
+    #[test]
+    fn duplicate_and_unordered_dates_count_once() {
+        let dates = [day(12), day(14), day(13), day(14), day(12), day(10)];
+        assert_eq!(daily_streak(&dates, today()), 3);
+    }
+}
@@ -1,5 +1,10 @@
+use super::fuzzy_score;
+use chrono::{DateTime, Local};
 use gpui::SharedString;
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The id the next new task gets
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
 
 /// The state of a task in its lifecycle.
 /// Follows the "Wind & Rain" metaphor:
@@ -23,9 +28,15 @@ pub struct TaskId(pub u64);
 
 impl TaskId {
     pub fn new() -> Self {
-        use std::sync::atomic::{AtomicU64, Ordering};
-        static COUNTER: AtomicU64 = AtomicU64::new(1);
-        Self(COUNTER.fetch_add(1, Ordering::Relaxed))
+        Self(NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Make sure ids handed out from now on are greater than `id`
+    /// The counter restarts at 1 with each launch while saved tasks keep their
+    /// ids, so loading calls this with the highest id saved, archived tasks
+    /// included. Lower ids leave the counter as it is.
+    pub fn bump_past(id: u64) {
+        NEXT_TASK_ID.fetch_max(id.saturating_add(1), Ordering::Relaxed);
     }
 }
 
@@ -45,8 +56,128 @@ pub enum TaskPriority {
     Medium,
     /// High priority, like a storm brew
     High,
+    /// Urgent priority, the storm has broken
+    Urgent,
+}
+
+impl TaskPriority {
+    /// The next priority when cycling through them, wrapping back to Low
+    pub fn next(self) -> Self {
+        match self {
+            TaskPriority::Low => TaskPriority::Medium,
+            TaskPriority::Medium => TaskPriority::High,
+            TaskPriority::High => TaskPriority::Urgent,
+            TaskPriority::Urgent => TaskPriority::Low,
+        }
+    }
+
+    /// The previous priority when cycling backwards, wrapping round to Urgent
+    pub fn previous(self) -> Self {
+        match self {
+            TaskPriority::Low => TaskPriority::Urgent,
+            TaskPriority::Medium => TaskPriority::Low,
+            TaskPriority::High => TaskPriority::Medium,
+            TaskPriority::Urgent => TaskPriority::High,
+        }
+    }
+}
+
+/// A single step within a larger task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subtask {
+    /// Identifier, unique within the parent task
+    pub id: u64,
+    pub content: String,
+    pub done: bool,
+}
+
+/// How often a task comes back after it has been overcome.
+/// Like the seasons, some winds return on schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
 }
 
+impl Recurrence {
+    /// Advance a date by one interval
+    pub fn advance(self, from: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Recurrence::Daily => from.checked_add_signed(chrono::Duration::days(1)),
+            Recurrence::Weekly => from.checked_add_signed(chrono::Duration::days(7)),
+            Recurrence::Monthly => from.checked_add_months(chrono::Months::new(1)),
+        }
+    }
+
+    /// The next due date after `now`, stepping from the previous due date so the
+    /// schedule is kept even when the task was completed late
+    pub fn next_due(
+        self,
+        due_date: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let mut next = self.advance(due_date.unwrap_or(now))?;
+        while next.date_naive() <= now.date_naive() {
+            next = self.advance(next)?;
+        }
+        Some(next)
+    }
+}
+
+/// How the pending task list is ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// The order tasks were added (or manually arranged) in
+    #[default]
+    Manual,
+    /// Storms first: highest priority at the top
+    PriorityDesc,
+    /// Soonest due date first; tasks without a due date last
+    DueDateAsc,
+    /// Oldest task first
+    CreatedAsc,
+}
+
+impl SortMode {
+    /// The next mode when cycling through them
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Manual => SortMode::PriorityDesc,
+            SortMode::PriorityDesc => SortMode::DueDateAsc,
+            SortMode::DueDateAsc => SortMode::CreatedAsc,
+            SortMode::CreatedAsc => SortMode::Manual,
+        }
+    }
+}
+
+/// Changes to several task fields, applied together as one edit.
+/// `None` leaves a field as it is; `Some(None)` clears an optional field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskPatch {
+    pub content: Option<SharedString>,
+    pub priority: Option<TaskPriority>,
+    pub due_date: Option<Option<DateTime<Local>>>,
+    /// Whether the due date has a time of day; clearing the due date clears it
+    pub due_has_time: Option<bool>,
+    pub notes: Option<Option<String>>,
+    pub recurrence: Option<Option<Recurrence>>,
+    pub tags: Option<Vec<SharedString>>,
+    pub effort: Option<Option<u32>>,
+    pub reminder_lead: Option<Option<u32>>,
+}
+
+impl TaskPatch {
+    /// Check if the patch changes nothing
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Titles longer than this many characters are cut short when added,
+/// with the full text kept in the notes
+pub const MAX_TITLE_CHARS: usize = 200;
+
 /// A task entity representing something to be overcome.
 #[derive(Debug, Clone)]
 pub struct Task {
@@ -61,17 +192,36 @@ pub struct Task {
     /// Priority level
     pub priority: TaskPriority,
     /// Optional due date
-    pub due_date: Option<chrono::DateTime<chrono::Local>>,
+    pub due_date: Option<DateTime<Local>>,
+    /// Whether the due date has a time of day; date-only due dates sit at the
+    /// start of their day
+    pub due_has_time: bool,
+    /// Tags parsed from `#word` tokens, used for cross-cutting grouping
+    pub tags: Vec<SharedString>,
+    /// Optional schedule on which a fresh copy is created after completion
+    pub recurrence: Option<Recurrence>,
+    /// Checklist steps breaking the task down
+    pub subtasks: Vec<Subtask>,
+    /// Kept above unpinned tasks whatever the sort order
+    pub pinned: bool,
+    /// How the task was overcome, jotted down when completing it
+    pub completion_note: Option<String>,
+    /// Rough estimate of the work it takes, in minutes
+    pub effort: Option<u32>,
+    /// Minutes ahead of the due date to send an early reminder
+    pub reminder_lead: Option<u32>,
+    /// The reminder time last notified about, so a restart doesn't notify again
+    pub notified_at: Option<DateTime<Local>>,
     /// When the task was created
-    pub created_at: Instant,
+    pub created_at: DateTime<Local>,
     /// When the task state last changed
-    pub updated_at: Instant,
+    pub updated_at: DateTime<Local>,
 }
 
 impl Task {
     /// Create a new pending task
     pub fn new(content: impl Into<SharedString>) -> Self {
-        let now = Instant::now();
+        let now = Local::now();
         Self {
             id: TaskId::new(),
             content: content.into(),
@@ -79,23 +229,67 @@ impl Task {
             state: TaskState::Pending,
             priority: TaskPriority::default(),
             due_date: None,
+            due_has_time: false,
+            tags: Vec::new(),
+            recurrence: None,
+            subtasks: Vec::new(),
+            pinned: false,
+            completion_note: None,
+            effort: None,
+            reminder_lead: None,
+            notified_at: None,
             created_at: now,
             updated_at: now,
         }
     }
 
+    /// Apply the changes in `patch`, returning a patch that restores the old values
+    /// Only fields that actually change appear in the returned patch.
+    pub fn apply(&mut self, patch: TaskPatch) -> TaskPatch {
+        fn swap<T: PartialEq>(field: &mut T, value: Option<T>) -> Option<T> {
+            let value = value.filter(|v| v != field)?;
+            Some(std::mem::replace(field, value))
+        }
+
+        let due_has_time = match patch.due_date {
+            Some(None) => Some(false),
+            _ => patch.due_has_time,
+        };
+        let inverse = TaskPatch {
+            content: swap(&mut self.content, patch.content),
+            priority: swap(&mut self.priority, patch.priority),
+            due_date: swap(&mut self.due_date, patch.due_date),
+            due_has_time: swap(&mut self.due_has_time, due_has_time),
+            notes: swap(&mut self.notes, patch.notes),
+            recurrence: swap(&mut self.recurrence, patch.recurrence),
+            tags: swap(&mut self.tags, patch.tags),
+            effort: swap(&mut self.effort, patch.effort),
+            reminder_lead: swap(&mut self.reminder_lead, patch.reminder_lead),
+        };
+        if !inverse.is_empty() {
+            self.updated_at = Local::now();
+        }
+        inverse
+    }
+
     /// Begin the completion animation
     pub fn begin_completing(&mut self) {
         if self.state == TaskState::Pending {
             self.state = TaskState::Completing;
-            self.updated_at = Instant::now();
+            self.updated_at = Local::now();
         }
     }
 
     /// Mark the task as fully completed
     pub fn complete(&mut self) {
         self.state = TaskState::Done;
-        self.updated_at = Instant::now();
+        self.updated_at = Local::now();
+    }
+
+    /// Move a completed task back to pending
+    pub fn reopen(&mut self) {
+        self.state = TaskState::Pending;
+        self.updated_at = Local::now();
     }
 
     /// Check if task is in pending state
@@ -112,4 +306,109 @@ impl Task {
     pub fn is_done(&self) -> bool {
         self.state == TaskState::Done
     }
+
+    /// Check if the due date carries a time of day
+    pub fn has_due_time(&self) -> bool {
+        self.due_date.is_some() && self.due_has_time
+    }
+
+    /// Set the due date and whether it has a time of day
+    pub fn set_due(&mut self, due_date: Option<DateTime<Local>>, has_time: bool) {
+        self.due_date = due_date;
+        self.due_has_time = due_date.is_some() && has_time;
+    }
+
+    /// Check if the task is due on the given calendar day
+    pub fn is_due_on(&self, day: chrono::NaiveDate) -> bool {
+        self.due_date.is_some_and(|due| due.date_naive() == day)
+    }
+
+    /// Check if the task is still open past its due date
+    /// Timed tasks are overdue the moment their time passes; date-only tasks once the day is over.
+    pub fn is_overdue(&self, now: DateTime<Local>) -> bool {
+        let Some(due) = self.due_date else {
+            return false;
+        };
+        if self.is_done() {
+            return false;
+        }
+        if self.has_due_time() {
+            due < now
+        } else {
+            due.date_naive() < now.date_naive()
+        }
+    }
+
+    /// Check if the task is open and its due moment has come
+    /// Timed tasks come due at their time; date-only tasks as their day begins.
+    pub fn is_due_by(&self, now: DateTime<Local>) -> bool {
+        let Some(due) = self.due_date else {
+            return false;
+        };
+        if self.is_done() {
+            return false;
+        }
+        if self.has_due_time() {
+            due <= now
+        } else {
+            due.date_naive() <= now.date_naive()
+        }
+    }
+
+    /// When the early reminder is due, `reminder_lead` before the due date
+    /// Date-only due dates count back from the start of their day.
+    pub fn reminder_at(&self) -> Option<DateTime<Local>> {
+        let lead = chrono::Duration::minutes(i64::from(self.reminder_lead?));
+        self.due_date?.checked_sub_signed(lead)
+    }
+
+    /// Check if every checklist step is done (vacuously true without steps)
+    pub fn all_subtasks_done(&self) -> bool {
+        self.subtasks.iter().all(|s| s.done)
+    }
+
+    /// Count of checklist steps done
+    pub fn subtasks_done_count(&self) -> usize {
+        self.subtasks.iter().filter(|s| s.done).count()
+    }
+
+    /// Check if the content runs past `MAX_TITLE_CHARS`
+    pub fn is_long(&self) -> bool {
+        self.content.chars().count() > MAX_TITLE_CHARS
+    }
+
+    /// Cut an overlong title back to `MAX_TITLE_CHARS` with an ellipsis, at a word
+    /// break where there is one, moving the full text into the notes
+    pub fn shorten_long_content(&mut self) -> bool {
+        if !self.is_long() {
+            return false;
+        }
+        let full = self.content.to_string();
+        let cut: String = full.chars().take(MAX_TITLE_CHARS - 1).collect();
+        let cut = match cut.rfind(char::is_whitespace) {
+            Some(pos) if pos > 0 => &cut[..pos],
+            _ => cut.as_str(),
+        };
+        self.content = format!("{}…", cut.trim_end()).into();
+        self.notes = Some(match self.notes.take() {
+            Some(notes) => format!("{}\n\n{}", full, notes),
+            None => full,
+        });
+        true
+    }
+
+    /// How well the query matches, or None if it doesn't
+    /// Content and tags are matched fuzzily with `fuzzy_score`; notes, being
+    /// long enough to hold almost any subsequence, only by substring (scoring 0).
+    pub fn search_score(&self, query: &str) -> Option<i32> {
+        let in_notes = self
+            .notes
+            .as_ref()
+            .is_some_and(|notes| notes.to_lowercase().contains(&query.trim().to_lowercase()));
+        std::iter::once(fuzzy_score(query, &self.content))
+            .chain(self.tags.iter().map(|tag| fuzzy_score(query, tag)))
+            .chain(std::iter::once(in_notes.then_some(0)))
+            .flatten()
+            .max()
+    }
 }
@@ -0,0 +1,205 @@
+use super::{TaskData, TaskStorage};
+use crate::domain::{local_datetime, Task};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime};
+
+const CSV_HEADER: [&str; 8] = [
+    "id",
+    "content",
+    "state",
+    "priority",
+    "due_date",
+    "tags",
+    "notes",
+    "created_at",
+];
+
+/// Quote a field per RFC 4180 when it contains a comma, quote or line break
+fn quote_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split CSV content into records, each paired with the line it starts on
+/// Quoted fields may contain commas, doubled quotes and line breaks.
+fn parse_records(content: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("Line {}: unterminated quoted field", record_line));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+
+    // Skip blank lines
+    records.retain(|(_, r)| !(r.len() == 1 && r[0].is_empty()));
+    Ok(records)
+}
+
+fn parse_timestamp(
+    value: &str,
+    column: &str,
+    line: usize,
+) -> Result<Option<DateTime<Local>>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| Some(date.with_timezone(&Local)))
+        .map_err(|e| format!("Line {}: invalid {} '{}': {}", line, column, value, e))
+}
+
+/// A due date is a plain `YYYY-MM-DD` date without a time of day, else an
+/// RFC 3339 timestamp; exports from before the plain form marked date-only due
+/// dates with a time of midnight
+fn parse_due(value: &str, line: usize) -> Result<Option<(DateTime<FixedOffset>, bool)>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    if let Ok(day) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(local_datetime(day, NaiveTime::MIN).map(|due| (due.fixed_offset(), false)));
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|due| Some((due, due.time() != NaiveTime::MIN)))
+        .map_err(|e| format!("Line {}: invalid due_date '{}': {}", line, value, e))
+}
+
+fn parse_row(line: usize, fields: &[String]) -> Result<Task, String> {
+    if fields.len() != CSV_HEADER.len() {
+        return Err(format!(
+            "Line {}: expected {} fields, found {}",
+            line,
+            CSV_HEADER.len(),
+            fields.len()
+        ));
+    }
+
+    let id = fields[0]
+        .parse()
+        .map_err(|_| format!("Line {}: invalid id '{}'", line, fields[0]))?;
+
+    let state = fields[2].as_str();
+    if !matches!(state, "pending" | "completing" | "done") {
+        return Err(format!("Line {}: invalid state '{}'", line, state));
+    }
+
+    let priority = fields[3].as_str();
+    if !matches!(priority, "low" | "medium" | "high" | "urgent") {
+        return Err(format!("Line {}: invalid priority '{}'", line, priority));
+    }
+
+    let due = parse_due(&fields[4], line)?;
+    let data = TaskData {
+        id,
+        content: fields[1].clone(),
+        notes: Some(fields[6].clone()).filter(|n| !n.is_empty()),
+        state: state.to_string(),
+        priority: priority.to_string(),
+        due_date: due.map(|(due, _)| due),
+        due_has_time: due.is_some_and(|(_, has_time)| has_time),
+        tags: fields[5]
+            .split(';')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect(),
+        recurrence: None,
+        subtasks: Vec::new(),
+        pinned: false,
+        completion_note: None,
+        effort: None,
+        reminder_lead: None,
+        notified_at: None,
+        created_at: parse_timestamp(&fields[7], "created_at", line)?,
+        updated_at: None,
+    };
+
+    Ok(data.into_task())
+}
+
+impl TaskStorage {
+    /// Render tasks as RFC 4180 CSV with a header row
+    pub fn export_csv(tasks: &[Task]) -> String {
+        let mut output = CSV_HEADER.join(",");
+        output.push_str("\r\n");
+
+        for task in tasks {
+            let data = TaskData::from(task);
+            let row = [
+                data.id.to_string(),
+                data.content,
+                data.state,
+                data.priority,
+                match data.due_date {
+                    Some(due) if data.due_has_time => due.to_rfc3339(),
+                    Some(due) => due.format("%Y-%m-%d").to_string(),
+                    None => String::new(),
+                },
+                data.tags.join(";"),
+                data.notes.unwrap_or_default(),
+                data.created_at.map(|d| d.to_rfc3339()).unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|f| quote_field(f)).collect();
+            output.push_str(&row.join(","));
+            output.push_str("\r\n");
+        }
+
+        output
+    }
+
+    /// Parse CSV written by `export_csv`; a leading header row is skipped
+    pub fn import_csv(content: &str) -> Result<Vec<Task>, String> {
+        let mut records = parse_records(content)?.into_iter().peekable();
+
+        if let Some((_, first)) = records.peek() {
+            if first.first().map(String::as_str) == Some(CSV_HEADER[0]) {
+                records.next();
+            }
+        }
+
+        records
+            .map(|(line, fields)| parse_row(line, &fields))
+            .collect()
+    }
+}
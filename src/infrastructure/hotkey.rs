@@ -0,0 +1,88 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// Settings for the system-wide quick capture shortcut, read from `hotkey.toml`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    /// Register the shortcut at all
+    pub enabled: bool,
+    /// The shortcut, e.g. `ctrl+alt+n` or `super+shift+space`
+    pub quick_capture: String,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quick_capture: "ctrl+alt+n".to_string(),
+        }
+    }
+}
+
+impl HotkeyConfig {
+    /// Load settings from a TOML file with `enabled` and `quick_capture` keys
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read hotkey file: {}", e))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse hotkey file: {}", e))
+    }
+}
+
+/// A shortcut registered with the operating system, pressed even while
+/// another app has focus
+#[cfg_attr(not(feature = "hotkey"), allow(dead_code))]
+pub struct GlobalHotkey {
+    #[cfg(feature = "hotkey")]
+    _manager: global_hotkey::GlobalHotKeyManager,
+    #[cfg(feature = "hotkey")]
+    id: u32,
+}
+
+impl GlobalHotkey {
+    /// Register `shortcut`; must be called on the main thread
+    #[cfg(feature = "hotkey")]
+    pub fn register(shortcut: &str) -> Result<Self, String> {
+        use global_hotkey::hotkey::HotKey;
+
+        let hotkey: HotKey = shortcut
+            .parse()
+            .map_err(|e| format!("Invalid shortcut {:?}: {}", shortcut, e))?;
+        let manager = global_hotkey::GlobalHotKeyManager::new()
+            .map_err(|e| format!("Global shortcuts are unavailable: {}", e))?;
+        manager
+            .register(hotkey)
+            .map_err(|e| format!("Failed to register {}: {}", shortcut, e))?;
+
+        Ok(Self {
+            _manager: manager,
+            id: hotkey.id(),
+        })
+    }
+
+    /// Without the `hotkey` feature there is no shortcut backend
+    #[cfg(not(feature = "hotkey"))]
+    pub fn register(shortcut: &str) -> Result<Self, String> {
+        Err(format!(
+            "Cannot register {}: built without the hotkey feature",
+            shortcut
+        ))
+    }
+
+    /// Whether the shortcut was pressed since the previous call
+    #[cfg(feature = "hotkey")]
+    pub fn take_pressed(&self) -> bool {
+        use global_hotkey::{GlobalHotKeyEvent, HotKeyState};
+
+        let mut pressed = false;
+        while let Ok(event) = GlobalHotKeyEvent::receiver().try_recv() {
+            pressed |= event.id == self.id && event.state == HotKeyState::Pressed;
+        }
+        pressed
+    }
+
+    #[cfg(not(feature = "hotkey"))]
+    pub fn take_pressed(&self) -> bool {
+        false
+    }
+}
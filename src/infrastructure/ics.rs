@@ -0,0 +1,159 @@
+use super::TaskStorage;
+use crate::domain::{Task, TaskPriority};
+use chrono::{DateTime, Local, Utc};
+
+/// Escape a TEXT value per RFC 5545 (backslash, semicolon, comma, newline)
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Format a timestamp as a UTC date-time, e.g. `20250314T090000Z`
+fn format_utc(date: DateTime<Local>) -> String {
+    date.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Append a content line, folding it at 75 octets as RFC 5545 requires
+fn push_line(output: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            width = 1;
+        }
+        output.push(c);
+        width += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+impl TaskStorage {
+    /// Render tasks with a due date as an iCalendar feed of VTODOs
+    /// UIDs are derived from the task id, so re-importing updates existing entries.
+    pub fn export_ics(tasks: &[Task]) -> String {
+        let mut output = String::new();
+        push_line(&mut output, "BEGIN:VCALENDAR");
+        push_line(&mut output, "VERSION:2.0");
+        push_line(&mut output, "PRODID:-//Waloyo//Waloyo//EN");
+
+        for task in tasks {
+            let Some(due) = task.due_date else {
+                continue;
+            };
+
+            push_line(&mut output, "BEGIN:VTODO");
+            push_line(
+                &mut output,
+                &format!("UID:waloyo-task-{}@waloyo", task.id.0),
+            );
+            push_line(
+                &mut output,
+                &format!("DTSTAMP:{}", format_utc(task.updated_at)),
+            );
+            push_line(&mut output, &format!("DUE:{}", format_utc(due)));
+            push_line(
+                &mut output,
+                &format!("SUMMARY:{}", escape_text(&task.content)),
+            );
+            if let Some(notes) = &task.notes {
+                push_line(&mut output, &format!("DESCRIPTION:{}", escape_text(notes)));
+            }
+            // iCalendar ranks 1 as highest and 9 as lowest
+            let priority = match task.priority {
+                TaskPriority::Urgent => 1,
+                TaskPriority::High => 3,
+                TaskPriority::Medium => 5,
+                TaskPriority::Low => 9,
+            };
+            push_line(&mut output, &format!("PRIORITY:{}", priority));
+            if task.is_done() {
+                push_line(&mut output, "STATUS:COMPLETED");
+                push_line(
+                    &mut output,
+                    &format!("COMPLETED:{}", format_utc(task.updated_at)),
+                );
+            } else {
+                push_line(&mut output, "STATUS:NEEDS-ACTION");
+            }
+            if let Some(lead) = task.reminder_lead {
+                push_line(&mut output, "BEGIN:VALARM");
+                push_line(&mut output, "ACTION:DISPLAY");
+                push_line(&mut output, &format!("TRIGGER;RELATED=END:-PT{}M", lead));
+                push_line(
+                    &mut output,
+                    &format!("DESCRIPTION:{}", escape_text(&task.content)),
+                );
+                push_line(&mut output, "END:VALARM");
+            }
+            push_line(&mut output, "END:VTODO");
+        }
+
+        push_line(&mut output, "END:VCALENDAR");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn due_task(content: &str) -> Task {
+        let mut task = Task::new(content.to_string());
+        task.due_date = Some(Local::now());
+        task
+    }
+
+    #[test]
+    fn blocks_are_balanced_and_nested() {
+        let mut reminded = due_task("Call mom");
+        reminded.reminder_lead = Some(15);
+        let undated = Task::new("Someday");
+        let ics = TaskStorage::export_ics(&[reminded, undated, due_task("Pay rent")]);
+
+        let mut open: Vec<&str> = Vec::new();
+        for line in ics.split("\r\n").filter(|line| !line.is_empty()) {
+            if let Some(name) = line.strip_prefix("BEGIN:") {
+                open.push(name);
+            } else if let Some(name) = line.strip_prefix("END:") {
+                assert_eq!(open.pop(), Some(name));
+            }
+        }
+        assert!(open.is_empty());
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VTODO").count(), 2);
+        assert_eq!(ics.matches("BEGIN:VALARM").count(), 1);
+        assert!(!ics.contains("Someday"));
+    }
+
+    #[test]
+    fn description_is_escaped() {
+        let mut task = due_task("Plan trip");
+        task.notes = Some("Flights; hotel, car\nPack C:\\bags".to_string());
+        let ics = TaskStorage::export_ics(&[task]);
+        assert!(ics.contains(r"DESCRIPTION:Flights\; hotel\, car\nPack C:\\bags"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let ics = TaskStorage::export_ics(&[due_task(&"a".repeat(200))]);
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "line too long: {}", line);
+        }
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("SUMMARY:{}\r\n", "a".repeat(200))));
+    }
+}
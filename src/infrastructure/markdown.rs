@@ -0,0 +1,194 @@
+use super::TaskStorage;
+use crate::domain::{parse_task, Recurrence, Task, TaskPriority};
+use std::fs;
+use std::path::Path;
+
+/// Format a task as a checklist line using the same inline syntax as quick-add
+/// Extra lines of multi-line content are indented so they stay in the item.
+fn markdown_line(task: &Task) -> String {
+    let mut line = format!(
+        "- [{}] {}",
+        if task.is_done() { "x" } else { " " },
+        task.content.replace('\n', "\n  ")
+    );
+
+    match task.priority {
+        TaskPriority::Urgent => line.push_str(" !u"),
+        TaskPriority::High => line.push_str(" !h"),
+        TaskPriority::Medium => line.push_str(" !m"),
+        TaskPriority::Low => {}
+    }
+
+    if let Some(due) = task.due_date {
+        line.push_str(&format!(" @{}", due.format("%Y-%m-%d")));
+        if task.has_due_time() {
+            line.push_str(&format!(" {}", due.format("%H:%M")));
+        }
+    }
+
+    if let Some(recurrence) = task.recurrence {
+        line.push_str(match recurrence {
+            Recurrence::Daily => " ~daily",
+            Recurrence::Weekly => " ~weekly",
+            Recurrence::Monthly => " ~monthly",
+        });
+    }
+
+    if let Some(effort) = task.effort {
+        line.push_str(&format!(" ~{}", span_token(effort)));
+    }
+
+    if let Some(lead) = task.reminder_lead {
+        line.push_str(&format!(" !remind{}", span_token(lead)));
+    }
+
+    for tag in &task.tags {
+        line.push_str(&format!(" #{}", tag));
+    }
+
+    line
+}
+
+/// Minutes as quick-add writes them after `~` or `!remind`: `30m`, `2h` or `1h30m`
+fn span_token(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h{}m", hours, minutes),
+    }
+}
+
+impl TaskStorage {
+    /// Render tasks as a Markdown checklist grouped into Pending and Overcome
+    pub fn export_markdown(tasks: &[Task]) -> String {
+        let mut output = String::from("## Pending\n\n");
+        for task in tasks.iter().filter(|t| !t.is_done()) {
+            output.push_str(&markdown_line(task));
+            output.push('\n');
+        }
+
+        output.push_str("\n## Overcome\n\n");
+        for task in tasks.iter().filter(|t| t.is_done()) {
+            output.push_str(&markdown_line(task));
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Parse `- [ ]` / `- [x]` checklist lines into tasks, skipping everything else
+    /// Indented lines right below an item continue its content on a new line.
+    /// Inline `!h`, `@today`, `~daily`, `~30m`, `!remind1h` and `#tag` tokens are
+    /// parsed as in quick-add.
+    pub fn import_markdown(content: &str) -> Vec<Task> {
+        let now = chrono::Local::now();
+        let mut items: Vec<(bool, String)> = Vec::new();
+        let mut in_item = false;
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+            let item = trimmed.strip_prefix("- [").and_then(|item| {
+                if let Some(text) = item.strip_prefix(" ]") {
+                    Some((false, text))
+                } else {
+                    item.strip_prefix("x]")
+                        .or_else(|| item.strip_prefix("X]"))
+                        .map(|text| (true, text))
+                }
+            });
+
+            match item {
+                Some((done, text)) => {
+                    items.push((done, text.trim().to_string()));
+                    in_item = true;
+                }
+                None if in_item && line.starts_with([' ', '\t']) && !trimmed.is_empty() => {
+                    if let Some((_, text)) = items.last_mut() {
+                        text.push('\n');
+                        text.push_str(trimmed);
+                    }
+                }
+                None => in_item = false,
+            }
+        }
+
+        items
+            .into_iter()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .map(|(done, text)| {
+                let mut task = parse_task(&text, now);
+                if done {
+                    task.complete();
+                }
+                task
+            })
+            .collect()
+    }
+
+    /// Write the Markdown checklist to a file
+    #[allow(dead_code)]
+    pub fn export_markdown_to(tasks: &[Task], path: &Path) -> Result<(), String> {
+        fs::write(path, Self::export_markdown(tasks))
+            .map_err(|e| format!("Failed to write Markdown export: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::local_datetime;
+    use chrono::{NaiveDate, NaiveTime};
+
+    fn sample_task() -> Task {
+        let mut task = Task::new("Write report");
+        task.priority = TaskPriority::High;
+        task.due_date = local_datetime(
+            NaiveDate::from_ymd_opt(2025, 3, 14).unwrap(),
+            NaiveTime::MIN,
+        );
+        task.recurrence = Some(Recurrence::Weekly);
+        task.effort = Some(90);
+        task.reminder_lead = Some(30);
+        task.tags = vec!["work".into()];
+        task
+    }
+
+    #[test]
+    fn line_carries_every_token() {
+        assert_eq!(
+            markdown_line(&sample_task()),
+            "- [ ] Write report !h @2025-03-14 ~weekly ~1h30m !remind30m #work"
+        );
+    }
+
+    #[test]
+    fn export_groups_pending_and_overcome() {
+        let mut done = Task::new("Buy milk\nand bread");
+        done.complete();
+        let exported = TaskStorage::export_markdown(&[sample_task(), done]);
+        assert_eq!(
+            exported,
+            "## Pending\n\n\
+             - [ ] Write report !h @2025-03-14 ~weekly ~1h30m !remind30m #work\n\
+             \n## Overcome\n\n\
+             - [x] Buy milk\n  and bread\n"
+        );
+    }
+
+    #[test]
+    fn export_then_import_keeps_the_fields() {
+        let original = sample_task();
+        let exported = TaskStorage::export_markdown(std::slice::from_ref(&original));
+        let imported = TaskStorage::import_markdown(&exported);
+
+        assert_eq!(imported.len(), 1);
+        let task = &imported[0];
+        assert_eq!(task.content, original.content);
+        assert_eq!(task.priority, original.priority);
+        assert_eq!(task.due_date, original.due_date);
+        assert_eq!(task.recurrence, original.recurrence);
+        assert_eq!(task.effort, original.effort);
+        assert_eq!(task.reminder_lead, original.reminder_lead);
+        assert_eq!(task.tags, original.tags);
+    }
+}
@@ -1,6 +1,27 @@
 // Infrastructure Layer - External systems and persistence
 // This layer handles file I/O, network, and other external concerns
 
+mod csv;
+mod hotkey;
+mod ics;
+mod markdown;
+mod notifier;
+mod settings;
+mod sound;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod storage;
+mod tray;
+mod watcher;
+mod window_state;
 
+pub use hotkey::*;
+pub use notifier::*;
+pub use settings::*;
+pub use sound::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite::*;
 pub use storage::*;
+pub use tray::*;
+pub use watcher::*;
+pub use window_state::*;
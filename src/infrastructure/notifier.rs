@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// Settings for due date reminders, read from `notifications.toml`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Send reminders at all
+    pub enabled: bool,
+    /// How often to look for tasks that have come due, in seconds
+    pub interval_secs: u64,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 60,
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Load settings from a TOML file with `enabled` and `interval_secs` keys
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read notifications file: {}", e))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse notifications file: {}", e))
+    }
+}
+
+/// Sends desktop notifications through the operating system
+pub struct Notifier;
+
+impl Notifier {
+    /// A notifier, if this build can send notifications
+    #[cfg(feature = "notifications")]
+    pub fn new() -> Result<Self, String> {
+        Ok(Self)
+    }
+
+    /// Without the `notifications` feature there is no notification backend
+    #[cfg(not(feature = "notifications"))]
+    pub fn new() -> Result<Self, String> {
+        Err("Built without the notifications feature".to_string())
+    }
+
+    /// Show a notification; may block briefly, so call it off the main thread
+    #[cfg(feature = "notifications")]
+    pub fn send(&self, summary: &str, body: &str) -> Result<(), String> {
+        notify_rust::Notification::new()
+            .appname("Waloyo")
+            .summary(summary)
+            .body(body)
+            .show()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to show notification: {}", e))
+    }
+
+    #[cfg(not(feature = "notifications"))]
+    pub fn send(&self, _summary: &str, _body: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
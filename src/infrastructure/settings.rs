@@ -0,0 +1,83 @@
+use crate::domain::{AnimationSpeed, Settings};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// On-disk form of `Settings`; missing keys keep their defaults
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsData {
+    animation_speed: String,
+    notifications: bool,
+}
+
+impl Default for SettingsData {
+    fn default() -> Self {
+        Self::from(&Settings::default())
+    }
+}
+
+impl From<&Settings> for SettingsData {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            animation_speed: animation_speed_to_str(settings.animation_speed).to_string(),
+            notifications: settings.notifications,
+        }
+    }
+}
+
+fn animation_speed_to_str(speed: AnimationSpeed) -> &'static str {
+    match speed {
+        AnimationSpeed::Slow => "slow",
+        AnimationSpeed::Normal => "normal",
+        AnimationSpeed::Fast => "fast",
+    }
+}
+
+fn animation_speed_from_str(s: &str) -> AnimationSpeed {
+    match s {
+        "slow" => AnimationSpeed::Slow,
+        "fast" => AnimationSpeed::Fast,
+        _ => AnimationSpeed::Normal,
+    }
+}
+
+/// Reads and writes the app settings in `settings.json`, apart from the tasks
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The settings in the data directory
+    pub fn in_data_dir() -> Self {
+        Self::new(super::data_dir().join("settings.json"))
+    }
+
+    /// The saved settings, or the defaults if none have been saved yet
+    pub fn load(&self) -> Result<Settings, String> {
+        if !self.path.exists() {
+            return Ok(Settings::default());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| format!("Failed to read settings: {}", e))?;
+        let data: SettingsData = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse settings: {}", e))?;
+        Ok(Settings {
+            animation_speed: animation_speed_from_str(&data.animation_speed),
+            notifications: data.notifications,
+        })
+    }
+
+    pub fn save(&self, settings: &Settings) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(&SettingsData::from(settings))
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(&self.path, content).map_err(|e| format!("Failed to write settings: {}", e))
+    }
+}
@@ -0,0 +1,148 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Settings for completion sounds, read from `sound.toml`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    /// Play sounds at all; off unless the user opts in
+    pub enabled: bool,
+    /// Loudness from 0.0 (silent) to 1.0
+    pub volume: f32,
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 0.5,
+        }
+    }
+}
+
+impl SoundConfig {
+    /// Load settings from a TOML file with `enabled` and `volume` keys
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read sound file: {}", e))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse sound file: {}", e))
+    }
+}
+
+/// A moment worth a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundCue {
+    /// A task was overcome: a soft patter of rain
+    Completed,
+    /// The last task was overcome: a brighter chime as the sky clears
+    AllOvercome,
+}
+
+impl SoundCue {
+    /// File name, without extension, looked up in the sounds directory
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn file_stem(self) -> &'static str {
+        match self {
+            SoundCue::Completed => "complete",
+            SoundCue::AllOvercome => "clear-sky",
+        }
+    }
+
+    /// Notes of the built-in fallback as (frequency in Hz, start in ms, length in ms)
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn fallback_notes(self) -> &'static [(f32, u64, u64)] {
+        match self {
+            SoundCue::Completed => &[(880.0, 0, 90), (660.0, 70, 140)],
+            SoundCue::AllOvercome => &[(523.3, 0, 160), (659.3, 120, 160), (784.0, 240, 320)],
+        }
+    }
+}
+
+/// Plays cue sounds on the default output device
+/// A file named after the cue in the sounds directory (`complete.wav`,
+/// `clear-sky.ogg`, ...) replaces the built-in tones.
+#[cfg_attr(not(feature = "audio"), allow(dead_code))]
+pub struct SoundPlayer {
+    #[cfg(feature = "audio")]
+    _stream: rodio::OutputStream,
+    #[cfg(feature = "audio")]
+    handle: rodio::OutputStreamHandle,
+    directory: PathBuf,
+    volume: f32,
+}
+
+impl SoundPlayer {
+    const EXTENSIONS: [&'static str; 4] = ["wav", "ogg", "mp3", "flac"];
+
+    /// Open the default output device; must stay on the thread that opened it
+    #[cfg(feature = "audio")]
+    pub fn open(directory: PathBuf, volume: f32) -> Result<Self, String> {
+        let (stream, handle) = rodio::OutputStream::try_default()
+            .map_err(|e| format!("No audio output device: {}", e))?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+            directory,
+            volume: volume.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Without the `audio` feature there is no playback backend
+    #[cfg(not(feature = "audio"))]
+    pub fn open(directory: PathBuf, _volume: f32) -> Result<Self, String> {
+        Err(format!(
+            "Cannot play sounds from {}: built without the audio feature",
+            directory.display()
+        ))
+    }
+
+    /// The user's file for `cue`, if there is one
+    #[cfg_attr(not(feature = "audio"), allow(dead_code))]
+    fn sound_file(&self, cue: SoundCue) -> Option<PathBuf> {
+        Self::EXTENSIONS
+            .iter()
+            .map(|ext| self.directory.join(format!("{}.{}", cue.file_stem(), ext)))
+            .find(|path| path.exists())
+    }
+
+    /// Start playing `cue` without waiting for it to finish
+    /// Files that fail to decode fall back to the built-in tones.
+    #[cfg(feature = "audio")]
+    pub fn play(&self, cue: SoundCue) {
+        use rodio::Source;
+
+        if let Some(path) = self.sound_file(cue) {
+            match self.play_file(&path) {
+                Ok(()) => return,
+                Err(e) => eprintln!("Failed to play {}: {}", path.display(), e),
+            }
+        }
+
+        for &(frequency, start, length) in cue.fallback_notes() {
+            let note = rodio::source::SineWave::new(frequency)
+                .take_duration(std::time::Duration::from_millis(length))
+                .fade_in(std::time::Duration::from_millis(10))
+                .amplify(self.volume * 0.2)
+                .delay(std::time::Duration::from_millis(start));
+            if let Err(e) = self.handle.play_raw(note) {
+                eprintln!("Failed to play sound: {}", e);
+                return;
+            }
+        }
+    }
+
+    #[cfg(feature = "audio")]
+    fn play_file(&self, path: &Path) -> Result<(), String> {
+        use rodio::Source;
+
+        let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+        let source =
+            rodio::Decoder::new(std::io::BufReader::new(file)).map_err(|e| e.to_string())?;
+        self.handle
+            .play_raw(source.amplify(self.volume).convert_samples())
+            .map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(feature = "audio"))]
+    pub fn play(&self, _cue: SoundCue) {}
+}
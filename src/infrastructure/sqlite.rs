@@ -0,0 +1,364 @@
+use super::storage::{
+    group_mode_from_str, group_mode_to_str, sort_mode_from_str, sort_mode_to_str, theme_from_str,
+    theme_to_str,
+};
+use super::{SubtaskData, TaskData, TaskStorage};
+use crate::domain::{Preferences, Task, TaskId, TaskRepository};
+use chrono::{DateTime, Local};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY,
+        position INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        notes TEXT,
+        state TEXT NOT NULL,
+        priority TEXT NOT NULL,
+        due_date TEXT,
+        tags TEXT NOT NULL,
+        recurrence TEXT,
+        subtasks TEXT NOT NULL,
+        created_at TEXT,
+        updated_at TEXT,
+        pinned INTEGER NOT NULL DEFAULT 0,
+        completion_note TEXT,
+        effort INTEGER,
+        reminder_lead INTEGER,
+        due_has_time INTEGER,
+        notified_at TEXT
+    );
+    CREATE TABLE IF NOT EXISTS archive (
+        id INTEGER PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS preferences (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+const TASK_COLUMNS: &str = "id, position, content, notes, state, priority, due_date, \
+     tags, recurrence, subtasks, created_at, updated_at, pinned, completion_note, effort, \
+     reminder_lead, due_has_time, notified_at";
+
+/// Columns added to `tasks` after its first release, with their definitions,
+/// added to older databases when they are opened
+const ADDED_COLUMNS: [(&str, &str); 6] = [
+    ("pinned", "INTEGER NOT NULL DEFAULT 0"),
+    ("completion_note", "TEXT"),
+    ("effort", "INTEGER"),
+    ("reminder_lead", "INTEGER"),
+    ("due_has_time", "INTEGER"),
+    ("notified_at", "TEXT"),
+];
+
+fn parse_timestamp(value: Option<String>) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(&value?)
+        .ok()
+        .map(|date| date.with_timezone(&Local))
+}
+
+/// Read a task row in `TASK_COLUMNS` order
+fn task_from_row(row: &Row) -> rusqlite::Result<TaskData> {
+    let tags: String = row.get(7)?;
+    let subtasks: String = row.get(9)?;
+    let due_date = row
+        .get::<_, Option<String>>(6)?
+        .and_then(|due| DateTime::parse_from_rfc3339(&due).ok());
+    // Rows saved before the column existed marked date-only due dates with midnight
+    let due_has_time = row
+        .get::<_, Option<bool>>(16)?
+        .unwrap_or_else(|| due_date.is_some_and(|due| due.time() != chrono::NaiveTime::MIN));
+    Ok(TaskData {
+        id: row.get::<_, i64>(0)? as u64,
+        content: row.get(2)?,
+        notes: row.get(3)?,
+        state: row.get(4)?,
+        priority: row.get(5)?,
+        due_date,
+        due_has_time,
+        tags: serde_json::from_str(&tags).unwrap_or_default(),
+        recurrence: row.get(8)?,
+        subtasks: serde_json::from_str::<Vec<SubtaskData>>(&subtasks).unwrap_or_default(),
+        pinned: row.get(12)?,
+        completion_note: row.get(13)?,
+        effort: row.get(14)?,
+        reminder_lead: row.get(15)?,
+        notified_at: parse_timestamp(row.get(17)?),
+        created_at: parse_timestamp(row.get(10)?),
+        updated_at: parse_timestamp(row.get(11)?),
+    })
+}
+
+/// Task storage in a SQLite database, written incrementally
+/// Only rows that changed since the last load or save are upserted or deleted.
+pub struct SqliteStorage {
+    connection: Connection,
+    /// Serialized form of each row as last read or written, keyed by task id
+    saved_rows: RefCell<HashMap<u64, String>>,
+    /// Set when the database file didn't exist before it was opened
+    fresh: bool,
+}
+
+impl SqliteStorage {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+        }
+        let fresh = !path.exists();
+        let connection = Connection::open(path)
+            .map_err(|e| format!("Failed to open database {}: {}", path.display(), e))?;
+        connection
+            .execute_batch(SCHEMA)
+            .map_err(|e| format!("Failed to create database schema: {}", e))?;
+
+        // Databases created by older versions lack the columns added since
+        for (column, definition) in ADDED_COLUMNS {
+            let exists: i64 = connection
+                .query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info('tasks') WHERE name = ?1",
+                    [column],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Failed to read database schema: {}", e))?;
+            if exists == 0 {
+                connection
+                    .execute_batch(&format!(
+                        "ALTER TABLE tasks ADD COLUMN {} {}",
+                        column, definition
+                    ))
+                    .map_err(|e| format!("Failed to upgrade database schema: {}", e))?;
+            }
+        }
+
+        Ok(Self {
+            connection,
+            saved_rows: RefCell::new(HashMap::new()),
+            fresh,
+        })
+    }
+
+    /// Open the database beside a JSON storage file (`tasks.json` → `tasks.db`),
+    /// importing the JSON tasks the first time, while the database is still empty
+    pub fn open_beside(json: &TaskStorage) -> Result<Self, String> {
+        let mut storage = Self::open(&Self::database_path(json))?;
+
+        let count: i64 = storage
+            .connection
+            .query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to count tasks: {}", e))?;
+        if count == 0 && json.file_path().exists() {
+            let (tasks, preferences) = json.load()?;
+            storage.save(&tasks, &preferences)?;
+            storage.fresh = false;
+            eprintln!(
+                "Imported {} tasks from {} into the database",
+                tasks.len(),
+                json.file_path().display()
+            );
+        }
+
+        Ok(storage)
+    }
+
+    fn database_path(json: &TaskStorage) -> PathBuf {
+        json.file_path().with_extension("db")
+    }
+
+    fn row_key(position: usize, data: &TaskData) -> String {
+        format!(
+            "{}:{}",
+            position,
+            serde_json::to_string(data).unwrap_or_default()
+        )
+    }
+
+    fn preference(&self, key: &str) -> Result<Option<String>, String> {
+        self.connection
+            .query_row(
+                "SELECT value FROM preferences WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read preference {}: {}", key, e))
+    }
+}
+
+impl TaskRepository for SqliteStorage {
+    fn is_fresh(&self) -> bool {
+        self.fresh
+    }
+
+    fn load(&self) -> Result<(Vec<Task>, Preferences), String> {
+        let mut statement = self
+            .connection
+            .prepare(&format!(
+                "SELECT {} FROM tasks ORDER BY position",
+                TASK_COLUMNS
+            ))
+            .map_err(|e| format!("Failed to query tasks: {}", e))?;
+        let rows: Vec<TaskData> = statement
+            .query_map([], task_from_row)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| format!("Failed to read tasks: {}", e))?;
+
+        let mut saved_rows = self.saved_rows.borrow_mut();
+        saved_rows.clear();
+        for (position, data) in rows.iter().enumerate() {
+            saved_rows.insert(data.id, Self::row_key(position, data));
+        }
+
+        let preferences = Preferences {
+            sort_mode: sort_mode_from_str(&self.preference("sort_mode")?.unwrap_or_default()),
+            group_mode: group_mode_from_str(&self.preference("group_mode")?.unwrap_or_default()),
+            theme: theme_from_str(&self.preference("theme")?.unwrap_or_default()),
+            reduced_motion: self.preference("reduced_motion")?.as_deref() == Some("true"),
+            completed_collapsed: self
+                .preference("completed_collapsed")?
+                .map(|value| value == "true"),
+            focus_mode: self.preference("focus_mode")?.as_deref() == Some("true"),
+            completed_oldest_first: self.preference("completed_oldest_first")?.as_deref()
+                == Some("true"),
+            tag_colors: self
+                .preference("tag_colors")?
+                .and_then(|value| serde_json::from_str(&value).ok())
+                .unwrap_or_default(),
+        };
+        // Keep new ids clear of archived tasks too, so archiving never replaces one
+        let archived_max: Option<i64> = self
+            .connection
+            .query_row("SELECT MAX(id) FROM archive", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read the archive: {}", e))?;
+        if let Some(id) = archived_max {
+            TaskId::bump_past(id as u64);
+        }
+        let tasks = rows.into_iter().map(|t| t.into_task()).collect();
+
+        Ok((tasks, preferences))
+    }
+
+    fn save(&self, tasks: &[Task], preferences: &Preferences) -> Result<(), String> {
+        let transaction = self
+            .connection
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        let mut saved_rows = self.saved_rows.borrow_mut();
+        let mut current = HashMap::with_capacity(tasks.len());
+
+        for (position, task) in tasks.iter().enumerate() {
+            let data = TaskData::from(task);
+            let key = Self::row_key(position, &data);
+            if saved_rows.get(&data.id) != Some(&key) {
+                transaction
+                    .execute(
+                        &format!(
+                            "INSERT OR REPLACE INTO tasks ({}) \
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+                            TASK_COLUMNS
+                        ),
+                        params![
+                            data.id as i64,
+                            position as i64,
+                            data.content,
+                            data.notes,
+                            data.state,
+                            data.priority,
+                            data.due_date.map(|d| d.to_rfc3339()),
+                            serde_json::to_string(&data.tags).unwrap_or_default(),
+                            data.recurrence,
+                            serde_json::to_string(&data.subtasks).unwrap_or_default(),
+                            data.created_at.map(|d| d.to_rfc3339()),
+                            data.updated_at.map(|d| d.to_rfc3339()),
+                            data.pinned,
+                            data.completion_note,
+                            data.effort,
+                            data.reminder_lead,
+                            data.due_has_time,
+                            data.notified_at.map(|d| d.to_rfc3339()),
+                        ],
+                    )
+                    .map_err(|e| format!("Failed to save task {}: {}", data.id, e))?;
+            }
+            current.insert(data.id, key);
+        }
+
+        for id in saved_rows.keys().filter(|id| !current.contains_key(id)) {
+            transaction
+                .execute("DELETE FROM tasks WHERE id = ?1", [*id as i64])
+                .map_err(|e| format!("Failed to delete task {}: {}", id, e))?;
+        }
+
+        let preference_rows = [
+            (
+                "sort_mode",
+                sort_mode_to_str(preferences.sort_mode).to_string(),
+            ),
+            (
+                "group_mode",
+                group_mode_to_str(preferences.group_mode).to_string(),
+            ),
+            ("theme", theme_to_str(preferences.theme).to_string()),
+            ("reduced_motion", preferences.reduced_motion.to_string()),
+            ("focus_mode", preferences.focus_mode.to_string()),
+            (
+                "completed_oldest_first",
+                preferences.completed_oldest_first.to_string(),
+            ),
+            (
+                "tag_colors",
+                serde_json::to_string(&preferences.tag_colors).unwrap_or_default(),
+            ),
+        ];
+        for (key, value) in preference_rows {
+            transaction
+                .execute(
+                    "INSERT OR REPLACE INTO preferences (key, value) VALUES (?1, ?2)",
+                    params![key, value],
+                )
+                .map_err(|e| format!("Failed to save preference {}: {}", key, e))?;
+        }
+        match preferences.completed_collapsed {
+            Some(collapsed) => transaction.execute(
+                "INSERT OR REPLACE INTO preferences (key, value) VALUES ('completed_collapsed', ?1)",
+                [collapsed.to_string()],
+            ),
+            None => transaction.execute(
+                "DELETE FROM preferences WHERE key = 'completed_collapsed'",
+                [],
+            ),
+        }
+        .map_err(|e| format!("Failed to save preference completed_collapsed: {}", e))?;
+
+        transaction
+            .commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        *saved_rows = current;
+        Ok(())
+    }
+
+    fn archive(&self, tasks: &[Task]) -> Result<(), String> {
+        for task in tasks {
+            let data = serde_json::to_string(&TaskData::from(task))
+                .map_err(|e| format!("Failed to serialize archive: {}", e))?;
+            self.connection
+                .execute(
+                    "INSERT OR REPLACE INTO archive (id, data) VALUES (?1, ?2)",
+                    params![task.id.0 as i64, data],
+                )
+                .map_err(|e| format!("Failed to archive task {}: {}", task.id.0, e))?;
+        }
+        Ok(())
+    }
+
+    /// Backups are JSON files, so restore through the JSON reader
+    fn restore_from(&self, path: &Path) -> Result<Vec<Task>, String> {
+        TaskStorage::with_path(path.to_path_buf()).restore_from(path)
+    }
+}
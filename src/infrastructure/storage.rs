@@ -1,9 +1,38 @@
-use crate::domain::{Task, TaskId, TaskState};
+use crate::domain::{
+    local_datetime, GroupMode, Preferences, Recurrence, SortMode, Subtask, Task, TaskId,
+    TaskRepository, TaskState, ThemeVariant,
+};
 use gpui::SharedString;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Current on-disk format version
+/// - 0: Files written before the version field existed
+/// - 1: Initial format
+/// - 2: Adds created_at and updated_at timestamps
+/// - 3: Due dates may carry a time of day; date-only means midnight
+/// - 4: Adds due_has_time, so a due time of midnight is a time like any other
+const CURRENT_VERSION: u32 = 4;
+
+/// Upgrade steps, indexed by the version they upgrade from
+const MIGRATIONS: [fn(StorageData) -> StorageData; CURRENT_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+];
+
+/// Serializable version of Subtask for JSON persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtaskData {
+    pub id: u64,
+    pub content: String,
+    #[serde(default)]
+    pub done: bool,
+}
 
 /// Serializable version of Task for JSON persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,7 +43,31 @@ pub struct TaskData {
     pub notes: Option<String>,
     pub state: String,
     pub priority: String,
-    pub due_date: Option<chrono::DateTime<chrono::Local>>,
+    /// Kept in the offset it was written in, so a date-only due date keeps its
+    /// day when the file is opened in another timezone
+    pub due_date: Option<chrono::DateTime<chrono::FixedOffset>>,
+    #[serde(default)]
+    pub due_has_time: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    #[serde(default)]
+    pub subtasks: Vec<SubtaskData>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub completion_note: Option<String>,
+    #[serde(default)]
+    pub effort: Option<u32>,
+    #[serde(default)]
+    pub reminder_lead: Option<u32>,
+    #[serde(default)]
+    pub notified_at: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(default)]
+    pub created_at: Option<chrono::DateTime<chrono::Local>>,
+    #[serde(default)]
+    pub updated_at: Option<chrono::DateTime<chrono::Local>>,
 }
 
 impl From<&Task> for TaskData {
@@ -25,38 +78,98 @@ impl From<&Task> for TaskData {
             notes: task.notes.clone(),
             state: match task.state {
                 TaskState::Pending => "pending".to_string(),
-                TaskState::Completing => "pending".to_string(),
+                TaskState::Completing => "completing".to_string(),
                 TaskState::Done => "done".to_string(),
             },
             priority: match task.priority {
                 crate::domain::TaskPriority::Low => "low".to_string(),
                 crate::domain::TaskPriority::Medium => "medium".to_string(),
                 crate::domain::TaskPriority::High => "high".to_string(),
+                crate::domain::TaskPriority::Urgent => "urgent".to_string(),
             },
-            due_date: task.due_date,
+            due_date: task.due_date.map(|due| due.fixed_offset()),
+            due_has_time: task.has_due_time(),
+            tags: task.tags.iter().map(|t| t.to_string()).collect(),
+            recurrence: task.recurrence.map(|recurrence| {
+                match recurrence {
+                    Recurrence::Daily => "daily",
+                    Recurrence::Weekly => "weekly",
+                    Recurrence::Monthly => "monthly",
+                }
+                .to_string()
+            }),
+            subtasks: task
+                .subtasks
+                .iter()
+                .map(|s| SubtaskData {
+                    id: s.id,
+                    content: s.content.clone(),
+                    done: s.done,
+                })
+                .collect(),
+            pinned: task.pinned,
+            completion_note: task.completion_note.clone(),
+            effort: task.effort,
+            reminder_lead: task.reminder_lead,
+            notified_at: task.notified_at,
+            created_at: Some(task.created_at),
+            updated_at: Some(task.updated_at),
         }
     }
 }
 
 impl TaskData {
+    /// Rebuild the task, keeping new ids from colliding with its own
     pub fn into_task(self) -> Task {
-        let now = Instant::now();
+        TaskId::bump_past(self.id);
+        let now = chrono::Local::now();
+        let created_at = self.created_at.unwrap_or(now);
         Task {
             id: TaskId(self.id),
             content: SharedString::from(self.content),
             notes: self.notes,
             state: match self.state.as_str() {
                 "done" => TaskState::Done,
+                "completing" => TaskState::Completing,
                 _ => TaskState::Pending,
             },
             priority: match self.priority.as_str() {
+                "urgent" => crate::domain::TaskPriority::Urgent,
                 "high" => crate::domain::TaskPriority::High,
                 "medium" => crate::domain::TaskPriority::Medium,
                 _ => crate::domain::TaskPriority::Low,
             },
-            due_date: self.due_date,
-            created_at: now,
-            updated_at: now,
+            due_date: self.due_date.and_then(|due| {
+                if self.due_has_time {
+                    Some(due.with_timezone(&chrono::Local))
+                } else {
+                    local_datetime(due.date_naive(), chrono::NaiveTime::MIN)
+                }
+            }),
+            due_has_time: self.due_has_time && self.due_date.is_some(),
+            subtasks: self
+                .subtasks
+                .into_iter()
+                .map(|s| Subtask {
+                    id: s.id,
+                    content: s.content,
+                    done: s.done,
+                })
+                .collect(),
+            tags: self.tags.into_iter().map(SharedString::from).collect(),
+            recurrence: match self.recurrence.as_deref() {
+                Some("daily") => Some(Recurrence::Daily),
+                Some("weekly") => Some(Recurrence::Weekly),
+                Some("monthly") => Some(Recurrence::Monthly),
+                _ => None,
+            },
+            pinned: self.pinned,
+            completion_note: self.completion_note,
+            effort: self.effort,
+            reminder_lead: self.reminder_lead,
+            notified_at: self.notified_at,
+            created_at,
+            updated_at: self.updated_at.unwrap_or(created_at),
         }
     }
 }
@@ -64,17 +177,308 @@ impl TaskData {
 /// Storage data format
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StorageData {
+    #[serde(default)]
     pub version: u32,
     pub tasks: Vec<TaskData>,
+    #[serde(default)]
+    pub sort_mode: String,
+    #[serde(default)]
+    pub group_mode: String,
+    #[serde(default)]
+    pub theme: String,
+    #[serde(default)]
+    pub reduced_motion: bool,
+    #[serde(default)]
+    pub completed_collapsed: Option<bool>,
+    #[serde(default)]
+    pub focus_mode: bool,
+    #[serde(default)]
+    pub completed_oldest_first: bool,
+    #[serde(default)]
+    pub tag_colors: HashMap<String, String>,
 }
 
 impl StorageData {
     pub fn new() -> Self {
         Self {
-            version: 1,
+            version: CURRENT_VERSION,
             tasks: Vec::new(),
+            sort_mode: sort_mode_to_str(SortMode::default()).to_string(),
+            group_mode: group_mode_to_str(GroupMode::default()).to_string(),
+            theme: theme_to_str(ThemeVariant::default()).to_string(),
+            reduced_motion: false,
+            completed_collapsed: None,
+            focus_mode: false,
+            completed_oldest_first: false,
+            tag_colors: HashMap::new(),
         }
     }
+
+    /// The stored form of the given tasks and preferences
+    pub fn from_state(tasks: &[Task], preferences: &Preferences) -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            tasks: tasks.iter().map(TaskData::from).collect(),
+            sort_mode: sort_mode_to_str(preferences.sort_mode).to_string(),
+            group_mode: group_mode_to_str(preferences.group_mode).to_string(),
+            theme: theme_to_str(preferences.theme).to_string(),
+            reduced_motion: preferences.reduced_motion,
+            completed_collapsed: preferences.completed_collapsed,
+            focus_mode: preferences.focus_mode,
+            completed_oldest_first: preferences.completed_oldest_first,
+            tag_colors: preferences.tag_colors.clone(),
+        }
+    }
+
+    /// Replace the text a user typed with placeholders of the same length,
+    /// keeping spaces so the shape of each task still shows
+    pub fn redact(&mut self) {
+        fn mask(text: &str) -> String {
+            text.chars()
+                .map(|c| if c.is_whitespace() { c } else { 'x' })
+                .collect()
+        }
+        for task in &mut self.tasks {
+            task.content = mask(&task.content);
+            task.notes = task.notes.as_deref().map(mask);
+            task.completion_note = task.completion_note.as_deref().map(mask);
+            for subtask in &mut task.subtasks {
+                subtask.content = mask(&subtask.content);
+            }
+        }
+    }
+}
+
+/// Bring data written by an older version up to the current format, one version at a time
+pub fn migrate(mut data: StorageData) -> StorageData {
+    if data.version > CURRENT_VERSION {
+        eprintln!(
+            "Storage version {} is newer than supported version {}; unknown fields will be dropped",
+            data.version, CURRENT_VERSION
+        );
+        return data;
+    }
+    while data.version < CURRENT_VERSION {
+        data = MIGRATIONS[data.version as usize](data);
+    }
+    data
+}
+
+/// Unversioned files share the v1 layout
+fn migrate_v0_to_v1(mut data: StorageData) -> StorageData {
+    data.version = 1;
+    data
+}
+
+/// v2 added timestamps; treat the load time as creation time for older tasks
+fn migrate_v1_to_v2(mut data: StorageData) -> StorageData {
+    let now = chrono::Local::now();
+    for task in &mut data.tasks {
+        task.created_at.get_or_insert(now);
+        task.updated_at.get_or_insert(now);
+    }
+    data.version = 2;
+    data
+}
+
+/// v3 gave due dates an optional time of day; older due dates never carried a
+/// chosen time, so move them to midnight to keep them date-only
+fn migrate_v2_to_v3(mut data: StorageData) -> StorageData {
+    for task in &mut data.tasks {
+        if let Some(due) = task.due_date {
+            task.due_date = local_datetime(due.date_naive(), chrono::NaiveTime::MIN)
+                .map(|midnight| midnight.fixed_offset())
+                .or(Some(due));
+        }
+    }
+    data.version = 3;
+    data
+}
+
+/// v4 records whether a due date has a time instead of reading it off the
+/// clock; in older files only a due time of midnight meant date-only
+fn migrate_v3_to_v4(mut data: StorageData) -> StorageData {
+    for task in &mut data.tasks {
+        task.due_has_time = task
+            .due_date
+            .is_some_and(|due| due.time() != chrono::NaiveTime::MIN);
+    }
+    data.version = 4;
+    data
+}
+
+/// Parse storage data task by task, skipping any task that can't be read
+/// Returns the data and the number of skipped tasks, or None if the JSON itself is broken.
+fn lenient_parse(content: &str) -> Option<(StorageData, usize)> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let object = value.as_object()?;
+    let field = |key: &str| {
+        object
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let mut skipped = 0;
+    let tasks = object
+        .get("tasks")
+        .and_then(|tasks| tasks.as_array())
+        .map(|tasks| {
+            tasks
+                .iter()
+                .filter_map(|task| {
+                    let data = serde_json::from_value::<TaskData>(task.clone()).ok();
+                    if data.is_none() {
+                        skipped += 1;
+                    }
+                    data
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let data = StorageData {
+        version: object
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or(0),
+        tasks,
+        sort_mode: field("sort_mode"),
+        group_mode: field("group_mode"),
+        theme: field("theme"),
+        reduced_motion: object
+            .get("reduced_motion")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        completed_collapsed: object.get("completed_collapsed").and_then(|v| v.as_bool()),
+        focus_mode: object
+            .get("focus_mode")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        completed_oldest_first: object
+            .get("completed_oldest_first")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        tag_colors: object
+            .get("tag_colors")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+    };
+    Some((data, skipped))
+}
+
+pub(super) fn sort_mode_to_str(mode: SortMode) -> &'static str {
+    match mode {
+        SortMode::Manual => "manual",
+        SortMode::PriorityDesc => "priority",
+        SortMode::DueDateAsc => "due_date",
+        SortMode::CreatedAsc => "created",
+    }
+}
+
+pub(super) fn sort_mode_from_str(value: &str) -> SortMode {
+    match value {
+        "priority" => SortMode::PriorityDesc,
+        "due_date" => SortMode::DueDateAsc,
+        "created" => SortMode::CreatedAsc,
+        _ => SortMode::Manual,
+    }
+}
+
+pub(super) fn group_mode_to_str(mode: GroupMode) -> &'static str {
+    match mode {
+        GroupMode::None => "none",
+        GroupMode::ByTag => "tag",
+        GroupMode::ByPriority => "priority",
+        GroupMode::ByDueDate => "due_date",
+    }
+}
+
+pub(super) fn group_mode_from_str(value: &str) -> GroupMode {
+    match value {
+        "tag" => GroupMode::ByTag,
+        "priority" => GroupMode::ByPriority,
+        "due_date" => GroupMode::ByDueDate,
+        _ => GroupMode::None,
+    }
+}
+
+pub(super) fn theme_to_str(theme: ThemeVariant) -> &'static str {
+    match theme {
+        ThemeVariant::Dark => "dark",
+        ThemeVariant::Light => "light",
+    }
+}
+
+pub(super) fn theme_from_str(value: &str) -> ThemeVariant {
+    match value {
+        "light" => ThemeVariant::Light,
+        _ => ThemeVariant::Dark,
+    }
+}
+
+/// The directory holding all of Waloyo's files: `$WALOYO_HOME` if set, else ~/.waloyo
+pub fn data_dir() -> PathBuf {
+    if let Some(home) = std::env::var_os("WALOYO_HOME").filter(|h| !h.is_empty()) {
+        return PathBuf::from(home);
+    }
+    let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push(".waloyo");
+    path
+}
+
+/// Timestamp in backup file names: RFC 3339 in UTC, with dashes for colons
+/// so the name is valid on every filesystem
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H-%M-%SZ";
+
+/// Minimum time between automatic backups
+const BACKUP_INTERVAL_HOURS: i64 = 24;
+
+/// How many automatic backups are kept
+const BACKUPS_TO_KEEP: usize = 10;
+
+/// Parse the timestamp out of a `<name>-<timestamp>.json` backup file name
+fn backup_timestamp(path: &Path, name: &str) -> Option<chrono::NaiveDateTime> {
+    let stamp = path
+        .file_name()?
+        .to_str()?
+        .strip_prefix(name)?
+        .strip_prefix('-')?
+        .strip_suffix(".json")?;
+    chrono::NaiveDateTime::parse_from_str(stamp, BACKUP_TIMESTAMP_FORMAT).ok()
+}
+
+/// The profile stored in the original `tasks.json`
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Profiles offered by the switcher even before they hold any tasks
+const BUILTIN_PROFILES: [&str; 3] = [DEFAULT_PROFILE, "work", "personal"];
+
+/// Profile names become file names, so keep them to a safe character set
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Open the storage for a profile: SQLite when built with the `sqlite` feature
+/// (falling back to JSON if the database can't be opened), JSON otherwise
+pub fn open_repository(profile: &str) -> Box<dyn TaskRepository> {
+    let json = TaskStorage::for_profile(profile);
+    #[cfg(feature = "sqlite")]
+    {
+        match super::SqliteStorage::open_beside(&json) {
+            Ok(sqlite) => return Box::new(sqlite),
+            Err(e) => eprintln!(
+                "Failed to open the task database, using JSON instead: {}",
+                e
+            ),
+        }
+    }
+    Box::new(json)
 }
 
 /// Task storage service for JSON file persistence
@@ -83,15 +487,89 @@ pub struct TaskStorage {
 }
 
 impl TaskStorage {
+    /// Render tasks and preferences as pretty JSON in the storage format, for
+    /// bug reports; `redacted` masks the text of every task
+    pub fn export_state_json(tasks: &[Task], preferences: &Preferences, redacted: bool) -> String {
+        let mut data = StorageData::from_state(tasks, preferences);
+        if redacted {
+            data.redact();
+        }
+        serde_json::to_string_pretty(&data).unwrap_or_else(|e| {
+            eprintln!("Failed to serialize state: {}", e);
+            String::new()
+        })
+    }
+
     pub fn new() -> Self {
-        let file_path = Self::get_storage_path();
+        Self::with_path(Self::get_storage_path())
+    }
+
+    /// Store tasks for a named profile in `<data dir>/profiles/<name>.json`
+    /// The default profile keeps using `<data dir>/tasks.json`.
+    pub fn for_profile(name: &str) -> Self {
+        if name == DEFAULT_PROFILE {
+            return Self::new();
+        }
+        let mut file_path = data_dir();
+        file_path.push("profiles");
+        file_path.push(format!("{}.json", name));
+        Self::with_path(file_path)
+    }
+
+    /// Every known profile: the built-in ones plus any found on disk, sorted
+    pub fn profiles() -> Vec<String> {
+        let mut profiles: Vec<String> = BUILTIN_PROFILES.iter().map(|p| p.to_string()).collect();
+        if let Ok(entries) = fs::read_dir(data_dir().join("profiles")) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                        if is_valid_profile_name(name) && !profiles.iter().any(|p| p == name) {
+                            profiles.push(name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        profiles[1..].sort();
+        profiles
+    }
+
+    fn active_profile_path() -> PathBuf {
+        data_dir().join("active_profile")
+    }
+
+    /// The profile that was active when the app last ran
+    pub fn active_profile() -> String {
+        fs::read_to_string(Self::active_profile_path())
+            .ok()
+            .map(|name| name.trim().to_string())
+            .filter(|name| is_valid_profile_name(name))
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+    }
+
+    /// Remember the active profile for the next launch
+    pub fn set_active_profile(name: &str) -> Result<(), String> {
+        fs::create_dir_all(data_dir())
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        fs::write(Self::active_profile_path(), name)
+            .map_err(|e| format!("Failed to save active profile: {}", e))
+    }
+
+    /// Store tasks in an arbitrary file instead of the data directory
+    pub fn with_path(file_path: PathBuf) -> Self {
         Self { file_path }
     }
 
+    /// The JSON file tasks are kept in
+    #[allow(dead_code)]
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
     fn get_storage_path() -> PathBuf {
-        // Use ~/.waloyo/tasks.json
-        let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
-        path.push(".waloyo");
+        // Use <data dir>/tasks.json
+        let mut path = data_dir();
         path.push("tasks.json");
         path
     }
@@ -104,38 +582,268 @@ impl TaskStorage {
         Ok(())
     }
 
-    /// Load tasks from storage
-    pub fn load(&self) -> Result<Vec<Task>, String> {
-        if !self.file_path.exists() {
+    /// Load tasks and preferences from any storage-format file
+    fn load_from(&self, path: &Path) -> Result<(Vec<Task>, Preferences), String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read storage file: {}", e))?;
+
+        let data = match serde_json::from_str::<StorageData>(&content) {
+            Ok(data) => data,
+            Err(e) if path == self.file_path => self.recover(&content, &e.to_string()),
+            Err(e) => return Err(format!("Failed to parse storage file: {}", e)),
+        };
+
+        if path == self.file_path && data.version != CURRENT_VERSION {
+            // Keep a copy in the old format before the next save rewrites it
+            if let Err(e) = self.backup() {
+                eprintln!("Failed to back up storage before migration: {}", e);
+            }
+        }
+
+        let data = migrate(data);
+        let preferences = Preferences {
+            sort_mode: sort_mode_from_str(&data.sort_mode),
+            group_mode: group_mode_from_str(&data.group_mode),
+            theme: theme_from_str(&data.theme),
+            reduced_motion: data.reduced_motion,
+            completed_collapsed: data.completed_collapsed,
+            focus_mode: data.focus_mode,
+            completed_oldest_first: data.completed_oldest_first,
+            tag_colors: data.tag_colors,
+        };
+        let tasks = data.tasks.into_iter().map(|t| t.into_task()).collect();
+
+        Ok((tasks, preferences))
+    }
+
+    /// Salvage what we can from a storage file that failed to parse, keeping the
+    /// original as `<file>.corrupt-<timestamp>` so the next save can't destroy it
+    fn recover(&self, content: &str, error: &str) -> StorageData {
+        eprintln!(
+            "Failed to parse storage file {}: {}",
+            self.file_path.display(),
+            error
+        );
+
+        let mut corrupt_path = self.file_path.clone().into_os_string();
+        corrupt_path.push(format!(
+            ".corrupt-{}",
+            chrono::Utc::now().format(BACKUP_TIMESTAMP_FORMAT)
+        ));
+        let corrupt_path = PathBuf::from(corrupt_path);
+
+        match lenient_parse(content) {
+            Some((data, skipped)) => {
+                match fs::copy(&self.file_path, &corrupt_path) {
+                    Ok(_) => eprintln!(
+                        "Recovered {} tasks ({} unreadable); the original was copied to {}",
+                        data.tasks.len(),
+                        skipped,
+                        corrupt_path.display()
+                    ),
+                    Err(e) => eprintln!("Failed to preserve the original storage file: {}", e),
+                }
+                data
+            }
+            None => {
+                match fs::rename(&self.file_path, &corrupt_path) {
+                    Ok(_) => eprintln!(
+                        "Starting with an empty task list; the unreadable file was moved to {}",
+                        corrupt_path.display()
+                    ),
+                    Err(e) => eprintln!("Failed to preserve the unreadable storage file: {}", e),
+                }
+                StorageData::new()
+            }
+        }
+    }
+
+    /// `archive.json` beside the storage file (`<profile>-archive.json` for profiles)
+    fn archive_path(&self) -> PathBuf {
+        let name = self.backup_name();
+        if name == "tasks" {
+            self.file_path.with_file_name("archive.json")
+        } else {
+            self.file_path
+                .with_file_name(format!("{}-archive.json", name))
+        }
+    }
+
+    /// The archived tasks, none if nothing has been archived yet
+    fn read_archive(&self) -> Result<Vec<TaskData>, String> {
+        let path = self.archive_path();
+        if !path.exists() {
             return Ok(Vec::new());
         }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read archive file: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse archive file: {}", e))
+    }
+
+    fn backup_dir(&self) -> PathBuf {
+        self.file_path.with_file_name("backups")
+    }
+
+    /// Backups are named after the storage file, e.g. `tasks` or a profile name
+    fn backup_name(&self) -> String {
+        self.file_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "tasks".to_string())
+    }
+
+    /// Backup files, newest first
+    /// Names embed a UTC timestamp, so lexical order is chronological order.
+    fn backup_files(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(self.backup_dir()) else {
+            return Vec::new();
+        };
+        let name = self.backup_name();
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| backup_timestamp(path, &name).is_some())
+            .collect();
+        files.sort();
+        files.reverse();
+        files
+    }
 
-        let content = fs::read_to_string(&self.file_path)
-            .map_err(|e| format!("Failed to read storage file: {}", e))?;
+    /// Copy the current storage file to `backups/<name>-<timestamp>.json`
+    pub fn backup(&self) -> Result<PathBuf, String> {
+        let backup_dir = self.backup_dir();
+        fs::create_dir_all(&backup_dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
 
-        let data: StorageData = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse storage file: {}", e))?;
+        let stamp = chrono::Utc::now().format(BACKUP_TIMESTAMP_FORMAT);
+        let backup_path = backup_dir.join(format!("{}-{}.json", self.backup_name(), stamp));
+        fs::copy(&self.file_path, &backup_path)
+            .map_err(|e| format!("Failed to back up storage file: {}", e))?;
 
-        Ok(data.tasks.into_iter().map(|t| t.into_task()).collect())
+        Ok(backup_path)
     }
 
-    /// Save tasks to storage
-    pub fn save(&self, tasks: &[Task]) -> Result<(), String> {
+    /// Delete all but the `keep` most recent backups, returning how many were removed
+    pub fn prune_backups(&self, keep: usize) -> Result<usize, String> {
+        let stale = self.backup_files().into_iter().skip(keep);
+        let mut removed = 0;
+        for path in stale {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove backup {}: {}", path.display(), e))?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Back up the storage file if the newest backup is older than `BACKUP_INTERVAL_HOURS`
+    fn backup_if_due(&self) -> Result<(), String> {
+        if !self.file_path.exists() {
+            return Ok(());
+        }
+
+        let newest = self
+            .backup_files()
+            .first()
+            .and_then(|p| backup_timestamp(p, &self.backup_name()));
+        let is_due = newest.is_none_or(|stamp| {
+            chrono::Utc::now().naive_utc() - stamp >= chrono::Duration::hours(BACKUP_INTERVAL_HOURS)
+        });
+        if is_due {
+            self.backup()?;
+            self.prune_backups(BACKUPS_TO_KEEP)?;
+        }
+        Ok(())
+    }
+
+    /// Write to a temp file beside the storage file, fsync it, then rename it over
+    /// the real file, so a crash mid-write never leaves tasks.json half written
+    fn write_atomically(&self, content: &[u8]) -> Result<(), String> {
+        let mut tmp_path = self.file_path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut file = fs::File::create(&tmp_path)
+            .map_err(|e| format!("Failed to create temp file {}: {}", tmp_path.display(), e))?;
+        file.write_all(content)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| format!("Failed to write temp file {}: {}", tmp_path.display(), e))?;
+
+        drop(file);
+
+        // The temp file is kept on failure so its contents are not lost
+        fs::rename(&tmp_path, &self.file_path).map_err(|e| {
+            format!(
+                "Failed to move {} to {}: {}",
+                tmp_path.display(),
+                self.file_path.display(),
+                e
+            )
+        })?;
+
+        Ok(())
+    }
+}
+
+impl TaskRepository for TaskStorage {
+    /// Load tasks and preferences from storage
+    /// New ids are kept clear of archived tasks as well as listed ones, so
+    /// archiving a new task never replaces an older archived one.
+    fn load(&self) -> Result<(Vec<Task>, Preferences), String> {
+        match self.read_archive() {
+            Ok(archived) => {
+                if let Some(id) = archived.iter().map(|t| t.id).max() {
+                    TaskId::bump_past(id);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+        if !self.file_path.exists() {
+            return Ok((Vec::new(), Preferences::default()));
+        }
+        self.load_from(&self.file_path)
+    }
+
+    /// Save tasks and preferences to storage
+    fn save(&self, tasks: &[Task], preferences: &Preferences) -> Result<(), String> {
         self.ensure_directory()
             .map_err(|e| format!("Failed to create storage directory: {}", e))?;
 
-        let data = StorageData {
-            version: 1,
-            tasks: tasks.iter().map(TaskData::from).collect(),
-        };
+        if let Err(e) = self.backup_if_due() {
+            eprintln!("Failed to back up tasks: {}", e);
+        }
 
+        let data = StorageData::from_state(tasks, preferences);
         let content = serde_json::to_string_pretty(&data)
             .map_err(|e| format!("Failed to serialize tasks: {}", e))?;
 
-        fs::write(&self.file_path, content)
-            .map_err(|e| format!("Failed to write storage file: {}", e))?;
+        self.write_atomically(content.as_bytes())
+    }
 
-        Ok(())
+    /// Append tasks to the archive, replacing any earlier copy of the same task
+    fn archive(&self, tasks: &[Task]) -> Result<(), String> {
+        let path = self.archive_path();
+        let mut archived = self.read_archive()?;
+
+        archived.retain(|old| !tasks.iter().any(|t| t.id.0 == old.id));
+        archived.extend(tasks.iter().map(TaskData::from));
+
+        let content = serde_json::to_string_pretty(&archived)
+            .map_err(|e| format!("Failed to serialize archive: {}", e))?;
+        self.ensure_directory()
+            .map_err(|e| format!("Failed to create storage directory: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write archive file: {}", e))
+    }
+
+    /// Load the tasks from a backup file, e.g. one returned by `backup()`
+    fn restore_from(&self, path: &Path) -> Result<Vec<Task>, String> {
+        self.load_from(path).map(|(tasks, _)| tasks)
+    }
+
+    fn is_fresh(&self) -> bool {
+        !self.file_path.exists()
+    }
+
+    fn watch_path(&self) -> Option<&Path> {
+        Some(&self.file_path)
     }
 }
 
@@ -144,3 +852,291 @@ impl Default for TaskStorage {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory of its own for each test, under the system temp dir
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("waloyo-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn atomic_write_replaces_the_file_and_leaves_no_temp_file() {
+        let dir = scratch_dir("atomic-write");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+
+        fs::write(dir.join("tasks.json"), "old contents").unwrap();
+        storage.write_atomically(b"new contents").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("tasks.json")).unwrap(),
+            "new contents"
+        );
+        assert!(!dir.join("tasks.json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn saved_tasks_load_back() {
+        let dir = scratch_dir("save-load");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let tasks = vec![Task::new("Water the plants"), Task::new("Call mom")];
+
+        storage.save(&tasks, &Preferences::default()).unwrap();
+        let (loaded, _) = storage.load().unwrap();
+
+        let contents: Vec<&str> = loaded.iter().map(|t| t.content.as_ref()).collect();
+        assert_eq!(contents, ["Water the plants", "Call mom"]);
+        assert!(!dir.join("tasks.json.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pruning_keeps_the_newest_backups_of_this_file_only() {
+        let dir = scratch_dir("prune-backups");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let backups = dir.join("backups");
+        fs::create_dir_all(&backups).unwrap();
+        for name in [
+            "tasks-2025-03-01T09-00-00Z.json",
+            "tasks-2025-03-03T09-00-00Z.json",
+            "tasks-2025-03-02T09-00-00Z.json",
+            "tasks-2024-12-31T23-59-59Z.json",
+            "work-2025-01-01T00-00-00Z.json",
+            "tasks-not-a-timestamp.json",
+            "notes.txt",
+        ] {
+            fs::write(backups.join(name), "{}").unwrap();
+        }
+
+        assert_eq!(storage.prune_backups(2).unwrap(), 2);
+
+        let mut left: Vec<String> = fs::read_dir(&backups)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "notes.txt",
+                "tasks-2025-03-02T09-00-00Z.json",
+                "tasks-2025-03-03T09-00-00Z.json",
+                "tasks-not-a-timestamp.json",
+                "work-2025-01-01T00-00-00Z.json",
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// A file as version 1 wrote it: no timestamps, due dates at the moment they
+    /// were set
+    const V1_FILE: &str = r#"{
+        "version": 1,
+        "tasks": [
+            {
+                "id": 3,
+                "content": "Pay rent",
+                "state": "pending",
+                "priority": "high",
+                "due_date": "2025-03-14T15:30:00+00:00"
+            },
+            {
+                "id": 7,
+                "content": "Buy milk",
+                "state": "done",
+                "priority": "low",
+                "due_date": null
+            }
+        ],
+        "sort_mode": "priority"
+    }"#;
+
+    #[test]
+    fn version_1_data_migrates_to_the_current_format() {
+        let data: StorageData = serde_json::from_str(V1_FILE).unwrap();
+        let before = chrono::Local::now();
+        let data = migrate(data);
+
+        assert_eq!(data.version, CURRENT_VERSION);
+        assert_eq!(data.sort_mode, "priority");
+        for task in &data.tasks {
+            assert!(task.created_at.is_some_and(|at| at >= before));
+            assert_eq!(task.updated_at, task.created_at);
+        }
+
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        let midnight = local_datetime(day, chrono::NaiveTime::MIN).unwrap();
+        assert_eq!(data.tasks[0].due_date, Some(midnight.fixed_offset()));
+        assert!(!data.tasks[0].due_has_time);
+        assert_eq!(data.tasks[1].due_date, None);
+    }
+
+    #[test]
+    fn version_3_due_times_become_explicit() {
+        let content = r#"{
+            "version": 3,
+            "tasks": [
+                { "id": 1, "content": "Date only", "state": "pending", "priority": "low",
+                  "due_date": "2025-03-14T00:00:00+09:00" },
+                { "id": 2, "content": "Timed", "state": "pending", "priority": "low",
+                  "due_date": "2025-03-14T17:30:00+09:00" }
+            ]
+        }"#;
+        let data = migrate(serde_json::from_str(content).unwrap());
+        assert!(!data.tasks[0].due_has_time);
+        assert!(data.tasks[1].due_has_time);
+
+        let tasks: Vec<Task> = data.tasks.into_iter().map(TaskData::into_task).collect();
+        // Written in another timezone, the date-only task keeps its day
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        assert_eq!(
+            tasks[0].due_date,
+            local_datetime(day, chrono::NaiveTime::MIN)
+        );
+        assert!(!tasks[0].has_due_time());
+        let timed: chrono::DateTime<chrono::Local> = "2025-03-14T17:30:00+09:00".parse().unwrap();
+        assert_eq!(tasks[1].due_date, Some(timed));
+        assert!(tasks[1].has_due_time());
+    }
+
+    #[test]
+    fn due_time_of_midnight_survives_a_save() {
+        let dir = scratch_dir("midnight-due");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        let mut timed = Task::new("At midnight");
+        timed.set_due(local_datetime(day, chrono::NaiveTime::MIN), true);
+        let mut dated = Task::new("Some time that day");
+        dated.set_due(local_datetime(day, chrono::NaiveTime::MIN), false);
+
+        storage
+            .save(&[timed, dated], &Preferences::default())
+            .unwrap();
+        let (loaded, _) = storage.load().unwrap();
+
+        assert!(loaded[0].has_due_time());
+        assert!(!loaded[1].has_due_time());
+        assert_eq!(loaded[0].due_date, loaded[1].due_date);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn loading_a_version_1_file_backs_it_up_first() {
+        let dir = scratch_dir("migrate-v1");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        fs::write(dir.join("tasks.json"), V1_FILE).unwrap();
+
+        let (tasks, preferences) = storage.load().unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].id, TaskId(3));
+        assert!(!tasks[0].has_due_time());
+        assert!(tasks[1].is_done());
+        assert_eq!(preferences.sort_mode, SortMode::PriorityDesc);
+        let backups = storage.backup_files();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), V1_FILE);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Files in `dir` whose name starts with `tasks.json.corrupt-`
+    fn corrupt_copies(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("tasks.json.corrupt-"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn truncated_file_is_set_aside_and_the_list_starts_empty() {
+        let dir = scratch_dir("recover-truncated");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let truncated = &V1_FILE[..V1_FILE.len() / 2];
+        fs::write(dir.join("tasks.json"), truncated).unwrap();
+
+        let (tasks, _) = storage.load().unwrap();
+
+        assert!(tasks.is_empty());
+        assert!(!dir.join("tasks.json").exists());
+        let copies = corrupt_copies(&dir);
+        assert_eq!(copies.len(), 1);
+        assert_eq!(fs::read_to_string(&copies[0]).unwrap(), truncated);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unreadable_tasks_are_skipped_and_the_rest_salvaged() {
+        let dir = scratch_dir("recover-lenient");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let content = r#"{
+            "version": 3,
+            "tasks": [
+                { "id": 1, "content": "Kept", "state": "pending", "priority": "low", "due_date": null },
+                { "id": 2, "content": 42 },
+                { "id": 3, "content": "Also kept", "state": "done", "priority": "high", "due_date": null }
+            ],
+            "theme": "light"
+        }"#;
+        fs::write(dir.join("tasks.json"), content).unwrap();
+
+        let (data, skipped) = lenient_parse(content).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(data.tasks.len(), 2);
+        assert_eq!(data.theme, "light");
+
+        let (tasks, preferences) = storage.load().unwrap();
+        let contents: Vec<&str> = tasks.iter().map(|t| t.content.as_ref()).collect();
+        assert_eq!(contents, ["Kept", "Also kept"]);
+        assert_eq!(preferences.theme, ThemeVariant::Light);
+        assert!(dir.join("tasks.json").exists());
+        let copies = corrupt_copies(&dir);
+        assert_eq!(copies.len(), 1);
+        assert_eq!(fs::read_to_string(&copies[0]).unwrap(), content);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn broken_json_cannot_be_parsed_leniently() {
+        assert!(lenient_parse(&V1_FILE[..V1_FILE.len() / 2]).is_none());
+        assert!(lenient_parse("[1, 2, 3]").is_none());
+    }
+
+    #[test]
+    fn new_ids_stay_clear_of_archived_tasks() {
+        let dir = scratch_dir("archive-ids");
+        let storage = TaskStorage::with_path(dir.join("tasks.json"));
+        let mut old = Task::new("Cleared long ago");
+        old.id = TaskId(90_000);
+        old.complete();
+        storage.archive(&[old]).unwrap();
+        storage.save(&[], &Preferences::default()).unwrap();
+
+        let _ = TaskStorage::with_path(dir.join("tasks.json"))
+            .load()
+            .unwrap();
+        let mut new = Task::new("Cleared today");
+        assert!(new.id.0 > 90_000);
+
+        new.complete();
+        storage.archive(&[new]).unwrap();
+        let archived: Vec<String> = storage
+            .read_archive()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.content)
+            .collect();
+        assert_eq!(archived, ["Cleared long ago", "Cleared today"]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,126 @@
+/// What the user picked from the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayCommand {
+    /// Bring the window forward
+    ShowWindow,
+    /// Bring the window forward with the task input focused
+    AddTask,
+    /// Leave the app
+    Quit,
+}
+
+/// An icon in the system tray showing how many tasks are pending
+#[cfg_attr(any(not(feature = "tray"), target_os = "linux"), allow(dead_code))]
+pub struct Tray {
+    #[cfg(feature = "tray")]
+    icon: tray_icon::TrayIcon,
+    #[cfg(feature = "tray")]
+    show_id: tray_icon::menu::MenuId,
+    #[cfg(feature = "tray")]
+    add_id: tray_icon::menu::MenuId,
+    #[cfg(feature = "tray")]
+    quit_id: tray_icon::menu::MenuId,
+}
+
+impl Tray {
+    /// Size of the generated icon, in pixels
+    #[cfg_attr(any(not(feature = "tray"), target_os = "linux"), allow(dead_code))]
+    const ICON_SIZE: u32 = 32;
+
+    /// Add the icon and its menu; must be called on the main thread
+    #[cfg(all(feature = "tray", not(target_os = "linux")))]
+    pub fn create() -> Result<Self, String> {
+        use tray_icon::menu::{Menu, MenuItem, PredefinedMenuItem};
+
+        let show = MenuItem::new("Show window", true, None);
+        let add = MenuItem::new("Add task", true, None);
+        let quit = MenuItem::new("Quit", true, None);
+        let menu = Menu::new();
+        menu.append_items(&[&show, &add, &PredefinedMenuItem::separator(), &quit])
+            .map_err(|e| format!("Failed to build tray menu: {}", e))?;
+
+        let icon = tray_icon::TrayIconBuilder::new()
+            .with_icon(Self::icon()?)
+            .with_tooltip("Waloyo")
+            .with_menu(Box::new(menu))
+            .build()
+            .map_err(|e| format!("Failed to create tray icon: {}", e))?;
+
+        Ok(Self {
+            icon,
+            show_id: show.id().clone(),
+            add_id: add.id().clone(),
+            quit_id: quit.id().clone(),
+        })
+    }
+
+    /// Tray icons on Linux live in a GTK main loop, which GPUI doesn't run
+    #[cfg(all(feature = "tray", target_os = "linux"))]
+    pub fn create() -> Result<Self, String> {
+        Err("Tray icons need a GTK main loop on Linux".to_string())
+    }
+
+    /// Without the `tray` feature there is no tray backend
+    #[cfg(not(feature = "tray"))]
+    pub fn create() -> Result<Self, String> {
+        Err("Built without the tray feature".to_string())
+    }
+
+    /// A filled circle in the rain-drop blue
+    #[cfg(feature = "tray")]
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    fn icon() -> Result<tray_icon::Icon, String> {
+        let size = Self::ICON_SIZE;
+        let center = (size as f32 - 1.0) / 2.0;
+        let radius = size as f32 / 2.0 - 1.0;
+        let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+                // One pixel of falloff keeps the edge smooth
+                let alpha = (radius - distance + 0.5).clamp(0.0, 1.0);
+                rgba.extend_from_slice(&[0x7a, 0xa2, 0xf7, (alpha * 255.0) as u8]);
+            }
+        }
+        tray_icon::Icon::from_rgba(rgba, size, size)
+            .map_err(|e| format!("Failed to build tray icon image: {}", e))
+    }
+
+    /// Show the number of pending tasks in the tooltip, and next to the icon where supported
+    #[cfg(feature = "tray")]
+    pub fn set_pending_count(&self, count: usize) {
+        let tooltip = match count {
+            0 => "Waloyo — clear skies".to_string(),
+            1 => "Waloyo — 1 task pending".to_string(),
+            n => format!("Waloyo — {} tasks pending", n),
+        };
+        if let Err(e) = self.icon.set_tooltip(Some(tooltip)) {
+            eprintln!("Failed to update tray tooltip: {}", e);
+        }
+        self.icon.set_title((count > 0).then(|| count.to_string()));
+    }
+
+    #[cfg(not(feature = "tray"))]
+    pub fn set_pending_count(&self, _count: usize) {}
+
+    /// The menu item picked since the previous call, if any
+    #[cfg(feature = "tray")]
+    pub fn take_command(&self) -> Option<TrayCommand> {
+        let mut command = None;
+        while let Ok(event) = tray_icon::menu::MenuEvent::receiver().try_recv() {
+            if event.id == self.show_id {
+                command = Some(TrayCommand::ShowWindow);
+            } else if event.id == self.add_id {
+                command = Some(TrayCommand::AddTask);
+            } else if event.id == self.quit_id {
+                command = Some(TrayCommand::Quit);
+            }
+        }
+        command
+    }
+
+    #[cfg(not(feature = "tray"))]
+    pub fn take_command(&self) -> Option<TrayCommand> {
+        None
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Notices when a storage file is changed by something other than this app
+/// The most recent change is kept until `take_change()` collects it.
+#[cfg_attr(not(feature = "watch"), allow(dead_code))]
+pub struct FileWatcher {
+    #[cfg(feature = "watch")]
+    _watcher: notify::RecommendedWatcher,
+    last_change: Arc<Mutex<Option<Instant>>>,
+}
+
+impl FileWatcher {
+    /// Start watching `path`
+    /// The parent directory is watched, since saves replace the file by renaming over it.
+    #[cfg(feature = "watch")]
+    pub fn watch(path: &Path) -> Result<Self, String> {
+        use notify::{EventKind, RecursiveMode, Watcher};
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("Cannot watch {}: not a file", path.display()))?
+            .to_owned();
+        let directory = path
+            .parent()
+            .ok_or_else(|| format!("Cannot watch {}: no parent directory", path.display()))?;
+        std::fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+
+        let last_change = Arc::new(Mutex::new(None));
+        let sink = last_change.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+                let Ok(event) = result else {
+                    return;
+                };
+                let is_write = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+                let is_ours = event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == Some(file_name.as_os_str()));
+                if is_write && is_ours {
+                    if let Ok(mut last) = sink.lock() {
+                        *last = Some(Instant::now());
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start file watcher: {}", e))?;
+        watcher
+            .watch(directory, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", directory.display(), e))?;
+
+        Ok(Self {
+            _watcher: watcher,
+            last_change,
+        })
+    }
+
+    /// Without the `watch` feature there is no watcher backend
+    #[cfg(not(feature = "watch"))]
+    pub fn watch(path: &Path) -> Result<Self, String> {
+        Err(format!(
+            "Cannot watch {}: built without the watch feature",
+            path.display()
+        ))
+    }
+
+    /// When the file last changed, if it has since the previous call
+    pub fn take_change(&self) -> Option<Instant> {
+        self.last_change.lock().ok()?.take()
+    }
+}
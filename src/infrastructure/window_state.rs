@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Where the window was last left, kept in `window.json` between launches
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// Whether the window was maximized; the other fields then hold its restored size
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl WindowState {
+    /// Smallest width or height worth restoring; anything less is treated as corrupt
+    pub const MIN_SIZE: f32 = 200.0;
+
+    /// Read the saved state from a JSON file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read window state: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse window state: {}", e))
+    }
+
+    /// Write the state to a JSON file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create window state directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write window state: {}", e))
+    }
+}
@@ -6,27 +6,410 @@
 // - Completing a task is like rain falling - washing away the challenge
 // - When all tasks are done, the sky clears
 
+#[cfg(test)]
+mod allocations;
 mod application;
+mod cli;
 mod domain;
 mod infrastructure;
 mod presentation;
 
+use application::TaskService;
+use domain::{Settings, SettingsChanged, Task, TaskEvent};
 use gpui::*;
+use infrastructure::{
+    GlobalHotkey, HotkeyConfig, NotificationConfig, Notifier, SettingsStore, SoundConfig, SoundCue,
+    SoundPlayer, Tray, TrayCommand, WindowState,
+};
+use presentation::animations::AnimationConfig;
+use presentation::theme::Theme;
 use presentation::views::TaskListView;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::Duration;
+
+fn load_custom_theme() {
+    let path = infrastructure::data_dir().join("theme.toml");
+    if !path.exists() {
+        return;
+    }
+    match Theme::from_file(&path) {
+        Ok(colors) => Theme::set_custom_colors(colors),
+        Err(e) => eprintln!("Failed to load theme, using defaults: {}", e),
+    }
+}
+
+fn load_animation_config() {
+    let path = infrastructure::data_dir().join("animation.toml");
+    if !path.exists() {
+        return;
+    }
+    match AnimationConfig::from_file(&path) {
+        Ok(config) => AnimationConfig::set(config),
+        Err(e) => eprintln!("Failed to load animation config, using defaults: {}", e),
+    }
+}
+
+fn load_settings() -> Settings {
+    SettingsStore::in_data_dir().load().unwrap_or_else(|e| {
+        eprintln!("Failed to load settings, using defaults: {}", e);
+        Settings::default()
+    })
+}
+
+/// Write the settings to `settings.json` as soon as they change
+fn register_settings(window: WindowHandle<TaskListView>, cx: &mut App) {
+    let store = SettingsStore::in_data_dir();
+    let Ok(view) = window.update(cx, |_view, _window, cx| cx.entity()) else {
+        return;
+    };
+    cx.subscribe(&view, move |_view, event: &SettingsChanged, _cx| {
+        if let Err(e) = store.save(&event.0) {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    })
+    .detach();
+}
+
+fn load_hotkey_config() -> HotkeyConfig {
+    let path = infrastructure::data_dir().join("hotkey.toml");
+    if !path.exists() {
+        return HotkeyConfig::default();
+    }
+    HotkeyConfig::from_file(&path).unwrap_or_else(|e| {
+        eprintln!("Failed to load hotkey config, using defaults: {}", e);
+        HotkeyConfig::default()
+    })
+}
+
+fn load_sound_config() -> SoundConfig {
+    let path = infrastructure::data_dir().join("sound.toml");
+    if !path.exists() {
+        return SoundConfig::default();
+    }
+    SoundConfig::from_file(&path).unwrap_or_else(|e| {
+        eprintln!("Failed to load sound config, using defaults: {}", e);
+        SoundConfig::default()
+    })
+}
+
+/// Play a sound when a task is overcome and a brighter one when the sky clears
+/// Without an output device this logs and stays silent.
+fn register_sounds(task_service: &Entity<TaskService>, cx: &mut App) {
+    let config = load_sound_config();
+    if !config.enabled {
+        return;
+    }
+    let player = match SoundPlayer::open(infrastructure::data_dir().join("sounds"), config.volume) {
+        Ok(player) => player,
+        Err(e) => {
+            eprintln!("Sounds unavailable: {}", e);
+            return;
+        }
+    };
+
+    cx.subscribe(
+        task_service,
+        move |_service, event: &TaskEvent, _cx| match event {
+            TaskEvent::Completed(_) => player.play(SoundCue::Completed),
+            TaskEvent::AllOvercome => player.play(SoundCue::AllOvercome),
+            _ => {}
+        },
+    )
+    .detach();
+}
+
+fn load_notification_config() -> NotificationConfig {
+    let path = infrastructure::data_dir().join("notifications.toml");
+    if !path.exists() {
+        return NotificationConfig::default();
+    }
+    NotificationConfig::from_file(&path).unwrap_or_else(|e| {
+        eprintln!("Failed to load notifications config, using defaults: {}", e);
+        NotificationConfig::default()
+    })
+}
+
+/// Title and body of the reminder for tasks that just came due
+/// A handful get one notification each; more are gathered into one so a
+/// backlog of overdue tasks at startup doesn't flood the screen.
+fn reminder_texts(tasks: &[Task]) -> Vec<(String, String)> {
+    const MAX_SEPARATE: usize = 3;
+
+    if tasks.len() > MAX_SEPARATE {
+        let mut body: Vec<String> = tasks
+            .iter()
+            .take(MAX_SEPARATE)
+            .map(|t| format!("• {}", t.content))
+            .collect();
+        body.push(format!("and {} more", tasks.len() - MAX_SEPARATE));
+        return vec![(format!("{} tasks are due", tasks.len()), body.join("\n"))];
+    }
+
+    tasks
+        .iter()
+        .map(|task| {
+            let now = chrono::Local::now();
+            let body = match task.due_date {
+                // An early reminder, sent `reminder_lead` ahead
+                Some(due) if !task.is_due_by(now) => {
+                    format!(
+                        "Coming up: {}",
+                        domain::humanize_due(due, task.has_due_time(), now).0
+                    )
+                }
+                Some(due) if task.has_due_time() => format!("Due at {}", due.format("%-I:%M %p")),
+                Some(due) if due.date_naive() < now.date_naive() => {
+                    format!("Overdue since {}", due.format("%m/%d"))
+                }
+                _ => "Due today".to_string(),
+            };
+            (task.content.to_string(), body)
+        })
+        .collect()
+}
+
+/// Send a desktop notification when a task comes due, once per task and due date
+/// Without a notification backend this logs and does nothing.
+fn register_reminders(window: WindowHandle<TaskListView>, cx: &mut App) {
+    let config = load_notification_config();
+    if !config.enabled {
+        return;
+    }
+    let notifier = match Notifier::new() {
+        Ok(notifier) => std::sync::Arc::new(notifier),
+        Err(e) => {
+            eprintln!("Reminders unavailable: {}", e);
+            return;
+        }
+    };
+    let interval = Duration::from_secs(config.interval_secs.max(1));
+
+    cx.spawn(async move |cx| loop {
+        // Tasks coming due while reminders are off in settings are still taken,
+        // so turning them back on doesn't bring a burst of old reminders
+        let Ok((enabled, due)) = window.update(cx, |view, _window, cx| {
+            let due = view.take_due_reminders(chrono::Local::now(), cx);
+            (view.settings().notifications, due)
+        }) else {
+            break;
+        };
+        let due = if enabled { due } else { Vec::new() };
+        for (summary, body) in reminder_texts(&due) {
+            let notifier = notifier.clone();
+            cx.background_executor()
+                .spawn(async move {
+                    if let Err(e) = notifier.send(&summary, &body) {
+                        eprintln!("{}", e);
+                    }
+                })
+                .detach();
+        }
+        cx.background_executor().timer(interval).await;
+    })
+    .detach();
+}
+
+/// Bring the window forward with the task input focused, returning false once
+/// the window is gone
+fn focus_quick_capture(window: WindowHandle<TaskListView>, cx: &mut AsyncApp) -> bool {
+    let _ = cx.update(|cx| cx.activate(true));
+    window
+        .update(cx, |view, window, cx| {
+            window.activate_window();
+            view.focus_task_input(window, cx);
+        })
+        .is_ok()
+}
+
+/// Show the pending count in the system tray, with a menu to show the window,
+/// add a task or quit
+/// Where tray icons aren't supported this logs and does nothing.
+fn register_tray(
+    window: WindowHandle<TaskListView>,
+    task_service: &Entity<TaskService>,
+    cx: &mut App,
+) {
+    let tray = match Tray::create() {
+        Ok(tray) => Rc::new(tray),
+        Err(e) => {
+            eprintln!("Tray icon unavailable: {}", e);
+            return;
+        }
+    };
+
+    tray.set_pending_count(task_service.read(cx).pending_count());
+    let counter = tray.clone();
+    cx.subscribe(task_service, move |_service, event: &TaskEvent, _cx| {
+        if let TaskEvent::PendingChanged(count) = event {
+            counter.set_pending_count(*count);
+        }
+    })
+    .detach();
+
+    cx.spawn(async move |cx| loop {
+        cx.background_executor()
+            .timer(Duration::from_millis(Theme::HOTKEY_POLL))
+            .await;
+        let open = match tray.take_command() {
+            Some(TrayCommand::ShowWindow) => {
+                let _ = cx.update(|cx| cx.activate(true));
+                window
+                    .update(cx, |_view, window, _cx| window.activate_window())
+                    .is_ok()
+            }
+            Some(TrayCommand::AddTask) => focus_quick_capture(window, cx),
+            Some(TrayCommand::Quit) => {
+                let _ = cx.update(|cx| cx.quit());
+                false
+            }
+            None => true,
+        };
+        if !open {
+            break;
+        }
+    })
+    .detach();
+}
+
+/// Bring the window forward with the task input focused whenever the quick
+/// capture shortcut is pressed, even from another app
+/// Where global shortcuts aren't supported this logs and does nothing.
+fn register_quick_capture(window: WindowHandle<TaskListView>, cx: &mut App) {
+    let config = load_hotkey_config();
+    if !config.enabled {
+        return;
+    }
+    let hotkey = match GlobalHotkey::register(&config.quick_capture) {
+        Ok(hotkey) => hotkey,
+        Err(e) => {
+            eprintln!("Quick capture shortcut unavailable: {}", e);
+            return;
+        }
+    };
+
+    cx.spawn(async move |cx| loop {
+        cx.background_executor()
+            .timer(Duration::from_millis(Theme::HOTKEY_POLL))
+            .await;
+        if hotkey.take_pressed() && !focus_quick_capture(window, cx) {
+            break;
+        }
+    })
+    .detach();
+}
+
+fn window_state_path() -> PathBuf {
+    infrastructure::data_dir().join("window.json")
+}
+
+/// Where the window was last left, if that still lands on a connected display
+/// None on first launch, or when the file is unreadable or the display is gone.
+fn restore_window_bounds(cx: &App) -> Option<WindowBounds> {
+    let path = window_state_path();
+    if !path.exists() {
+        return None;
+    }
+    let state = WindowState::from_file(&path)
+        .map_err(|e| eprintln!("Failed to restore window position: {}", e))
+        .ok()?;
+    if state.width < WindowState::MIN_SIZE || state.height < WindowState::MIN_SIZE {
+        return None;
+    }
+
+    let bounds = Bounds::new(
+        point(px(state.x), px(state.y)),
+        size(px(state.width), px(state.height)),
+    );
+    let on_screen = cx
+        .displays()
+        .iter()
+        .any(|display| display.bounds().intersects(&bounds));
+    if !on_screen {
+        return None;
+    }
+    Some(if state.maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    })
+}
+
+/// Save the window's position and size whenever it moves or resizes
+fn register_window_state(window: WindowHandle<TaskListView>, cx: &mut App) {
+    let path = window_state_path();
+    let _ = window.update(cx, |_view, window, cx| {
+        let mut last_saved = None;
+        cx.observe_window_bounds(window, move |_view, window, _cx| {
+            let (bounds, maximized) = match window.window_bounds() {
+                WindowBounds::Windowed(bounds) => (bounds, false),
+                WindowBounds::Maximized(bounds) => (bounds, true),
+                // Fullscreen isn't restored; the last windowed bounds stay saved
+                WindowBounds::Fullscreen(_) => return,
+            };
+            let state = WindowState {
+                x: f32::from(bounds.origin.x),
+                y: f32::from(bounds.origin.y),
+                width: f32::from(bounds.size.width),
+                height: f32::from(bounds.size.height),
+                maximized,
+            };
+            if last_saved != Some(state) {
+                if let Err(e) = state.save(&path) {
+                    eprintln!("Failed to save window position: {}", e);
+                }
+                last_saved = Some(state);
+            }
+        })
+        .detach();
+    });
+}
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    load_custom_theme();
+    load_animation_config();
+    let settings = load_settings();
+
     Application::new().run(|cx: &mut App| {
-        let bounds = Bounds::centered(None, size(px(420.0), px(680.0)), cx);
-        let _ = cx.open_window(
+        let window_bounds = restore_window_bounds(cx).unwrap_or_else(|| {
+            WindowBounds::Windowed(Bounds::centered(None, size(px(420.0), px(680.0)), cx))
+        });
+        // Load tasks from storage (or create demo tasks if empty), shared by
+        // every view that shows them
+        let task_service = cx.new(|_cx| {
+            let mut service = TaskService::default();
+            service.watch_for_external_changes();
+            service
+        });
+        let window = cx.open_window(
             WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                window_bounds: Some(window_bounds),
                 titlebar: Some(TitlebarOptions {
                     title: Some("Waloyo - We Overcome".into()),
                     ..Default::default()
                 }),
                 ..Default::default()
             },
-            |_window, cx| cx.new(|cx| TaskListView::new(cx)),
+            |_window, cx| {
+                let task_service = task_service.clone();
+                cx.new(|cx| TaskListView::new(task_service, cx).with_settings(settings))
+            },
         );
+        match window {
+            Ok(window) => {
+                register_window_state(window, cx);
+                register_settings(window, cx);
+                register_sounds(&task_service, cx);
+                register_tray(window, &task_service, cx);
+                register_reminders(window, cx);
+                register_quick_capture(window, cx);
+            }
+            Err(e) => eprintln!("Failed to open window: {}", e),
+        }
     });
 }
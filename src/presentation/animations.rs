@@ -1,38 +1,279 @@
+use crate::domain::{AnimationSpeed, TaskPriority};
 use crate::presentation::theme::Theme;
 use gpui::*;
+use serde::Deserialize;
 use std::f32::consts::PI;
+use std::path::Path;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
 use std::time::Duration;
 
+/// Durations loaded from the user's animation file, replacing the defaults
+static ANIMATION_CONFIG: OnceLock<AnimationConfig> = OnceLock::new();
+
+/// Index into `AnimationSpeed::ALL` of the chosen speed, switchable while running
+static ANIMATION_SPEED: AtomicU8 = AtomicU8::new(1);
+
+/// How long each animation takes (ms)
+/// Missing keys keep the built-in durations.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct AnimationConfig {
+    /// One full back-and-forth of the wind sway
+    pub sway_ms: u64,
+    /// The rain drop fall; tasks complete when it ends
+    pub rain_ms: u64,
+    /// The clear sky glow
+    pub clear_sky_ms: u64,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            sway_ms: Theme::ANIM_WIND_SWAY,
+            rain_ms: Theme::ANIM_RAIN_DROP,
+            clear_sky_ms: Theme::ANIM_CLEAR_SKY,
+        }
+    }
+}
+
+impl AnimationConfig {
+    /// The active durations, scaled by the chosen speed
+    pub fn get() -> Self {
+        let config = ANIMATION_CONFIG.get().copied().unwrap_or_default();
+        let factor = Self::speed().duration_factor();
+        let scale = |ms: u64| ((ms as f32 * factor).round() as u64).max(1);
+        Self {
+            sway_ms: scale(config.sway_ms),
+            rain_ms: scale(config.rain_ms),
+            clear_sky_ms: scale(config.clear_sky_ms),
+        }
+    }
+
+    /// How quickly animations play
+    pub fn speed() -> AnimationSpeed {
+        let index = ANIMATION_SPEED.load(Ordering::Relaxed) as usize;
+        AnimationSpeed::ALL.get(index).copied().unwrap_or_default()
+    }
+
+    /// Change the speed; animations started afterwards pick it up
+    pub fn set_speed(speed: AnimationSpeed) {
+        let index = AnimationSpeed::ALL
+            .iter()
+            .position(|s| *s == speed)
+            .unwrap_or(1);
+        ANIMATION_SPEED.store(index as u8, Ordering::Relaxed);
+    }
+
+    /// Install durations loaded at startup; later calls are ignored
+    pub fn set(config: AnimationConfig) {
+        let _ = ANIMATION_CONFIG.set(config);
+    }
+
+    /// Load durations from a TOML file with `sway_ms`, `rain_ms` and `clear_sky_ms` keys
+    /// A zero duration can't be animated, so it's raised to 1 ms.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read animation file: {}", e))?;
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse animation file: {}", e))?;
+        Ok(config.clamped())
+    }
+
+    /// The same durations with each raised to at least 1 ms
+    fn clamped(self) -> Self {
+        let at_least_1 = |key: &str, ms: u64| {
+            if ms == 0 {
+                eprintln!("Animation duration '{}' is 0, using 1 ms", key);
+            }
+            ms.max(1)
+        };
+        Self {
+            sway_ms: at_least_1("sway_ms", self.sway_ms),
+            rain_ms: at_least_1("rain_ms", self.rain_ms),
+            clear_sky_ms: at_least_1("clear_sky_ms", self.clear_sky_ms),
+        }
+    }
+}
+
 /// Easing function for the "Wind Sway" effect.
-/// Oscillates smoothly between 0 and 1.
-pub fn wind_sway_easing(delta: f32) -> f32 {
-    let oscillation = (delta * 2.0 * PI).sin();
+/// Oscillates smoothly between 0 and 1, shifted by `phase` (a fraction of one cycle).
+pub fn wind_sway_easing(delta: f32, phase: f32) -> f32 {
+    let oscillation = ((delta + phase) * 2.0 * PI).sin();
     (oscillation + 1.0) / 2.0
 }
 
+/// How heavy the rain is when a task of this priority is overcome
+/// Low priority tasks get the plain drop; weightier ones fall further and
+/// flash as they go.
+pub fn rain_intensity(priority: TaskPriority) -> f32 {
+    match priority {
+        TaskPriority::Low => 1.0,
+        TaskPriority::Medium => 1.2,
+        TaskPriority::High => 1.5,
+        TaskPriority::Urgent => 1.8,
+    }
+}
+
+/// One frame of the rain drop: an 80px fall and a fade to 0.3 opacity at
+/// intensity 1.0
+/// Heavier rain falls further and starts with a brief glow around the element
+/// that fades over the first quarter of the drop.
+fn rain_drop_frame<E: Styled>(element: E, delta: f32, intensity: f32) -> E {
+    let fall_distance = 80.0 * intensity * delta;
+    let opacity_val = 1.0 - (delta * 0.7);
+    let element = element.mt(px(fall_distance)).opacity(opacity_val);
+    if intensity <= 1.0 {
+        return element;
+    }
+
+    let mut flash = Theme::state_completing();
+    flash.a = (1.0 - delta * 4.0).max(0.0) * (intensity - 1.0) * 0.6;
+    element.shadow(vec![BoxShadow {
+        color: flash.into(),
+        offset: point(px(0.0), px(0.0)),
+        blur_radius: px(16.0 * intensity),
+        spread_radius: px(0.0),
+    }])
+}
+
+/// Shafts of sunlight for the clear sky: where each sits across the sky, as a
+/// fraction of the width, and how wide it is
+const CLEAR_SKY_RAYS: [(f32, f32); 3] = [(0.18, 36.0), (0.46, 56.0), (0.74, 28.0)];
+
+/// Motes of light rising with the clear sky: where each starts across the
+/// sky, its size, and how far into the celebration it sets off
+const CLEAR_SKY_MOTES: [(f32, f32, f32); 8] = [
+    (0.08, 6.0, 0.0),
+    (0.22, 4.0, 0.25),
+    (0.35, 8.0, 0.1),
+    (0.5, 5.0, 0.35),
+    (0.61, 7.0, 0.05),
+    (0.72, 4.0, 0.3),
+    (0.84, 6.0, 0.15),
+    (0.93, 5.0, 0.4),
+];
+
+/// Sun rays and rising motes of light for the moment every task is overcome
+/// Plays once over the clear sky duration and leaves nothing behind; with
+/// reduced motion, or while inactive, it's an empty layer. Place it over the
+/// view, absolutely positioned.
+pub fn clear_sky_rays(active: bool) -> AnyElement {
+    if !active || Theme::reduced_motion() {
+        return div().into_any_element();
+    }
+    let duration = Duration::from_millis(AnimationConfig::get().clear_sky_ms);
+
+    let rays = CLEAR_SKY_RAYS
+        .iter()
+        .enumerate()
+        .map(|(i, &(left, width))| {
+            div()
+                .absolute()
+                .top_0()
+                .left(relative(left))
+                .w(px(width))
+                .h(relative(0.7))
+                .rounded_b_full()
+                .with_animation(
+                    ElementId::NamedInteger("clear-sky-ray".into(), i as u64),
+                    Animation::new(duration).with_easing(ease_in_out),
+                    |ray, delta| {
+                        // Brightest halfway through, gone by the end
+                        let mut light = Theme::clear_sky_accent();
+                        light.a = (delta * PI).sin() * 0.12;
+                        ray.bg(light)
+                    },
+                )
+        });
+
+    let motes = CLEAR_SKY_MOTES
+        .iter()
+        .enumerate()
+        .map(|(i, &(left, size, start))| {
+            div()
+                .absolute()
+                .left(relative(left))
+                .size(px(size))
+                .rounded_full()
+                .with_animation(
+                    ElementId::NamedInteger("clear-sky-mote".into(), i as u64),
+                    Animation::new(duration),
+                    move |mote, delta| {
+                        let rise = ((delta - start) / (1.0 - start)).clamp(0.0, 1.0);
+                        let mut light = Theme::clear_sky_accent();
+                        light.a = (rise * PI).sin() * 0.5;
+                        mote.bottom(relative(rise * 0.8)).bg(light)
+                    },
+                )
+        });
+
+    div()
+        .absolute()
+        .inset_0()
+        .overflow_hidden()
+        .children(rays)
+        .children(motes)
+        .into_any_element()
+}
+
+/// How faded a card dragged `offset` pixels into a fling is; it thins out as
+/// it nears the delete distance
+pub fn fling_opacity(offset: f32) -> f32 {
+    1.0 - (offset / Theme::FLING_DELETE_DISTANCE * 0.4).clamp(0.0, 0.5)
+}
+
+/// How far past where it was let go a flung card blows before it's gone
+const BLOW_AWAY_DISTANCE: f32 = 320.0;
+
+/// One frame of a card let go mid-fling, from `from` pixels right of its place:
+/// blown the rest of the way off and faded out, or eased back into place
+fn fling_settle_frame<E: Styled>(element: E, delta: f32, from: f32, blow_away: bool) -> E {
+    if blow_away {
+        let offset = from + BLOW_AWAY_DISTANCE * delta;
+        element
+            .ml(px(offset))
+            .opacity(fling_opacity(from) * (1.0 - delta))
+    } else {
+        let offset = from * (1.0 - delta);
+        element.ml(px(offset)).opacity(fling_opacity(offset))
+    }
+}
+
 /// A trait to add metaphorical animations to elements.
+/// With reduced motion on, every animation leaves the element static.
 pub trait WaloyoAnimations: Sized {
     /// Apply the "Wind Sway" animation - a gentle horizontal oscillation.
-    fn wind_sway(self, id: impl Into<ElementId>, active: bool) -> AnyElement;
+    /// `phase` offsets the cycle so neighbouring elements don't sway in unison.
+    fn wind_sway(self, id: impl Into<ElementId>, phase: f32, active: bool) -> AnyElement;
 
     /// Apply the "Rain Drop" animation - falling and fading.
-    fn rain_drop(self, id: impl Into<ElementId>, active: bool) -> AnyElement;
+    /// `intensity` scales the downpour, see `rain_intensity`; 1.0 is the plain drop.
+    fn rain_drop(self, id: impl Into<ElementId>, intensity: f32, active: bool) -> AnyElement;
 
     /// Apply the "Clear Sky" celebration effect - a subtle golden glow.
     fn clear_sky(self, id: impl Into<ElementId>, active: bool) -> AnyElement;
+
+    /// Apply the "Gust In" entrance - blown in from the left, fading in.
+    fn gust_in(self, id: impl Into<ElementId>, active: bool) -> AnyElement;
+
+    /// Settle a card let go mid-fling, `from` pixels right of its place - blown
+    /// away by the wind if `blow_away`, otherwise springing back.
+    /// With reduced motion a card blowing away is simply gone.
+    fn fling_settle(self, id: impl Into<ElementId>, from: f32, blow_away: bool) -> AnyElement;
 }
 
 impl WaloyoAnimations for Div {
-    fn wind_sway(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+    fn wind_sway(self, id: impl Into<ElementId>, phase: f32, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(3000))
+            Animation::new(Duration::from_millis(AnimationConfig::get().sway_ms))
                 .repeat()
-                .with_easing(wind_sway_easing),
+                .with_easing(move |delta| wind_sway_easing(delta, phase)),
             move |element, delta| {
                 let sway_offset = (delta - 0.5) * 6.0;
                 element.ml(px(sway_offset))
@@ -41,51 +282,80 @@ impl WaloyoAnimations for Div {
         .into_any_element()
     }
 
-    fn rain_drop(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+    fn rain_drop(self, id: impl Into<ElementId>, intensity: f32, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(Theme::ANIM_RAIN_DROP)).with_easing(ease_in_out),
-            move |element, delta| {
-                let fall_distance = 80.0 * delta;
-                let opacity_val = 1.0 - (delta * 0.7);
-                element.mt(px(fall_distance)).opacity(opacity_val)
-            },
+            Animation::new(Duration::from_millis(AnimationConfig::get().rain_ms))
+                .with_easing(ease_in_out),
+            move |element, delta| rain_drop_frame(element, delta, intensity),
         )
         .into_any_element()
     }
 
     fn clear_sky(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+        if !active || Theme::reduced_motion() {
+            return self.into_any_element();
+        }
+
+        self.with_animation(
+            id,
+            Animation::new(Duration::from_millis(AnimationConfig::get().clear_sky_ms))
+                .with_easing(ease_in_out),
+            |element, delta| {
+                let mut glow = Theme::clear_sky_accent();
+                glow.a = delta * 0.08;
+                element.bg(glow)
+            },
+        )
+        .into_any_element()
+    }
+
+    fn gust_in(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(2000)).with_easing(ease_in_out),
+            Animation::new(Duration::from_millis(Theme::ANIM_NORMAL)).with_easing(ease_in_out),
             |element, delta| {
-                let opacity = delta * 0.08;
-                element.bg(rgba(0xffc77700 + ((opacity * 255.0) as u32)))
+                let gust_offset = (1.0 - delta) * -12.0;
+                element.ml(px(gust_offset)).opacity(delta)
             },
         )
         .into_any_element()
     }
+
+    fn fling_settle(self, id: impl Into<ElementId>, from: f32, blow_away: bool) -> AnyElement {
+        if Theme::reduced_motion() {
+            let element = if blow_away { self.opacity(0.0) } else { self };
+            return element.into_any_element();
+        }
+
+        self.with_animation(
+            id,
+            Animation::new(Duration::from_millis(Theme::ANIM_NORMAL)).with_easing(ease_in_out),
+            move |element, delta| fling_settle_frame(element, delta, from, blow_away),
+        )
+        .into_any_element()
+    }
 }
 
 impl WaloyoAnimations for Stateful<Div> {
-    fn wind_sway(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+    fn wind_sway(self, id: impl Into<ElementId>, phase: f32, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(3000))
+            Animation::new(Duration::from_millis(AnimationConfig::get().sway_ms))
                 .repeat()
-                .with_easing(wind_sway_easing),
+                .with_easing(move |delta| wind_sway_easing(delta, phase)),
             move |element, delta| {
                 let sway_offset = (delta - 0.5) * 6.0;
                 element.ml(px(sway_offset))
@@ -94,36 +364,65 @@ impl WaloyoAnimations for Stateful<Div> {
         .into_any_element()
     }
 
-    fn rain_drop(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+    fn rain_drop(self, id: impl Into<ElementId>, intensity: f32, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(Theme::ANIM_RAIN_DROP)).with_easing(ease_in_out),
-            move |element, delta| {
-                let fall_distance = 80.0 * delta;
-                let opacity_val = 1.0 - (delta * 0.7);
-                element.mt(px(fall_distance)).opacity(opacity_val)
-            },
+            Animation::new(Duration::from_millis(AnimationConfig::get().rain_ms))
+                .with_easing(ease_in_out),
+            move |element, delta| rain_drop_frame(element, delta, intensity),
         )
         .into_any_element()
     }
 
     fn clear_sky(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
-        if !active {
+        if !active || Theme::reduced_motion() {
+            return self.into_any_element();
+        }
+
+        self.with_animation(
+            id,
+            Animation::new(Duration::from_millis(AnimationConfig::get().clear_sky_ms))
+                .with_easing(ease_in_out),
+            |element, delta| {
+                let mut glow = Theme::clear_sky_accent();
+                glow.a = delta * 0.08;
+                element.bg(glow)
+            },
+        )
+        .into_any_element()
+    }
+
+    fn gust_in(self, id: impl Into<ElementId>, active: bool) -> AnyElement {
+        if !active || Theme::reduced_motion() {
             return self.into_any_element();
         }
 
         self.with_animation(
             id,
-            Animation::new(Duration::from_millis(2000)).with_easing(ease_in_out),
+            Animation::new(Duration::from_millis(Theme::ANIM_NORMAL)).with_easing(ease_in_out),
             |element, delta| {
-                let opacity = delta * 0.08;
-                element.bg(rgba(0xffc77700 + ((opacity * 255.0) as u32)))
+                let gust_offset = (1.0 - delta) * -12.0;
+                element.ml(px(gust_offset)).opacity(delta)
             },
         )
         .into_any_element()
     }
+
+    fn fling_settle(self, id: impl Into<ElementId>, from: f32, blow_away: bool) -> AnyElement {
+        if Theme::reduced_motion() {
+            let element = if blow_away { self.opacity(0.0) } else { self };
+            return element.into_any_element();
+        }
+
+        self.with_animation(
+            id,
+            Animation::new(Duration::from_millis(Theme::ANIM_NORMAL)).with_easing(ease_in_out),
+            move |element, delta| fling_settle_frame(element, delta, from, blow_away),
+        )
+        .into_any_element()
+    }
 }
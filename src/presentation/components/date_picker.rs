@@ -0,0 +1,289 @@
+use crate::domain::{humanize_due, local_datetime, DatePickerEvent};
+use crate::presentation::theme::Theme;
+use chrono::{DateTime, Datelike, Local, Months, NaiveDate, NaiveTime};
+use gpui::prelude::*;
+use gpui::*;
+
+/// Times of day offered below the month grid
+const TIME_PRESETS: [(u32, u32); 4] = [(9, 0), (12, 0), (17, 0), (20, 0)];
+
+/// Width of one day in the month grid
+const DAY_CELL: f32 = 28.0;
+
+/// A month calendar for choosing a due date, with an optional time of day
+/// The picker only asks for changes through `DatePickerEvent`; the owner sets
+/// the due date and closes it.
+pub struct DatePicker {
+    focus_handle: FocusHandle,
+    /// First day of the month on show
+    month: NaiveDate,
+    day: Option<NaiveDate>,
+    /// None for a date-only due date
+    time: Option<NaiveTime>,
+}
+
+impl DatePicker {
+    /// Open on the month of `due`, or on this month without one, with its time
+    /// of day chosen if `has_time`
+    pub fn new(due: Option<DateTime<Local>>, has_time: bool, cx: &mut App) -> Self {
+        let today = Local::now().date_naive();
+        let day = due.map(|due| due.date_naive());
+        let month = day.unwrap_or(today);
+        Self {
+            focus_handle: cx.focus_handle(),
+            month: month.with_day(1).unwrap_or(month),
+            day,
+            time: due.filter(|_| has_time).map(|due| due.time()),
+        }
+    }
+
+    /// Move keyboard focus into the picker, so Enter and Escape reach it
+    pub fn focus(&self, window: &mut Window) {
+        self.focus_handle.focus(window);
+    }
+
+    /// The chosen moment, at midnight when no time is chosen
+    fn chosen(&self) -> Option<DateTime<Local>> {
+        self.day
+            .and_then(|day| local_datetime(day, self.time.unwrap_or(NaiveTime::MIN)))
+    }
+
+    fn shift_month(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let shifted = if forward {
+            self.month.checked_add_months(Months::new(1))
+        } else {
+            self.month.checked_sub_months(Months::new(1))
+        };
+        if let Some(month) = shifted {
+            self.month = month;
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, cx: &mut Context<Self>) {
+        if let Some(due) = self.chosen() {
+            cx.emit(DatePickerEvent::Picked(Some(due), self.time.is_some()));
+        }
+    }
+
+    /// A small clickable label, highlighted when selected
+    fn chip(label: impl Into<SharedString>, selected: bool) -> Div {
+        div()
+            .px(px(Theme::PADDING_SM))
+            .py_0p5()
+            .rounded(px(Theme::RADIUS_SM))
+            .text_xs()
+            .cursor_pointer()
+            .text_color(if selected {
+                Theme::accent_primary()
+            } else {
+                Theme::text_secondary()
+            })
+            .when(selected, |this| this.bg(Theme::surface_hover()))
+            .hover(|s| s.bg(Theme::surface_hover()))
+            .child(label.into())
+    }
+
+    /// The days of the month on show, in weeks starting on Monday
+    fn render_grid(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let today = Local::now().date_naive();
+        let offset = self.month.weekday().num_days_from_monday() as usize;
+        let days: Vec<NaiveDate> = self
+            .month
+            .iter_days()
+            .take_while(|day| day.month() == self.month.month())
+            .collect();
+
+        let mut cells: Vec<Option<NaiveDate>> = vec![None; offset];
+        cells.extend(days.into_iter().map(Some));
+        cells.resize(cells.len().div_ceil(7) * 7, None);
+
+        let header = div()
+            .flex()
+            .children(["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"].map(|name| {
+                div()
+                    .w(px(DAY_CELL))
+                    .flex()
+                    .justify_center()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child(name)
+            }));
+
+        let weeks = cells.chunks(7).map(|week| {
+            div().flex().children(week.iter().map(|cell| {
+                let cell_div = div()
+                    .w(px(DAY_CELL))
+                    .h(px(DAY_CELL))
+                    .flex()
+                    .items_center()
+                    .justify_center();
+                let Some(day) = *cell else {
+                    return cell_div;
+                };
+                let selected = self.day == Some(day);
+                cell_div
+                    .rounded(px(Theme::RADIUS_SM))
+                    .text_sm()
+                    .cursor_pointer()
+                    .text_color(if selected {
+                        Theme::background()
+                    } else {
+                        Theme::text_primary()
+                    })
+                    .when(selected, |this| this.bg(Theme::accent_primary()))
+                    .when(!selected, |this| {
+                        this.hover(|s| s.bg(Theme::surface_hover()))
+                    })
+                    .when(day == today && !selected, |this| {
+                        this.border_1().border_color(Theme::accent_primary())
+                    })
+                    .child(day.day().to_string())
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.day = Some(day);
+                            cx.notify();
+                        }),
+                    )
+            }))
+        });
+
+        div().flex().flex_col().child(header).children(weeks)
+    }
+
+    /// All day, a few common times, and the task's own time if it's none of those
+    fn render_times(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut times: Vec<NaiveTime> = TIME_PRESETS
+            .iter()
+            .filter_map(|&(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0))
+            .collect();
+        if let Some(time) = self.time.filter(|time| !times.contains(time)) {
+            times.push(time);
+            times.sort();
+        }
+
+        let all_day = Self::chip("All day", self.time.is_none()).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                this.time = None;
+                cx.notify();
+            }),
+        );
+
+        div()
+            .flex()
+            .flex_wrap()
+            .items_center()
+            .gap_1()
+            .child(all_day)
+            .children(times.into_iter().map(|time| {
+                Self::chip(
+                    time.format("%-I:%M %p").to_string(),
+                    self.time == Some(time),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.time = Some(time);
+                        cx.notify();
+                    }),
+                )
+            }))
+    }
+}
+
+impl EventEmitter<DatePickerEvent> for DatePicker {}
+
+impl Render for DatePicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let chosen = self.chosen();
+        let summary = match chosen {
+            Some(due) => humanize_due(due, self.time.is_some(), Local::now()).0,
+            None => "No date".to_string(),
+        };
+
+        div()
+            .id("date-picker")
+            .track_focus(&self.focus_handle)
+            .p(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .bg(Theme::surface())
+            .border_1()
+            .border_color(Theme::border())
+            .rounded(px(Theme::RADIUS_MD))
+            .shadow_lg()
+            .occlude()
+            .on_mouse_down_out(cx.listener(|_this, _event: &MouseDownEvent, _window, cx| {
+                cx.emit(DatePickerEvent::Dismissed);
+            }))
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                match event.keystroke.key.as_str() {
+                    "escape" => cx.emit(DatePickerEvent::Dismissed),
+                    "enter" => this.confirm(cx),
+                    "left" | "pageup" => this.shift_month(false, cx),
+                    "right" | "pagedown" => this.shift_month(true, cx),
+                    _ => return,
+                }
+                cx.stop_propagation();
+            }))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(Self::chip("‹", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                            this.shift_month(false, cx);
+                        }),
+                    ))
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(Theme::text_primary())
+                            .child(self.month.format("%B %Y").to_string()),
+                    )
+                    .child(Self::chip("›", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                            this.shift_month(true, cx);
+                        }),
+                    )),
+            )
+            .child(self.render_grid(cx))
+            .child(self.render_times(cx))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .child(summary),
+                    )
+                    .child(Self::chip("Clear", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|_this, _event: &MouseDownEvent, _window, cx| {
+                            cx.emit(DatePickerEvent::Picked(None, false));
+                        }),
+                    ))
+                    .child(
+                        Self::chip("Set", chosen.is_some())
+                            .when(chosen.is_none(), |this| this.opacity(0.4))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.confirm(cx);
+                                }),
+                            ),
+                    ),
+            )
+    }
+}
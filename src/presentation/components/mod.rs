@@ -1,5 +1,11 @@
+mod date_picker;
+mod search_input;
 mod task_input;
 mod task_item;
+mod text_buffer;
 
+pub use date_picker::*;
+pub use search_input::*;
 pub use task_input::*;
 pub use task_item::*;
+pub use text_buffer::*;
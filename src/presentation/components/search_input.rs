@@ -0,0 +1,184 @@
+use crate::domain::SearchQueryChanged;
+use crate::presentation::components::{text_input_layer, TextBuffer};
+use crate::presentation::theme::Theme;
+use gpui::prelude::*;
+use gpui::*;
+use std::ops::Range;
+
+/// A text input for live-filtering the task list
+pub struct SearchInput {
+    focus_handle: FocusHandle,
+    query: TextBuffer,
+}
+
+impl SearchInput {
+    pub fn new(cx: &mut App) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+            query: TextBuffer::default(),
+        }
+    }
+
+    /// Empty the query, showing every task again
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.query.clear();
+        self.query_changed(cx);
+    }
+
+    fn query_changed(&mut self, cx: &mut Context<Self>) {
+        cx.emit(SearchQueryChanged(self.query.text().to_string()));
+        cx.notify();
+    }
+}
+
+impl EventEmitter<SearchQueryChanged> for SearchInput {}
+
+/// Typed and composed text arrives here from the platform, see `text_input_layer`
+impl EntityInputHandler for SearchInput {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        adjusted_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let (text, range) = self.query.text_for_range(range_utf16);
+        *adjusted_range = Some(range);
+        Some(text)
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.query.selected_range(),
+            reversed: false,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.query.marked_range()
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.query.unmark();
+        cx.notify();
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        text: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.query.replace_text(range_utf16, text);
+        self.query_changed(cx);
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The list filters once the composed text is committed
+        self.query
+            .replace_and_mark_text(range_utf16, new_text, new_selected_range_utf16);
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        element_bounds: Bounds<Pixels>,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        Some(
+            self.query
+                .bounds_for_range(range_utf16, element_bounds, window),
+        )
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        _point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        None
+    }
+}
+
+impl Render for SearchInput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let focused = focus_handle.is_focused(window);
+
+        div()
+            .id("search-input-container")
+            .w_full()
+            .px(px(Theme::PADDING_LG))
+            .pb(px(Theme::PADDING_SM))
+            .child(
+                div()
+                    .id("search-input")
+                    .track_focus(&focus_handle)
+                    .w_full()
+                    .px(px(Theme::PADDING_MD))
+                    .py(px(Theme::PADDING_SM / 2.0))
+                    .rounded(px(Theme::RADIUS_MD))
+                    .border_1()
+                    .border_color(Theme::border())
+                    .flex()
+                    .items_center()
+                    .gap(px(Theme::PADDING_SM))
+                    .text_sm()
+                    .focus(|style| style.border_color(Theme::accent_primary()))
+                    .child(div().text_color(Theme::text_secondary()).child("🔍"))
+                    .child(
+                        div()
+                            .flex_1()
+                            .relative()
+                            .text_color(if self.query.is_empty() {
+                                Theme::text_secondary()
+                            } else {
+                                Theme::text_primary()
+                            })
+                            .child(if self.query.is_empty() {
+                                div().child("Search tasks...").into_any_element()
+                            } else {
+                                self.query.render_with_caret(focused).into_any_element()
+                            })
+                            .child(text_input_layer(focus_handle.clone(), cx.entity())),
+                    )
+                    .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                        // While an input method is composing, its keys are its own
+                        if this.query.is_composing() {
+                            return;
+                        }
+                        match event.keystroke.key.as_str() {
+                            "escape" => this.clear(cx),
+                            // The query is a single line
+                            "enter" => {}
+                            _ => {
+                                if this.query.handle_key(&event.keystroke) {
+                                    cx.stop_propagation();
+                                    this.query_changed(cx);
+                                }
+                            }
+                        }
+                    })),
+            )
+    }
+}
@@ -1,41 +1,290 @@
-use crate::domain::TaskSubmitted;
+use crate::domain::{
+    format_effort, parse_task_input, Recurrence, TaskPriority, TaskSubmitted, MAX_TITLE_CHARS,
+};
+use crate::presentation::components::{text_input_layer, TextBuffer};
 use crate::presentation::theme::Theme;
+use gpui::prelude::*;
 use gpui::*;
+use std::ops::Range;
 
 /// A simple text input component for adding new tasks
 pub struct TaskInput {
     focus_handle: FocusHandle,
-    content: SharedString,
+    content: TextBuffer,
 }
 
 impl TaskInput {
     pub fn new(cx: &mut App) -> Self {
         Self {
             focus_handle: cx.focus_handle(),
-            content: "".into(),
+            content: TextBuffer::default(),
         }
     }
 
+    /// Move keyboard focus into the input
+    pub fn focus(&self, window: &mut Window) {
+        self.focus_handle.focus(window);
+    }
+
+    /// Whether the input has keyboard focus
+    pub fn is_focused(&self, window: &Window) -> bool {
+        self.focus_handle.is_focused(window)
+    }
+
     fn submit(&mut self, cx: &mut Context<Self>) {
-        let content = self.content.to_string().trim().to_string();
+        let content = self.content.text().trim().to_string();
         if !content.is_empty() {
             cx.emit(TaskSubmitted(content));
-            self.content = "".into();
+            self.content.clear();
+        }
+    }
+
+    /// Handle Ctrl/Cmd + V, C and X, returning true if the key was a clipboard shortcut
+    fn handle_clipboard(&mut self, keystroke: &Keystroke, cx: &mut Context<Self>) -> bool {
+        if !keystroke.modifiers.secondary() {
+            return false;
+        }
+
+        match keystroke.key.as_str() {
+            "v" => {
+                if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
+                    self.content.paste(&text);
+                }
+            }
+            "c" => {
+                cx.write_to_clipboard(ClipboardItem::new_string(self.content.text().to_string()));
+            }
+            "x" => {
+                cx.write_to_clipboard(ClipboardItem::new_string(self.content.text().to_string()));
+                self.content.clear();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Preview what quick-add will make of the current text
+    /// Shown only once the text contains a recognized token.
+    fn render_hints(&self) -> Option<Div> {
+        if self.content.is_empty() {
+            return None;
+        }
+        let now = chrono::Local::now();
+        let parsed = parse_task_input(self.content.text(), now);
+        let has_tokens = parsed.priority.is_some()
+            || parsed.due_date.is_some()
+            || parsed.recurrence.is_some()
+            || parsed.effort.is_some()
+            || parsed.reminder_lead.is_some()
+            || !parsed.tags.is_empty();
+        if !has_tokens {
+            return None;
+        }
+
+        let chip = |text: String, color: Rgba, bg: Rgba| {
+            div()
+                .px_1()
+                .py_0()
+                .rounded(px(Theme::RADIUS_SM))
+                .bg(bg)
+                .text_color(color)
+                .text_xs()
+                .child(text)
+        };
+
+        let mut row =
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .px(px(Theme::PADDING_MD))
+                .text_xs()
+                .child(div().text_color(Theme::text_secondary()).child(
+                    if parsed.content.is_empty() {
+                        "(no content)".to_string()
+                    } else {
+                        parsed.content.clone()
+                    },
+                ));
+
+        if let Some(priority) = parsed.priority {
+            let (label, color, bg) = match priority {
+                TaskPriority::Urgent => (
+                    "Urgent",
+                    Theme::priority_urgent(),
+                    Theme::priority_urgent_bg(),
+                ),
+                TaskPriority::High => ("High", Theme::priority_high(), Theme::priority_high_bg()),
+                TaskPriority::Medium => (
+                    "Medium",
+                    Theme::priority_medium(),
+                    Theme::priority_medium_bg(),
+                ),
+                TaskPriority::Low => ("Low", Theme::priority_low(), Theme::priority_low_bg()),
+            };
+            row = row.child(chip(label.to_string(), color, bg));
+        }
+
+        if let Some(due) = parsed.due_date {
+            let mut label = format!("📅 {}", due.format("%a %m/%d"));
+            if parsed.due_has_time {
+                label = format!("{} {}", label, due.format("%-I:%M %p"));
+            }
+            row = row.child(chip(label, Theme::text_primary(), Theme::surface()));
+        }
+
+        if let Some(recurrence) = parsed.recurrence {
+            let label = match recurrence {
+                Recurrence::Daily => "↻ Daily",
+                Recurrence::Weekly => "↻ Weekly",
+                Recurrence::Monthly => "↻ Monthly",
+            };
+            row = row.child(chip(
+                label.to_string(),
+                Theme::text_primary(),
+                Theme::surface(),
+            ));
         }
+
+        if let Some(effort) = parsed.effort {
+            row = row.child(chip(
+                format!("~{}", format_effort(effort)),
+                Theme::text_primary(),
+                Theme::surface(),
+            ));
+        }
+
+        if let Some(lead) = parsed.reminder_lead {
+            row = row.child(chip(
+                format!("🔔 {} before", format_effort(lead)),
+                Theme::text_primary(),
+                Theme::surface(),
+            ));
+        }
+
+        for tag in &parsed.tags {
+            row = row.child(chip(
+                format!("#{}", tag),
+                Theme::tag_color(tag),
+                Theme::tag_color_bg(tag),
+            ));
+        }
+
+        Some(row)
     }
 }
 
 impl EventEmitter<TaskSubmitted> for TaskInput {}
 
+/// Typed and composed text arrives here from the platform, see `text_input_layer`
+impl EntityInputHandler for TaskInput {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        adjusted_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let (text, range) = self.content.text_for_range(range_utf16);
+        *adjusted_range = Some(range);
+        Some(text)
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.content.selected_range(),
+            reversed: false,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.content.marked_range()
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.content.unmark();
+        cx.notify();
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        text: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.content.replace_text(range_utf16, text);
+        cx.notify();
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.content
+            .replace_and_mark_text(range_utf16, new_text, new_selected_range_utf16);
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        element_bounds: Bounds<Pixels>,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        Some(
+            self.content
+                .bounds_for_range(range_utf16, element_bounds, window),
+        )
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        _point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        None
+    }
+}
+
 impl Render for TaskInput {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let focus_handle = self.focus_handle.clone();
+        let focused = focus_handle.is_focused(window);
+        let can_submit = !self.content.text().trim().is_empty();
+        // Counted without the quick-add tokens, the same way the title will be
+        let title_chars = if self.content.text().chars().count() > Theme::INPUT_COUNT_THRESHOLD {
+            parse_task_input(self.content.text(), chrono::Local::now())
+                .content
+                .chars()
+                .count()
+        } else {
+            0
+        };
 
         div()
             .id("task-input-container")
             .w_full()
             .px(px(Theme::PADDING_LG))
             .py(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_1()
             .child(
                 div()
                     .id("task-input")
@@ -46,7 +295,7 @@ impl Render for TaskInput {
                     .bg(Theme::surface())
                     .rounded(px(Theme::RADIUS_MD))
                     .border_1()
-                    .border_color(rgba(0xffffff10))
+                    .border_color(Theme::border())
                     .flex()
                     .items_center()
                     .gap(px(Theme::PADDING_SM))
@@ -62,48 +311,80 @@ impl Render for TaskInput {
                     .child(
                         div()
                             .flex_1()
+                            .relative()
                             .text_color(if self.content.is_empty() {
                                 Theme::text_secondary()
                             } else {
                                 Theme::text_primary()
                             })
                             .child(if self.content.is_empty() {
-                                "Add a new task to overcome...".into()
+                                div()
+                                    .child("Add a new task to overcome...")
+                                    .into_any_element()
                             } else {
-                                self.content.clone()
-                            }),
+                                self.content.render_with_caret(focused).into_any_element()
+                            })
+                            .child(text_input_layer(focus_handle.clone(), cx.entity())),
+                    )
+                    .when(title_chars > Theme::INPUT_COUNT_THRESHOLD, |this| {
+                        this.child(
+                            div()
+                                .flex_shrink_0()
+                                .text_xs()
+                                .text_color(if title_chars > MAX_TITLE_CHARS {
+                                    Theme::accent_warning()
+                                } else {
+                                    Theme::text_secondary()
+                                })
+                                .child(format!("{}/{}", title_chars, MAX_TITLE_CHARS)),
+                        )
+                    })
+                    // Submit button for mouse and touch, dimmed until there's something to add
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .px(px(Theme::PADDING_SM))
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::accent_primary())
+                            .when(!can_submit, |this| this.opacity(0.3))
+                            .when(can_submit, |this| {
+                                this.cursor_pointer()
+                                    .hover(|s| s.bg(Theme::surface_hover()))
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(
+                                            |this, _event: &MouseDownEvent, _window, cx| {
+                                                this.submit(cx);
+                                                cx.notify();
+                                            },
+                                        ),
+                                    )
+                            })
+                            .child("+ Add"),
                     )
                     .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
-                        match &event.keystroke.key {
-                            key if key == "enter" => {
+                        // While an input method is composing, Enter, Backspace and the
+                        // arrows are its own
+                        if this.content.is_composing() {
+                            return;
+                        }
+                        if event.keystroke.key == "enter" && !event.keystroke.modifiers.shift {
+                            // Enter on an empty input is left to the list, where
+                            // Ctrl+Enter completes the top task
+                            if !this.content.text().trim().is_empty() {
                                 this.submit(cx);
-                                cx.notify();
-                            }
-                            key if key == "backspace" => {
-                                let mut content = this.content.to_string();
-                                content.pop();
-                                this.content = content.into();
-                                cx.notify();
-                            }
-                            key if key == "space" => {
-                                let mut content = this.content.to_string();
-                                content.push(' ');
-                                this.content = content.into();
-                                cx.notify();
-                            }
-                            key if key.len() == 1 => {
-                                let mut content = this.content.to_string();
-                                if event.keystroke.modifiers.shift {
-                                    content.push_str(&key.to_uppercase());
-                                } else {
-                                    content.push_str(key);
-                                }
-                                this.content = content.into();
-                                cx.notify();
+                                cx.stop_propagation();
                             }
-                            _ => {}
+                            cx.notify();
+                        } else if this.handle_clipboard(&event.keystroke, cx) {
+                            cx.notify();
+                        } else if this.content.handle_key(&event.keystroke) {
+                            cx.stop_propagation();
+                            cx.notify();
                         }
                     })),
             )
+            .children(self.render_hints())
     }
 }
@@ -1,28 +1,168 @@
-use crate::domain::{Task, TaskId};
-use crate::presentation::animations::WaloyoAnimations;
+use crate::domain::{
+    format_effort, fuzzy_match_ranges, humanize_due, humanize_relative, Recurrence, Subtask, Task,
+    TaskId, TaskPriority, TaskState,
+};
+use crate::presentation::animations::{
+    fling_opacity, rain_intensity, wind_sway_easing, WaloyoAnimations,
+};
 use crate::presentation::theme::Theme;
 use gpui::prelude::*;
 use gpui::*;
+use std::time::Duration;
 
 /// Type alias for task event handlers
 pub type TaskEventHandler = Box<dyn Fn(TaskId, &mut Window, &mut App) + 'static>;
 
+/// Type alias for context menu handlers, receiving where the card was right-clicked
+pub type ContextMenuHandler = Box<dyn Fn(TaskId, Point<Pixels>, &mut Window, &mut App) + 'static>;
+
+/// Type alias for priority chip handlers, told whether to step up (true) or down
+pub type PriorityCycleHandler = Box<dyn Fn(TaskId, bool, &mut Window, &mut App) + 'static>;
+
+/// Type alias for fling handlers, told what the pointer did on the card
+pub type FlingHandler = Box<dyn Fn(TaskId, FlingEvent, &mut Window, &mut App) + 'static>;
+
+/// What the pointer did on a card that can be flung away
+/// Positions are window x coordinates; the owner works out the offset and
+/// hands it back through `TaskItem::fling`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FlingEvent {
+    Press(f32),
+    Move(f32),
+    Release,
+}
+
+/// Where a card is in a fling to the right, which deletes it when let go far enough
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fling {
+    /// Following the pointer, this many pixels right of where it was pressed
+    Dragging(f32),
+    /// Let go short of `Theme::FLING_DELETE_DISTANCE`, springing back from this offset
+    Returning(f32),
+    /// Let go past it, blowing away from this offset
+    BlowingAway(f32),
+}
+
+/// Type alias for checklist step event handlers, receiving the parent task and step id
+pub type SubtaskEventHandler = Box<dyn Fn(TaskId, u64, &mut Window, &mut App) + 'static>;
+
+/// An id for one of a task's elements, unique per task
+/// Built from a static name and the task's number, so the ids made for a card
+/// on every animation frame allocate nothing, where the formatted names they
+/// replace allocated for each of up to six ids per card.
+fn task_element_id(name: &'static str, task_id: TaskId) -> ElementId {
+    ElementId::NamedInteger(name.into(), task_id.0)
+}
+
+/// What a card shows of its task
+/// Copied out of the task when the card is built, since the list is rendered
+/// again on every animation frame: notes are only checked for, the completion
+/// note only copied once the task is done, and the rest are ids, enums and
+/// `SharedString`s that are cheap to clone. That's fewer allocations per frame
+/// than cloning the list and then each task took, and none for a plain task;
+/// see the tests below.
+struct TaskCard {
+    id: TaskId,
+    content: SharedString,
+    state: TaskState,
+    priority: TaskPriority,
+    due_date: Option<chrono::DateTime<chrono::Local>>,
+    due_has_time: bool,
+    overdue: bool,
+    tags: Vec<SharedString>,
+    subtasks: Vec<Subtask>,
+    effort: Option<u32>,
+    reminder_lead: Option<u32>,
+    recurrence: Option<Recurrence>,
+    has_notes: bool,
+    completion_note: Option<SharedString>,
+    created_at: chrono::DateTime<chrono::Local>,
+    pinned: bool,
+}
+
+impl TaskCard {
+    fn new(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            content: task.content.clone(),
+            state: task.state,
+            priority: task.priority,
+            due_date: task.due_date,
+            due_has_time: task.has_due_time(),
+            overdue: task.is_overdue(chrono::Local::now()),
+            tags: task.tags.clone(),
+            subtasks: task.subtasks.clone(),
+            effort: task.effort,
+            reminder_lead: task.reminder_lead,
+            recurrence: task.recurrence,
+            has_notes: task.notes.is_some(),
+            completion_note: task
+                .completion_note
+                .as_deref()
+                .filter(|_| task.is_done())
+                .map(|note| SharedString::from(note.to_string())),
+            created_at: task.created_at,
+            pinned: task.pinned,
+        }
+    }
+}
+
 /// A single task item component - the "wind" element
 #[derive(IntoElement)]
 pub struct TaskItem {
-    task: Task,
+    task: TaskCard,
     on_complete: Option<TaskEventHandler>,
+    on_reopen: Option<TaskEventHandler>,
     on_delete: Option<TaskEventHandler>,
     on_click_content: Option<TaskEventHandler>,
+    on_click_notes: Option<TaskEventHandler>,
+    confirming_delete: bool,
+    selected: bool,
+    entering: bool,
+    expanded: bool,
+    on_edit: Option<TaskEventHandler>,
+    on_add_subtask: Option<TaskEventHandler>,
+    on_toggle_subtask: Option<SubtaskEventHandler>,
+    on_remove_subtask: Option<SubtaskEventHandler>,
+    checked: bool,
+    selecting: bool,
+    on_toggle_select: Option<TaskEventHandler>,
+    on_toggle_pin: Option<TaskEventHandler>,
+    on_context_menu: Option<ContextMenuHandler>,
+    on_cycle_priority: Option<PriorityCycleHandler>,
+    compact: bool,
+    highlight_query: SharedString,
+    fling: Option<Fling>,
+    on_fling: Option<FlingHandler>,
 }
 
 impl TaskItem {
-    pub fn new(task: Task) -> Self {
+    pub fn new(task: &Task) -> Self {
         Self {
-            task,
+            task: TaskCard::new(task),
             on_complete: None,
+            on_reopen: None,
             on_delete: None,
             on_click_content: None,
+            on_click_notes: None,
+            confirming_delete: false,
+            selected: false,
+            entering: false,
+            expanded: false,
+            on_edit: None,
+            on_add_subtask: None,
+            on_toggle_subtask: None,
+            on_remove_subtask: None,
+            checked: false,
+            selecting: false,
+            on_toggle_select: None,
+            on_toggle_pin: None,
+            on_context_menu: None,
+            on_cycle_priority: None,
+            compact: false,
+            highlight_query: SharedString::default(),
+            fling: None,
+            on_fling: None,
         }
     }
 
@@ -34,6 +174,12 @@ impl TaskItem {
         self
     }
 
+    /// Called when a done task's indicator is clicked, to move it back to pending
+    pub fn on_reopen(mut self, handler: impl Fn(TaskId, &mut Window, &mut App) + 'static) -> Self {
+        self.on_reopen = Some(Box::new(handler));
+        self
+    }
+
     pub fn on_delete(mut self, handler: impl Fn(TaskId, &mut Window, &mut App) + 'static) -> Self {
         self.on_delete = Some(Box::new(handler));
         self
@@ -46,14 +192,162 @@ impl TaskItem {
         self.on_click_content = Some(Box::new(handler));
         self
     }
+
+    pub fn on_click_notes(
+        mut self,
+        handler: impl Fn(TaskId, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_click_notes = Some(Box::new(handler));
+        self
+    }
+
+    /// Show the delete button as an armed "Confirm?" that deletes on the next click
+    pub fn confirming_delete(mut self, confirming: bool) -> Self {
+        self.confirming_delete = confirming;
+        self
+    }
+
+    /// Draw the keyboard selection ring around the card
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Play the gust-in entrance instead of the wind sway, for a just-added task
+    pub fn entering(mut self, entering: bool) -> Self {
+        self.entering = entering;
+        self
+    }
+
+    /// Called when the star is clicked, to pin the task to the top or unpin it
+    pub fn on_toggle_pin(
+        mut self,
+        handler: impl Fn(TaskId, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle_pin = Some(Box::new(handler));
+        self
+    }
+
+    /// Called when the card is right-clicked, with the cursor position
+    pub fn on_context_menu(
+        mut self,
+        handler: impl Fn(TaskId, Point<Pixels>, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_context_menu = Some(Box::new(handler));
+        self
+    }
+
+    /// Called when the priority chip is clicked: `true` to raise it, `false`
+    /// (right-click) to lower it
+    pub fn on_cycle_priority(
+        mut self,
+        handler: impl Fn(TaskId, bool, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_cycle_priority = Some(Box::new(handler));
+        self
+    }
+
+    /// Highlight the characters of the content that a search query matched
+    pub fn highlight(mut self, query: impl Into<SharedString>) -> Self {
+        self.highlight_query = query.into();
+        self
+    }
+
+    /// Show the card partway through a fling, see `on_fling`
+    pub fn fling(mut self, fling: Option<Fling>) -> Self {
+        self.fling = fling;
+        self
+    }
+
+    /// Let a pending card be dragged to the right and flung away
+    /// The handler hears where the pointer went; the owner keeps the offset.
+    pub fn on_fling(
+        mut self,
+        handler: impl Fn(TaskId, FlingEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_fling = Some(Box::new(handler));
+        self
+    }
+
+    /// Render a slim card with just the content, for dense views like the agenda
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Show the checklist of steps below the metadata row
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    pub fn on_edit(mut self, handler: impl Fn(TaskId, &mut Window, &mut App) + 'static) -> Self {
+        self.on_edit = Some(Box::new(handler));
+        self
+    }
+
+    pub fn on_add_subtask(
+        mut self,
+        handler: impl Fn(TaskId, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_add_subtask = Some(Box::new(handler));
+        self
+    }
+
+    pub fn on_toggle_subtask(
+        mut self,
+        handler: impl Fn(TaskId, u64, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle_subtask = Some(Box::new(handler));
+        self
+    }
+
+    pub fn on_remove_subtask(
+        mut self,
+        handler: impl Fn(TaskId, u64, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_remove_subtask = Some(Box::new(handler));
+        self
+    }
+
+    /// Mark the task as part of the multi-selection
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Show the multi-select checkbox, while any task is selected
+    pub fn selecting(mut self, selecting: bool) -> Self {
+        self.selecting = selecting;
+        self
+    }
+
+    /// Called on Shift/Ctrl-click or a checkbox click, to add or drop the task
+    /// from the multi-selection
+    pub fn on_toggle_select(
+        mut self,
+        handler: impl Fn(TaskId, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_toggle_select = Some(Box::new(handler));
+        self
+    }
+}
+
+/// Format how long ago a task was added, e.g. "added 3h ago"
+fn format_age(created_at: chrono::DateTime<chrono::Local>) -> String {
+    format!(
+        "added {}",
+        humanize_relative(created_at, chrono::Local::now())
+    )
 }
 
 impl RenderOnce for TaskItem {
     fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
         let task_id = self.task.id;
-        let is_pending = self.task.is_pending();
-        let is_completing = self.task.is_completing();
-        let is_done = self.task.is_done();
+        let is_pending = self.task.state == TaskState::Pending;
+        let is_completing = self.task.state == TaskState::Completing;
+        let is_done = self.task.state == TaskState::Done;
+        let is_overdue = is_pending && self.task.overdue;
 
         let content_color = if is_done {
             Theme::text_secondary()
@@ -69,8 +363,39 @@ impl RenderOnce for TaskItem {
 
         // Prepare handlers
         let on_complete = self.on_complete.map(std::sync::Arc::new);
+        let on_reopen = self.on_reopen.map(std::sync::Arc::new);
         let on_delete = self.on_delete.map(std::sync::Arc::new);
         let on_click_content = self.on_click_content.map(std::sync::Arc::new);
+        let on_click_notes = self.on_click_notes.map(std::sync::Arc::new);
+        let on_edit = self.on_edit.map(std::sync::Arc::new);
+        let on_add_subtask = self.on_add_subtask.map(std::sync::Arc::new);
+        let on_toggle_subtask = self.on_toggle_subtask.map(std::sync::Arc::new);
+        let on_remove_subtask = self.on_remove_subtask.map(std::sync::Arc::new);
+        let on_toggle_select = self.on_toggle_select.map(std::sync::Arc::new);
+        let on_toggle_pin = self.on_toggle_pin.filter(|_| is_pending);
+        let on_context_menu = self.on_context_menu;
+
+        // Multi-select checkbox
+        let checked = self.checked;
+        let checkbox = on_toggle_select
+            .clone()
+            .filter(|_| checked || self.selecting)
+            .map(|handler| {
+                div()
+                    .flex_shrink_0()
+                    .cursor_pointer()
+                    .text_sm()
+                    .text_color(if checked {
+                        Theme::accent_primary()
+                    } else {
+                        Theme::text_secondary()
+                    })
+                    .child(if checked { "☑" } else { "☐" })
+                    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                        cx.stop_propagation();
+                        handler(task_id, window, cx);
+                    })
+            });
 
         // Build state indicator
         let mut indicator = div()
@@ -96,18 +421,62 @@ impl RenderOnce for TaskItem {
                 );
             }
         }
+        if is_done {
+            if let Some(handler) = on_reopen {
+                indicator = indicator
+                    .cursor_pointer()
+                    .hover(|style| style.bg(Theme::state_pending()))
+                    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                        handler(task_id, window, cx);
+                    });
+            }
+        }
 
         // Build Content Area
-        let mut content_area = div().flex_1().flex().flex_col().gap_1().child(
+        let compact = self.compact;
+        let query = self.highlight_query.trim();
+        let matched = (!query.is_empty())
+            .then(|| fuzzy_match_ranges(query, &self.task.content))
+            .flatten()
+            .filter(|ranges| !ranges.is_empty());
+        let content = match matched {
+            Some(ranges) => {
+                let style = HighlightStyle {
+                    color: Some(Theme::accent_primary().into()),
+                    font_weight: Some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+                StyledText::new(self.task.content.clone())
+                    .with_highlights(ranges.into_iter().map(|range| (range, style)))
+                    .into_any_element()
+            }
+            None => self.task.content.clone().into_any_element(),
+        };
+        // Long content wraps within the card and keeps its line breaks
+        let mut content_area = div().flex_1().min_w_0().flex().flex_col().gap_1().child(
             div()
+                .whitespace_normal()
+                .when(compact, |this| this.text_sm())
                 .text_color(content_color)
                 .when(is_done, |this| this.line_through())
-                .child(self.task.content.clone()),
+                .child(content),
         );
+        if let Some(note) = self.task.completion_note.clone() {
+            content_area = content_area.child(
+                div()
+                    .text_xs()
+                    .italic()
+                    .text_color(Theme::clear_sky_accent())
+                    .child(format!("✓ {}", note)),
+            );
+        }
 
         // Metadata row (Priority & Due Date)
-        if !is_done && !is_completing {
+        if !is_done && !is_completing && !compact {
             let (priority_color, priority_bg) = match self.task.priority {
+                crate::domain::TaskPriority::Urgent => {
+                    (Theme::priority_urgent(), Theme::priority_urgent_bg())
+                }
                 crate::domain::TaskPriority::High => {
                     (Theme::priority_high(), Theme::priority_high_bg())
                 }
@@ -122,39 +491,82 @@ impl RenderOnce for TaskItem {
             let mut meta_row = div().flex().items_center().gap_3();
 
             // Priority dot
+            let priority_chip = div()
+                .px_1()
+                .py_0()
+                .rounded(px(Theme::RADIUS_SM))
+                .bg(priority_bg)
+                .text_color(priority_color)
+                .text_xs()
+                .child(match self.task.priority {
+                    crate::domain::TaskPriority::Urgent => "Urgent",
+                    crate::domain::TaskPriority::High => "High",
+                    crate::domain::TaskPriority::Medium => "Medium",
+                    crate::domain::TaskPriority::Low => "Low",
+                });
+            let priority_chip = match self.on_cycle_priority.filter(|_| is_pending) {
+                Some(handler) => {
+                    let handler = std::sync::Arc::new(handler);
+                    let handler_back = handler.clone();
+                    priority_chip
+                        .cursor_pointer()
+                        .hover(|s| s.opacity(0.8))
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, true, window, cx);
+                        })
+                        .on_mouse_down(MouseButton::Right, move |_event, window, cx| {
+                            // Keep the card's context menu from opening too
+                            cx.stop_propagation();
+                            handler_back(task_id, false, window, cx);
+                        })
+                }
+                None => priority_chip,
+            };
             meta_row = meta_row.child(
-                div()
-                    .px_1()
-                    .py_0()
-                    .rounded(px(Theme::RADIUS_SM))
-                    .bg(priority_bg)
-                    .text_color(priority_color)
-                    .text_xs()
-                    .child(match self.task.priority {
-                        crate::domain::TaskPriority::High => "High",
-                        crate::domain::TaskPriority::Medium => "Medium",
-                        crate::domain::TaskPriority::Low => "Low",
-                    }),
+                if self.task.priority == crate::domain::TaskPriority::Urgent
+                    && !Theme::reduced_motion()
+                {
+                    // The storm has broken: the chip pulses
+                    priority_chip
+                        .with_animation(
+                            task_element_id("urgent-pulse", task_id),
+                            Animation::new(Duration::from_millis(1200))
+                                .repeat()
+                                .with_easing(|delta| wind_sway_easing(delta, 0.0)),
+                            |chip, delta| chip.opacity(0.55 + 0.45 * delta),
+                        )
+                        .into_any_element()
+                } else {
+                    priority_chip.into_any_element()
+                },
             );
 
+            // Tags
+            for tag in &self.task.tags {
+                meta_row = meta_row.child(
+                    div()
+                        .px_1()
+                        .py_0()
+                        .rounded(px(Theme::RADIUS_SM))
+                        .bg(Theme::tag_color_bg(tag))
+                        .text_color(Theme::tag_color(tag))
+                        .text_xs()
+                        .child(format!("#{}", tag)),
+                );
+            }
+
             // Due Date
             if let Some(due_date) = self.task.due_date {
-                let now = chrono::Local::now();
-                let today = now.date_naive();
-                let due_naive = due_date.date_naive();
-
-                let date_str = if due_naive == today {
-                    "Today".to_string()
-                } else if due_naive == today.succ_opt().unwrap_or(today) {
-                    "Tomorrow".to_string()
-                } else if due_naive < today {
-                    format!("Overdue: {}", due_naive.format("%m/%d"))
+                let (day_str, overdue) =
+                    humanize_due(due_date, self.task.due_has_time, chrono::Local::now());
+                let is_overdue = overdue && !is_done;
+                let date_str = if is_overdue {
+                    format!("Overdue: {}", day_str)
                 } else {
-                    due_naive.format("%m/%d").to_string()
+                    day_str
                 };
 
-                let is_overdue = due_naive < today;
-
                 meta_row = meta_row.child(
                     div()
                         .flex()
@@ -171,22 +583,206 @@ impl RenderOnce for TaskItem {
                 );
             }
 
-            // Notes indicator
-            if self.task.notes.is_some() {
+            // Checklist progress
+            if !self.task.subtasks.is_empty() {
+                let steps_done = self.task.subtasks.iter().filter(|s| s.done).count();
                 meta_row = meta_row.child(
                     div()
                         .flex()
                         .items_center()
                         .gap_1()
                         .text_xs()
+                        .text_color(if steps_done == self.task.subtasks.len() {
+                            Theme::state_done()
+                        } else {
+                            Theme::text_secondary()
+                        })
+                        .child("☑")
+                        .child(format!("{}/{}", steps_done, self.task.subtasks.len())),
+                );
+            }
+
+            // Effort estimate
+            if let Some(effort) = self.task.effort {
+                meta_row = meta_row.child(
+                    div()
+                        .px_1()
+                        .rounded(px(Theme::RADIUS_SM))
+                        .bg(Theme::surface_hover())
+                        .text_xs()
                         .text_color(Theme::text_secondary())
-                        .child("📝"),
+                        .child(format!("~{}", format_effort(effort))),
                 );
             }
 
+            // Early reminder
+            if let Some(lead) = self
+                .task
+                .reminder_lead
+                .filter(|_| is_pending && self.task.due_date.is_some())
+            {
+                meta_row = meta_row.child(
+                    div()
+                        .px_1()
+                        .rounded(px(Theme::RADIUS_SM))
+                        .bg(Theme::surface_hover())
+                        .text_xs()
+                        .text_color(Theme::text_secondary())
+                        .child(format!("🔔 {} before", format_effort(lead))),
+                );
+            }
+
+            // Recurrence indicator
+            if let Some(recurrence) = self.task.recurrence {
+                meta_row = meta_row.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_1()
+                        .text_xs()
+                        .text_color(Theme::text_secondary())
+                        .child("🔁")
+                        .child(match recurrence {
+                            Recurrence::Daily => "Daily",
+                            Recurrence::Weekly => "Weekly",
+                            Recurrence::Monthly => "Monthly",
+                        }),
+                );
+            }
+
+            // Notes indicator - faint when there are no notes yet but they can be added
+            let has_notes = self.task.has_notes;
+            if has_notes || on_click_notes.is_some() {
+                let mut notes_indicator = div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .when(!has_notes, |this| this.opacity(0.4))
+                    .child("📝");
+
+                if let Some(handler) = on_click_notes {
+                    notes_indicator = notes_indicator.cursor_pointer().on_mouse_down(
+                        MouseButton::Left,
+                        move |_event, window, cx| {
+                            // Keep the click from also starting a content edit
+                            cx.stop_propagation();
+                            handler(task_id, window, cx);
+                        },
+                    );
+                }
+
+                meta_row = meta_row.child(notes_indicator);
+            }
+
+            // Age hint
+            meta_row = meta_row.child(
+                div()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child(format_age(self.task.created_at)),
+            );
+
             content_area = content_area.child(meta_row);
         }
 
+        // Expanded checklist
+        if is_pending && self.expanded {
+            let mut checklist = div().flex().flex_col().gap_1().pt_1();
+
+            for subtask in &self.task.subtasks {
+                let subtask_id = subtask.id;
+                let mut checkbox = div()
+                    .flex_shrink_0()
+                    .text_xs()
+                    .text_color(if subtask.done {
+                        Theme::state_done()
+                    } else {
+                        Theme::text_secondary()
+                    })
+                    .child(if subtask.done { "☑" } else { "☐" });
+                if let Some(handler) = on_toggle_subtask.clone() {
+                    checkbox = checkbox.cursor_pointer().on_mouse_down(
+                        MouseButton::Left,
+                        move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, subtask_id, window, cx);
+                        },
+                    );
+                }
+
+                let remove_btn = on_remove_subtask.clone().map(|handler| {
+                    div()
+                        .px_1()
+                        .rounded(px(Theme::RADIUS_SM))
+                        .cursor_pointer()
+                        .text_xs()
+                        .text_color(Theme::text_secondary())
+                        .hover(|s| s.text_color(Theme::accent_error()))
+                        .child("×")
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, subtask_id, window, cx);
+                        })
+                });
+
+                checklist = checklist.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap(px(Theme::PADDING_SM))
+                        .child(checkbox)
+                        .child(
+                            div()
+                                .flex_1()
+                                .text_sm()
+                                .text_color(if subtask.done {
+                                    Theme::text_secondary()
+                                } else {
+                                    Theme::text_primary()
+                                })
+                                .when(subtask.done, |this| this.line_through())
+                                .child(subtask.content.clone()),
+                        )
+                        .when_some(remove_btn, |this, btn| this.child(btn)),
+                );
+            }
+
+            let mut actions = div()
+                .flex()
+                .items_center()
+                .gap_3()
+                .text_xs()
+                .text_color(Theme::text_secondary());
+            if let Some(handler) = on_add_subtask {
+                actions = actions.child(
+                    div()
+                        .cursor_pointer()
+                        .hover(|s| s.text_color(Theme::text_accent()))
+                        .child("+ Add step")
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, window, cx);
+                        }),
+                );
+            }
+            if let Some(handler) = on_edit {
+                actions = actions.child(
+                    div()
+                        .cursor_pointer()
+                        .hover(|s| s.text_color(Theme::text_accent()))
+                        .child("✎ Edit")
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, window, cx);
+                        }),
+                );
+            }
+
+            content_area = content_area.child(checklist.child(actions));
+        }
+
         if is_pending {
             if let Some(handler) = on_click_content {
                 content_area = content_area.cursor_pointer().on_mouse_down(
@@ -199,21 +795,36 @@ impl RenderOnce for TaskItem {
         }
 
         // Build Delete Button
+        let confirming_delete = self.confirming_delete;
         let delete_btn = if !is_completing {
             on_delete.map(|handler| {
                 div()
-                    .id(ElementId::Name(format!("delete-{}", task_id.0).into()))
-                    .w(px(24.0))
+                    .id(task_element_id("delete", task_id))
                     .h(px(24.0))
+                    .when(!confirming_delete, |this| this.w(px(24.0)))
+                    .when(confirming_delete, |this| {
+                        this.px(px(Theme::PADDING_SM))
+                            .bg(Theme::accent_error_bg())
+                            .text_xs()
+                    })
                     .rounded(px(4.0))
                     .flex()
                     .items_center()
                     .justify_center()
                     .cursor_pointer()
-                    .text_color(Theme::text_secondary())
-                    .hover(|s| s.bg(rgba(0xff000020)).text_color(Theme::accent_error()))
-                    .child("×")
+                    .text_color(if confirming_delete {
+                        Theme::accent_error()
+                    } else {
+                        Theme::text_secondary()
+                    })
+                    .hover(|s| {
+                        s.bg(Theme::accent_error_bg())
+                            .text_color(Theme::accent_error())
+                    })
+                    .child(if confirming_delete { "Confirm?" } else { "×" })
                     .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                        // Clicks anywhere else cancel an armed delete
+                        cx.stop_propagation();
                         handler(task_id, window, cx);
                     })
             })
@@ -221,31 +832,209 @@ impl RenderOnce for TaskItem {
             None
         };
 
+        // Star: a toggle on pending tasks, a plain marker elsewhere
+        let pinned = self.task.pinned;
+        let star = (pinned || on_toggle_pin.is_some()).then(|| {
+            div()
+                .flex_shrink_0()
+                .text_sm()
+                .when(!pinned, |this| this.opacity(0.3))
+                .child(if pinned { "⭐" } else { "☆" })
+                .when_some(on_toggle_pin, |this, handler| {
+                    this.cursor_pointer()
+                        .hover(|s| s.opacity(1.0))
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            handler(task_id, window, cx);
+                        })
+                })
+        });
+
+        // Fling to delete: only pending cards go with the wind
+        let fling = self.fling.filter(|_| is_pending);
+        let on_fling = self
+            .on_fling
+            .filter(|_| is_pending)
+            .map(std::sync::Arc::new);
+        let armed = matches!(
+            fling,
+            Some(Fling::Dragging(offset)) if offset >= Theme::FLING_DELETE_DISTANCE
+        );
+
         // Build the base card
         let base = div()
-            .id(ElementId::Name(format!("task-item-{}", task_id.0).into()))
+            .id(task_element_id("task-item", task_id))
             .w_full()
-            .px(px(Theme::PADDING_MD))
-            .py(px(Theme::PADDING_SM))
+            .px(px(if compact {
+                Theme::PADDING_SM
+            } else {
+                Theme::PADDING_MD
+            }))
+            .py(px(if compact {
+                Theme::PADDING_SM / 2.0
+            } else {
+                Theme::PADDING_SM
+            }))
             .bg(card_bg)
             .rounded(px(Theme::RADIUS_MD))
             .border_1()
-            .border_color(rgba(0xffffff10))
+            .border_color(if armed {
+                Theme::accent_error()
+            } else if self.selected || checked {
+                Theme::accent_primary()
+            } else {
+                Theme::border()
+            })
+            .when(checked, |this| this.bg(Theme::surface_hover()))
+            .when(armed, |this| this.bg(Theme::accent_error_bg()))
             .hover(|style| style.bg(Theme::surface_hover()))
             .flex()
             .items_center()
             .gap(px(Theme::PADDING_SM))
+            // Overdue accent along the left edge
+            .when(is_overdue, |this| {
+                this.child(
+                    div()
+                        .w(px(3.0))
+                        .self_stretch()
+                        .rounded(px(Theme::RADIUS_SM))
+                        .bg(Theme::accent_error())
+                        .opacity(0.7),
+                )
+            })
+            .when_some(checkbox, |this, checkbox| this.child(checkbox))
             .child(indicator)
             .child(content_area)
-            .when_some(delete_btn, |this, btn| this.child(btn));
+            .when_some(star, |this, star| this.child(star))
+            .when(armed, |this| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .text_color(Theme::accent_error())
+                        .child("Release to delete"),
+                )
+            })
+            .when_some(delete_btn, |this, btn| this.child(btn))
+            .when_some(on_context_menu, |this, handler| {
+                this.on_mouse_down(MouseButton::Right, move |event, window, cx| {
+                    cx.stop_propagation();
+                    handler(task_id, event.position, window, cx);
+                })
+            })
+            // Shift/Ctrl-click anywhere on the card toggles selection instead of
+            // completing, expanding or deleting
+            .when_some(on_toggle_select, |this, handler| {
+                this.capture_any_mouse_down(move |event, window, cx| {
+                    let modifiers = &event.modifiers;
+                    if event.button == MouseButton::Left
+                        && (modifiers.shift || modifiers.secondary())
+                    {
+                        cx.stop_propagation();
+                        handler(task_id, window, cx);
+                    }
+                })
+            })
+            // Press and drag right to fling; letting go anywhere ends the drag
+            .when_some(on_fling, |this, handler| {
+                let on_press = handler.clone();
+                let on_move = handler.clone();
+                let on_release = handler.clone();
+                this.on_mouse_down(MouseButton::Left, move |event, window, cx| {
+                    let x = f32::from(event.position.x);
+                    on_press(task_id, FlingEvent::Press(x), window, cx);
+                })
+                .on_mouse_move(move |event, window, cx| {
+                    if event.pressed_button == Some(MouseButton::Left) {
+                        let x = f32::from(event.position.x);
+                        on_move(task_id, FlingEvent::Move(x), window, cx);
+                    }
+                })
+                .on_mouse_up(MouseButton::Left, move |_event, window, cx| {
+                    on_release(task_id, FlingEvent::Release, window, cx);
+                })
+                .on_mouse_up_out(MouseButton::Left, move |_event, window, cx| {
+                    handler(task_id, FlingEvent::Release, window, cx);
+                })
+            });
 
         // Apply Metaphorical Animations (Mutually Exclusive)
-        if is_pending {
-            base.wind_sway(ElementId::Name(format!("sway-{}", task_id.0).into()), true)
+        if let Some(fling) = fling {
+            // The pointer, not the wind, moves a card being flung
+            match fling {
+                Fling::Dragging(offset) => base
+                    .ml(px(offset))
+                    .opacity(fling_opacity(offset))
+                    .into_any_element(),
+                Fling::Returning(from) => {
+                    base.fling_settle(task_element_id("fling-return", task_id), from, false)
+                }
+                Fling::BlowingAway(from) => {
+                    base.fling_settle(task_element_id("fling-away", task_id), from, true)
+                }
+            }
+        } else if is_pending && self.entering {
+            base.gust_in(task_element_id("gust", task_id), true)
+        } else if is_pending {
+            // Golden-ratio spacing spreads consecutive ids evenly around the cycle,
+            // and stays fixed for a task across re-renders
+            let phase = (task_id.0 as f64 * 0.618_033_988_75).fract() as f32;
+            base.wind_sway(task_element_id("sway", task_id), phase, true)
         } else if is_completing {
-            base.rain_drop(ElementId::Name(format!("rain-{}", task_id.0).into()), true)
+            let intensity = rain_intensity(self.task.priority);
+            base.rain_drop(task_element_id("rain", task_id), intensity, true)
         } else {
             base.into_any_element()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocations::allocations;
+
+    fn busy_task() -> Task {
+        let mut task = Task::new(SharedString::from("Plan the trip".to_string()));
+        task.notes = Some("Book early".to_string());
+        task.tags = vec!["travel".into(), "family".into()];
+        task.subtasks = vec![
+            Subtask {
+                id: 1,
+                content: "Flights".to_string(),
+                done: false,
+            },
+            Subtask {
+                id: 2,
+                content: "Hotel".to_string(),
+                done: true,
+            },
+        ];
+        task
+    }
+
+    #[test]
+    fn a_card_copies_less_than_the_clones_it_replaced() {
+        let task = busy_task();
+        // Reading the clock the first time sets up the timezone
+        drop(TaskCard::new(&task));
+
+        let list_copy = allocations(|| vec![task.clone()]) - 1;
+        let item_copy = allocations(|| task.clone());
+        assert!(allocations(|| TaskCard::new(&task)) < list_copy + item_copy);
+    }
+
+    #[test]
+    fn element_ids_allocate_nothing() {
+        let id = TaskId(42);
+        assert_eq!(allocations(|| task_element_id("task-item", id)), 0);
+    }
+
+    #[test]
+    fn a_card_for_a_plain_task_allocates_nothing() {
+        let mut task = Task::new("Water plants");
+        task.notes = Some("Only the ferns".to_string());
+        drop(TaskCard::new(&task));
+        assert_eq!(allocations(|| TaskCard::new(&task)), 0);
+    }
+}
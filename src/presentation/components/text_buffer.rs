@@ -0,0 +1,371 @@
+use crate::presentation::theme::Theme;
+use gpui::prelude::*;
+use gpui::*;
+use std::ops::Range;
+
+/// An invisible layer over an input's text that hands typed and composed text
+/// from the platform, input methods included, to `view` while `focus_handle`
+/// has focus
+/// Place it in a relatively positioned container around the text, which the
+/// input method's candidate window is placed against.
+pub fn text_input_layer<V: EntityInputHandler>(
+    focus_handle: FocusHandle,
+    view: Entity<V>,
+) -> impl IntoElement {
+    canvas(
+        |_bounds, _window, _cx| {},
+        move |bounds, _, window, cx| {
+            window.handle_input(&focus_handle, ElementInputHandler::new(bounds, view), cx);
+        },
+    )
+    .absolute()
+    .size_full()
+}
+
+/// Byte offset in `text` of the given UTF-16 offset, clamped to the end
+fn byte_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16 = 0;
+    for (index, c) in text.char_indices() {
+        if utf16 >= utf16_offset {
+            return index;
+        }
+        utf16 += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Editable text with a cursor, one or more lines long
+/// The cursor is a byte index that always sits on a char boundary. Text being
+/// composed by an input method is kept in the buffer as a marked range until
+/// it's committed.
+#[derive(Debug, Clone, Default)]
+pub struct TextBuffer {
+    text: String,
+    cursor: usize,
+    marked: Option<Range<usize>>,
+}
+
+impl TextBuffer {
+    /// Create a buffer with the cursor placed at the end of the text
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let cursor = text.len();
+        Self {
+            text,
+            cursor,
+            marked: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.marked = None;
+    }
+
+    pub fn insert(&mut self, s: &str) {
+        self.text.insert_str(self.cursor, s);
+        self.cursor += s.len();
+    }
+
+    /// Insert pasted text, flattening line breaks into spaces
+    pub fn paste(&mut self, s: &str) {
+        let line = s
+            .trim_end_matches(['\r', '\n'])
+            .replace("\r\n", " ")
+            .replace(['\r', '\n'], " ");
+        self.insert(&line);
+    }
+
+    /// Remove the character before the cursor
+    pub fn backspace(&mut self) {
+        if let Some(prev) = self.prev_boundary() {
+            self.text.replace_range(prev..self.cursor, "");
+            self.cursor = prev;
+        }
+    }
+
+    /// Remove the character after the cursor
+    pub fn delete(&mut self) {
+        if let Some(next) = self.next_boundary() {
+            self.text.replace_range(self.cursor..next, "");
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if let Some(prev) = self.prev_boundary() {
+            self.cursor = prev;
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if let Some(next) = self.next_boundary() {
+            self.cursor = next;
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.text.len();
+    }
+
+    /// Apply an editing key press, returning true if it was one
+    /// Moves the cursor, deletes, and starts a new line on Shift+Enter. Typed
+    /// text doesn't come through here but through the input handler, see
+    /// `replace_text`; plain Enter and Escape are left to the caller.
+    pub fn handle_key(&mut self, keystroke: &Keystroke) -> bool {
+        match keystroke.key.as_str() {
+            "enter" if keystroke.modifiers.shift => self.insert("\n"),
+            "left" => self.move_left(),
+            "right" => self.move_right(),
+            "home" => self.move_home(),
+            "end" => self.move_end(),
+            "backspace" => self.backspace(),
+            "delete" => self.delete(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether an input method is composing text; its keys are its own until then
+    pub fn is_composing(&self) -> bool {
+        self.marked.is_some()
+    }
+
+    /// The text in a UTF-16 range, and the range it actually covers
+    pub fn text_for_range(&self, range_utf16: Range<usize>) -> (String, Range<usize>) {
+        let range = self.range_from_utf16(&range_utf16);
+        let text = self.text[range.clone()].to_string();
+        (text, self.range_to_utf16(&range))
+    }
+
+    /// The cursor as an empty UTF-16 selection
+    pub fn selected_range(&self) -> Range<usize> {
+        let cursor = self.offset_to_utf16(self.cursor);
+        cursor..cursor
+    }
+
+    /// The text being composed, as a UTF-16 range
+    pub fn marked_range(&self) -> Option<Range<usize>> {
+        self.marked.as_ref().map(|range| self.range_to_utf16(range))
+    }
+
+    /// Keep the composed text as it is and stop composing
+    pub fn unmark(&mut self) {
+        self.marked = None;
+    }
+
+    /// Replace a UTF-16 range with committed text and stop composing
+    /// Without a range this replaces the text being composed, or inserts at
+    /// the cursor.
+    pub fn replace_text(&mut self, range_utf16: Option<Range<usize>>, text: &str) {
+        let range = self.replacement_range(range_utf16);
+        self.text.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+        self.marked = None;
+    }
+
+    /// Replace a UTF-16 range with text that's still being composed
+    /// `selected_utf16` is where the cursor goes, relative to the new text.
+    pub fn replace_and_mark_text(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        text: &str,
+        selected_utf16: Option<Range<usize>>,
+    ) {
+        let range = self.replacement_range(range_utf16);
+        self.text.replace_range(range.clone(), text);
+        self.marked = (!text.is_empty()).then(|| range.start..range.start + text.len());
+        self.cursor = range.start
+            + selected_utf16.map_or(text.len(), |selected| byte_offset(text, selected.end));
+    }
+
+    /// Where the text in a UTF-16 range sits, for placing the input method's
+    /// candidate window next to it
+    pub fn bounds_for_range(
+        &self,
+        range_utf16: Range<usize>,
+        element_bounds: Bounds<Pixels>,
+        window: &mut Window,
+    ) -> Bounds<Pixels> {
+        let start = self.range_from_utf16(&range_utf16).start;
+        let line_start = self.text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let row = self.text[..line_start].matches('\n').count();
+        let prefix = &self.text[line_start..start];
+
+        let style = window.text_style();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line_height = window.line_height();
+        let x = window
+            .text_system()
+            .shape_line(
+                prefix.to_string().into(),
+                font_size,
+                &[style.to_run(prefix.len())],
+                None,
+            )
+            .width;
+
+        Bounds::new(
+            point(
+                element_bounds.left() + x,
+                element_bounds.top() + line_height * row as f32,
+            ),
+            size(px(1.0), line_height),
+        )
+    }
+
+    /// Render the text line by line, with a caret at the cursor position and
+    /// text being composed underlined
+    pub fn render_with_caret(&self, show_caret: bool) -> Div {
+        let mut start = 0;
+        let lines = self.text.split('\n').map(|line| {
+            let row = self.render_line(line, start, show_caret);
+            start += line.len() + 1;
+            row
+        });
+
+        div().flex().flex_col().children(lines)
+    }
+
+    /// One line of the text, starting at byte `start`, cut into pieces at the
+    /// cursor and the ends of the composed text
+    fn render_line(&self, line: &str, start: usize, show_caret: bool) -> Div {
+        let end = start + line.len();
+        let local = |offset: usize| offset.clamp(start, end) - start;
+        let cursor = (start..=end)
+            .contains(&self.cursor)
+            .then(|| self.cursor - start);
+        let marked = self
+            .marked
+            .as_ref()
+            .map(|range| local(range.start)..local(range.end))
+            .filter(|range| !range.is_empty());
+
+        let mut cuts = vec![0, line.len()];
+        cuts.extend(cursor);
+        if let Some(range) = &marked {
+            cuts.extend([range.start, range.end]);
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let mut row = div().flex().flex_wrap().items_center().min_h(px(16.0));
+        for (i, &cut) in cuts.iter().enumerate() {
+            if show_caret && cursor == Some(cut) {
+                row = row.child(div().w(px(1.0)).h(px(16.0)).bg(Theme::accent_primary()));
+            }
+            if let Some(&next) = cuts.get(i + 1) {
+                let piece = div().child(line[cut..next].to_string());
+                let composing = marked
+                    .as_ref()
+                    .is_some_and(|range| range.start <= cut && next <= range.end);
+                row = row.child(if composing { piece.underline() } else { piece });
+            }
+        }
+        row
+    }
+
+    /// The byte range a replacement covers: the given UTF-16 range, else the
+    /// text being composed, else the cursor
+    fn replacement_range(&self, range_utf16: Option<Range<usize>>) -> Range<usize> {
+        range_utf16
+            .map(|range| self.range_from_utf16(&range))
+            .or_else(|| self.marked.clone())
+            .unwrap_or(self.cursor..self.cursor)
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        self.text[..offset].chars().map(char::len_utf16).sum()
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        let start = byte_offset(&self.text, range_utf16.start);
+        let end = byte_offset(&self.text, range_utf16.end);
+        start.min(end)..end
+    }
+
+    fn prev_boundary(&self) -> Option<usize> {
+        self.text[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.text[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(source: &str) -> Keystroke {
+        Keystroke::parse(source).unwrap()
+    }
+
+    #[test]
+    fn composed_text_is_marked_until_committed() {
+        let mut buffer = TextBuffer::new("买");
+        buffer.replace_and_mark_text(None, "ni", None);
+        assert_eq!(buffer.text(), "买ni");
+        assert!(buffer.is_composing());
+        assert_eq!(buffer.marked_range(), Some(1..3));
+
+        buffer.replace_and_mark_text(None, "niu", None);
+        assert_eq!(buffer.text(), "买niu");
+        buffer.replace_text(None, "牛奶");
+        assert_eq!(buffer.text(), "买牛奶");
+        assert!(!buffer.is_composing());
+        assert_eq!(buffer.selected_range(), 3..3);
+    }
+
+    #[test]
+    fn ranges_are_counted_in_utf16() {
+        let mut buffer = TextBuffer::new("a😀b");
+        assert_eq!(buffer.text_for_range(1..3), ("😀".to_string(), 1..3));
+        buffer.replace_text(Some(1..3), "!");
+        assert_eq!(buffer.text(), "a!b");
+        assert_eq!(buffer.selected_range(), 2..2);
+    }
+
+    #[test]
+    fn editing_keys_respect_char_boundaries() {
+        let mut buffer = TextBuffer::new("晴天");
+        assert!(buffer.handle_key(&key("backspace")));
+        assert_eq!(buffer.text(), "晴");
+        assert!(buffer.handle_key(&key("home")));
+        assert!(buffer.handle_key(&key("delete")));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn typed_characters_are_left_to_the_input_handler() {
+        let mut buffer = TextBuffer::default();
+        assert!(!buffer.handle_key(&key("a")));
+        assert!(!buffer.handle_key(&key("shift-1")));
+        assert!(!buffer.handle_key(&key("enter")));
+        assert!(buffer.is_empty());
+        assert!(buffer.handle_key(&key("shift-enter")));
+        assert_eq!(buffer.text(), "\n");
+    }
+}
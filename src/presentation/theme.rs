@@ -1,4 +1,166 @@
-use gpui::{rgb, rgba, Rgba};
+use crate::domain::ThemeVariant;
+use gpui::{hsla, rgb, rgba, Rgba};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+
+/// Whether the light palette is active. A process-wide switch so that every
+/// `Theme::*()` call site picks up the change on the next render.
+static LIGHT_VARIANT: AtomicBool = AtomicBool::new(false);
+
+/// Whether animations are turned off, switched the same way as the palette
+static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+/// Chip colors the user chose for tags, keyed by lowercase tag name
+static TAG_COLORS: RwLock<BTreeMap<String, Rgba>> = RwLock::new(BTreeMap::new());
+
+/// Colors loaded from the user's theme file, replacing the built-in dark palette
+static CUSTOM_COLORS: OnceLock<ThemeColors> = OnceLock::new();
+
+/// The full set of colors making up one palette
+#[derive(Debug, Clone, Copy)]
+pub struct ThemeColors {
+    pub background: Rgba,
+    pub surface: Rgba,
+    pub surface_hover: Rgba,
+    pub border: Rgba,
+    pub text_primary: Rgba,
+    pub text_secondary: Rgba,
+    pub text_accent: Rgba,
+    pub state_pending: Rgba,
+    pub state_completing: Rgba,
+    pub state_done: Rgba,
+    pub priority_urgent: Rgba,
+    pub priority_urgent_bg: Rgba,
+    pub priority_high: Rgba,
+    pub priority_high_bg: Rgba,
+    pub priority_medium: Rgba,
+    pub priority_medium_bg: Rgba,
+    pub priority_low: Rgba,
+    pub priority_low_bg: Rgba,
+    pub tag: Rgba,
+    pub tag_bg: Rgba,
+    pub accent_primary: Rgba,
+    pub accent_warning: Rgba,
+    pub accent_error: Rgba,
+    pub accent_error_bg: Rgba,
+    pub clear_sky_background: Rgba,
+    pub clear_sky_accent: Rgba,
+}
+
+impl ThemeColors {
+    /// The storm at night - based on Tokyo Night
+    pub fn dark() -> Self {
+        Self {
+            background: rgb(0x1a1b26),
+            surface: rgb(0x24283b),
+            surface_hover: rgb(0x2f3549),
+            border: rgba(0xffffff10),
+            text_primary: rgb(0xa9b1d6),
+            text_secondary: rgb(0x565f89),
+            text_accent: rgb(0x7aa2f7),
+            state_pending: rgb(0x414868),
+            state_completing: rgb(0x7aa2f7),
+            state_done: rgb(0x9ece6a),
+            priority_urgent: rgb(0xff4d6d),
+            priority_urgent_bg: rgba(0xff4d6d33),
+            priority_high: rgb(0xf7768e),
+            priority_high_bg: rgba(0xf7768e1a),
+            priority_medium: rgb(0xe0af68),
+            priority_medium_bg: rgba(0xe0af681a),
+            priority_low: rgb(0x565f89),
+            priority_low_bg: rgba(0x565f891a),
+            tag: rgb(0x7aa2f7),
+            tag_bg: rgba(0x7aa2f71a),
+            accent_primary: rgb(0x7aa2f7),
+            accent_warning: rgb(0xe0af68),
+            accent_error: rgb(0xf7768e),
+            accent_error_bg: rgba(0xff000020),
+            clear_sky_background: rgb(0x1a1f36),
+            clear_sky_accent: rgb(0xffc777),
+        }
+    }
+
+    /// The storm by day - based on Tokyo Night Day
+    pub fn light() -> Self {
+        Self {
+            background: rgb(0xe1e2e7),
+            surface: rgb(0xf4f5f8),
+            surface_hover: rgb(0xd5d6db),
+            border: rgba(0x0000001a),
+            text_primary: rgb(0x343b58),
+            text_secondary: rgb(0x848cb5),
+            text_accent: rgb(0x2e7de9),
+            state_pending: rgb(0xa8aecb),
+            state_completing: rgb(0x2e7de9),
+            state_done: rgb(0x587539),
+            priority_urgent: rgb(0xd7263d),
+            priority_urgent_bg: rgba(0xd7263d26),
+            priority_high: rgb(0xf52a65),
+            priority_high_bg: rgba(0xf52a651a),
+            priority_medium: rgb(0x8c6c3e),
+            priority_medium_bg: rgba(0x8c6c3e1a),
+            priority_low: rgb(0x848cb5),
+            priority_low_bg: rgba(0x848cb51a),
+            tag: rgb(0x2e7de9),
+            tag_bg: rgba(0x2e7de91a),
+            accent_primary: rgb(0x2e7de9),
+            accent_warning: rgb(0x8c6c3e),
+            accent_error: rgb(0xf52a65),
+            accent_error_bg: rgba(0xf52a6520),
+            clear_sky_background: rgb(0xf0ead8),
+            clear_sky_accent: rgb(0xb15c00),
+        }
+    }
+
+    /// Every palette entry paired with its key in a theme file
+    fn entries_mut(&mut self) -> [(&'static str, &mut Rgba); 26] {
+        [
+            ("background", &mut self.background),
+            ("surface", &mut self.surface),
+            ("surface_hover", &mut self.surface_hover),
+            ("border", &mut self.border),
+            ("text_primary", &mut self.text_primary),
+            ("text_secondary", &mut self.text_secondary),
+            ("text_accent", &mut self.text_accent),
+            ("state_pending", &mut self.state_pending),
+            ("state_completing", &mut self.state_completing),
+            ("state_done", &mut self.state_done),
+            ("priority_urgent", &mut self.priority_urgent),
+            ("priority_urgent_bg", &mut self.priority_urgent_bg),
+            ("priority_high", &mut self.priority_high),
+            ("priority_high_bg", &mut self.priority_high_bg),
+            ("priority_medium", &mut self.priority_medium),
+            ("priority_medium_bg", &mut self.priority_medium_bg),
+            ("priority_low", &mut self.priority_low),
+            ("priority_low_bg", &mut self.priority_low_bg),
+            ("tag", &mut self.tag),
+            ("tag_bg", &mut self.tag_bg),
+            ("accent_primary", &mut self.accent_primary),
+            ("accent_warning", &mut self.accent_warning),
+            ("accent_error", &mut self.accent_error),
+            ("accent_error_bg", &mut self.accent_error_bg),
+            ("clear_sky_background", &mut self.clear_sky_background),
+            ("clear_sky_accent", &mut self.clear_sky_accent),
+        ]
+    }
+}
+
+/// Parse `#rrggbb` or `#rrggbbaa` (the `#` is optional)
+fn parse_hex_color(value: &str) -> Option<Rgba> {
+    let hex = value.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let bits = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(rgb(bits)),
+        8 => Some(rgba(bits)),
+        _ => None,
+    }
+}
 
 /// Waloyo Theme - "Wind & Rain" color palette
 ///
@@ -10,23 +172,128 @@ use gpui::{rgb, rgba, Rgba};
 pub struct Theme;
 
 impl Theme {
+    /// The active palette variant
+    pub fn variant() -> ThemeVariant {
+        if LIGHT_VARIANT.load(Ordering::Relaxed) {
+            ThemeVariant::Light
+        } else {
+            ThemeVariant::Dark
+        }
+    }
+
+    /// Switch the palette; views must re-render to pick it up
+    pub fn set_variant(variant: ThemeVariant) {
+        LIGHT_VARIANT.store(variant == ThemeVariant::Light, Ordering::Relaxed);
+    }
+
+    /// Whether animations are turned off
+    pub fn reduced_motion() -> bool {
+        REDUCED_MOTION.load(Ordering::Relaxed)
+    }
+
+    /// Turn animations off or back on
+    pub fn set_reduced_motion(reduced_motion: bool) {
+        REDUCED_MOTION.store(reduced_motion, Ordering::Relaxed);
+    }
+
+    /// How long to wait for an animation of `ms` to play out; near zero with
+    /// reduced motion, so what follows the animation still happens promptly
+    pub fn anim_delay(ms: u64) -> Duration {
+        if Self::reduced_motion() {
+            Duration::from_millis(Self::ANIM_REDUCED_MOTION)
+        } else {
+            Duration::from_millis(ms)
+        }
+    }
+
+    /// Blend from one color toward another, `t` running from 0.0 (`from`) to 1.0 (`to`)
+    pub fn mix(from: Rgba, to: Rgba, t: f32) -> Rgba {
+        let t = t.clamp(0.0, 1.0);
+        Rgba {
+            r: from.r + (to.r - from.r) * t,
+            g: from.g + (to.g - from.g) * t,
+            b: from.b + (to.b - from.b) * t,
+            a: from.a + (to.a - from.a) * t,
+        }
+    }
+
+    /// The colors of the active palette
+    pub fn colors() -> ThemeColors {
+        match Self::variant() {
+            ThemeVariant::Dark => CUSTOM_COLORS
+                .get()
+                .copied()
+                .unwrap_or_else(ThemeColors::dark),
+            ThemeVariant::Light => ThemeColors::light(),
+        }
+    }
+
+    /// Load a palette of hex strings from a TOML (or `.json`) file.
+    /// Keys match the `ThemeColors` fields; missing keys, and keys whose value
+    /// isn't a hex color, keep the built-in dark colors.
+    pub fn from_file(path: &Path) -> Result<ThemeColors, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read theme file: {}", e))?;
+
+        let entries: HashMap<String, String> = if path.extension().is_some_and(|ext| ext == "json")
+        {
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Failed to parse theme file: {}", e))?
+        } else {
+            toml::from_str(&content).map_err(|e| format!("Failed to parse theme file: {}", e))?
+        };
+
+        let mut colors = ThemeColors::dark();
+        let mut known = 0;
+        for (key, slot) in colors.entries_mut() {
+            if let Some(value) = entries.get(key) {
+                match parse_hex_color(value) {
+                    Some(color) => *slot = color,
+                    None => eprintln!(
+                        "Invalid hex color '{}' for '{}' in theme file, using the default",
+                        value, key
+                    ),
+                }
+                known += 1;
+            }
+        }
+        if known < entries.len() {
+            eprintln!("Ignoring unknown keys in theme file {}", path.display());
+        }
+
+        Ok(colors)
+    }
+
+    /// Use custom colors in place of the built-in dark palette.
+    /// Only the first call takes effect; the theme is read once at startup.
+    pub fn set_custom_colors(colors: ThemeColors) {
+        if CUSTOM_COLORS.set(colors).is_err() {
+            eprintln!("A custom theme is already loaded");
+        }
+    }
+
     // ═══════════════════════════════════════════════════════════════════
     // Background Colors - The Storm
     // ═══════════════════════════════════════════════════════════════════
 
     /// Main background - deep stormy night
     pub fn background() -> Rgba {
-        rgb(0x1a1b26)
+        Self::colors().background
     }
 
     /// Surface background - slightly lighter for cards
     pub fn surface() -> Rgba {
-        rgb(0x24283b)
+        Self::colors().surface
     }
 
     /// Elevated surface - for hover states
     pub fn surface_hover() -> Rgba {
-        rgb(0x2f3549)
+        Self::colors().surface_hover
+    }
+
+    /// Hairline border around cards and inputs
+    pub fn border() -> Rgba {
+        Self::colors().border
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -35,17 +302,17 @@ impl Theme {
 
     /// Primary text - soft white
     pub fn text_primary() -> Rgba {
-        rgb(0xa9b1d6)
+        Self::colors().text_primary
     }
 
     /// Secondary text - muted
     pub fn text_secondary() -> Rgba {
-        rgb(0x565f89)
+        Self::colors().text_secondary
     }
 
     /// Accent text - rain blue
     pub fn text_accent() -> Rgba {
-        rgb(0x7aa2f7)
+        Self::colors().text_accent
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -54,47 +321,115 @@ impl Theme {
 
     /// Pending state - wind gray with blue tint
     pub fn state_pending() -> Rgba {
-        rgb(0x414868)
+        Self::colors().state_pending
     }
 
     /// Completing state - rain blue (animated)
     pub fn state_completing() -> Rgba {
-        rgb(0x7aa2f7)
+        Self::colors().state_completing
     }
 
     /// Done state - clear sky green
     pub fn state_done() -> Rgba {
-        rgb(0x9ece6a)
+        Self::colors().state_done
+    }
+
+    /// Urgent priority - pulsing storm red
+    pub fn priority_urgent() -> Rgba {
+        Self::colors().priority_urgent
+    }
+
+    /// Urgent priority background - 20% opacity storm red
+    pub fn priority_urgent_bg() -> Rgba {
+        Self::colors().priority_urgent_bg
     }
 
     /// High priority - storm red
     pub fn priority_high() -> Rgba {
-        rgb(0xf7768e)
+        Self::colors().priority_high
     }
 
     /// High priority background - 10% opacity storm red
     pub fn priority_high_bg() -> Rgba {
-        rgba(0xf7768e1a)
+        Self::colors().priority_high_bg
     }
 
     /// Medium priority - lightning yellow
     pub fn priority_medium() -> Rgba {
-        rgb(0xe0af68)
+        Self::colors().priority_medium
     }
 
     /// Medium priority background - 10% opacity lightning yellow
     pub fn priority_medium_bg() -> Rgba {
-        rgba(0xe0af681a)
+        Self::colors().priority_medium_bg
     }
 
     /// Low priority - gentle breeze
     pub fn priority_low() -> Rgba {
-        rgb(0x565f89)
+        Self::colors().priority_low
     }
 
     /// Low priority background - 10% opacity gentle breeze
     pub fn priority_low_bg() -> Rgba {
-        rgba(0x565f891a)
+        Self::colors().priority_low_bg
+    }
+
+    /// Tag chip - rain blue
+    #[allow(dead_code)]
+    pub fn tag() -> Rgba {
+        Self::colors().tag
+    }
+
+    /// Tag chip background - 10% opacity rain blue
+    #[allow(dead_code)]
+    pub fn tag_bg() -> Rgba {
+        Self::colors().tag_bg
+    }
+
+    /// Replace the chosen tag colors with `colors`, hex strings keyed by tag name
+    /// Entries that aren't valid hex are skipped and fall back to the hashed hue.
+    pub fn set_tag_colors(colors: &HashMap<String, String>) {
+        let parsed = colors
+            .iter()
+            .filter_map(|(tag, hex)| Some((tag.to_lowercase(), parse_hex_color(hex)?)))
+            .collect();
+        if let Ok(mut tag_colors) = TAG_COLORS.write() {
+            *tag_colors = parsed;
+        }
+    }
+
+    /// Text color for a tag chip: the user's choice, else a hue derived from the name
+    pub fn tag_color(name: &str) -> Rgba {
+        TAG_COLORS
+            .read()
+            .ok()
+            .and_then(|colors| colors.get(&name.to_lowercase()).copied())
+            .unwrap_or_else(|| Self::color_for_tag(name))
+    }
+
+    /// Background for a tag chip, a faint wash of its text color
+    pub fn tag_color_bg(name: &str) -> Rgba {
+        Rgba {
+            a: 0.12,
+            ..Self::tag_color(name)
+        }
+    }
+
+    /// A stable hue for a tag name, so every tag looks the same on every launch
+    /// Uses FNV-1a rather than the std hasher, whose output may change between releases.
+    pub fn color_for_tag(name: &str) -> Rgba {
+        let hash = name
+            .to_lowercase()
+            .bytes()
+            .fold(0x811c_9dc5_u32, |hash, byte| {
+                (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+            });
+        let hue = (hash % 360) as f32 / 360.0;
+        let lightness = match Self::variant() {
+            ThemeVariant::Dark => 0.7,
+            ThemeVariant::Light => 0.4,
+        };
+        hsla(hue, 0.6, lightness, 1.0).into()
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -103,17 +438,22 @@ impl Theme {
 
     /// Primary accent - electric blue
     pub fn accent_primary() -> Rgba {
-        rgb(0x7aa2f7)
+        Self::colors().accent_primary
     }
 
     /// Warning - lightning yellow
     pub fn accent_warning() -> Rgba {
-        rgb(0xe0af68)
+        Self::colors().accent_warning
     }
 
     /// Error - storm red
     pub fn accent_error() -> Rgba {
-        rgb(0xf7768e)
+        Self::colors().accent_error
+    }
+
+    /// Error background - for destructive hover states
+    pub fn accent_error_bg() -> Rgba {
+        Self::colors().accent_error_bg
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -122,12 +462,22 @@ impl Theme {
 
     /// Clear sky background - peaceful dawn
     pub fn clear_sky_background() -> Rgba {
-        rgb(0x1a1f36)
+        Self::colors().clear_sky_background
+    }
+
+    /// The night sky darkened by a storm of the given intensity, 0.0 to 1.0
+    /// A faint red creeps in as the load of pending tasks grows.
+    pub fn storm_background(intensity: f32) -> Rgba {
+        Self::mix(
+            Self::background(),
+            Self::priority_urgent(),
+            intensity * 0.12,
+        )
     }
 
     /// Clear sky accent - sunrise gold
     pub fn clear_sky_accent() -> Rgba {
-        rgb(0xffc777)
+        Self::colors().clear_sky_accent
     }
 
     // ═══════════════════════════════════════════════════════════════════
@@ -145,8 +495,54 @@ impl Theme {
     pub const RADIUS_LG: f32 = 12.0;
 
     /// Animation durations (ms)
+    /// The sway, rain drop and clear sky entries are defaults for `AnimationConfig`.
     pub const ANIM_FAST: u64 = 150;
     pub const ANIM_NORMAL: u64 = 300;
     pub const ANIM_SLOW: u64 = 600;
+    pub const ANIM_WIND_SWAY: u64 = 3000;
     pub const ANIM_RAIN_DROP: u64 = 800;
+    pub const ANIM_CLEAR_SKY: u64 = 2000;
+    /// Stand-in for every duration when reduced motion is on
+    pub const ANIM_REDUCED_MOTION: u64 = 50;
+
+    /// Characters in the task input past which a counter appears
+    pub const INPUT_COUNT_THRESHOLD: usize = 80;
+
+    /// Completed tasks beyond which the Overcome section starts folded
+    pub const COMPLETED_COLLAPSE_THRESHOLD: usize = 10;
+
+    /// Completed tasks the Overcome section builds at a time; more are shown on request
+    pub const COMPLETED_PAGE_SIZE: usize = 50;
+
+    /// Idle time after the last change before tasks are written to disk (ms)
+    pub const SAVE_DEBOUNCE: u64 = 500;
+
+    /// How long a toast stays up before it has faded out (ms)
+    pub const TOAST_DURATION: u64 = 2500;
+
+    /// How far right a card is dragged before letting go deletes it (px)
+    pub const FLING_DELETE_DISTANCE: f32 = 160.0;
+    /// Drag below which a press on a card is still taken as a click (px)
+    pub const FLING_START_DISTANCE: f32 = 6.0;
+
+    /// How long a "Confirm?" delete stays armed (ms)
+    pub const DELETE_CONFIRM_TIMEOUT: u64 = 3000;
+
+    /// How often to check whether another program changed the task file (ms)
+    pub const EXTERNAL_CHANGE_POLL: u64 = 1000;
+
+    /// How often to check for the global quick capture shortcut and tray menu picks (ms)
+    pub const HOTKEY_POLL: u64 = 100;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_tag_always_gets_the_same_color_whatever_its_case() {
+        assert_eq!(Theme::color_for_tag("work"), Theme::color_for_tag("work"));
+        assert_eq!(Theme::color_for_tag("Work"), Theme::color_for_tag("WORK"));
+        assert_ne!(Theme::color_for_tag("work"), Theme::color_for_tag("home"));
+    }
 }
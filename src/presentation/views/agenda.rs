@@ -0,0 +1,170 @@
+use crate::application::TaskService;
+use crate::domain::{AgendaEvent, SortMode, Task};
+use crate::presentation::components::TaskItem;
+use crate::presentation::theme::Theme;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use gpui::prelude::*;
+use gpui::*;
+
+/// Week-at-a-glance view
+/// Lays out the open tasks under their due day for the coming week, with
+/// anything past due collected on top and undated tasks under "Someday".
+pub struct AgendaView {
+    task_service: Entity<TaskService>,
+    _service_observer: Subscription,
+}
+
+impl AgendaView {
+    /// Number of days shown, starting today
+    const DAYS: i64 = 7;
+
+    pub fn new(task_service: Entity<TaskService>, cx: &mut Context<Self>) -> Self {
+        let _service_observer = cx.observe(&task_service, |_this, _service, cx| cx.notify());
+        Self {
+            task_service,
+            _service_observer,
+        }
+    }
+
+    /// Header for a day of the coming week
+    fn day_label(day: NaiveDate, today: NaiveDate) -> String {
+        match (day - today).num_days() {
+            0 => "Today".to_string(),
+            1 => "Tomorrow".to_string(),
+            _ => day.format("%A %m/%d").to_string(),
+        }
+    }
+
+    /// The tasks due on `day`, or before `today` when `day` is None
+    fn tasks_for<'a>(
+        tasks: &[&'a Task],
+        day: Option<NaiveDate>,
+        today: NaiveDate,
+    ) -> Vec<&'a Task> {
+        tasks
+            .iter()
+            .copied()
+            .filter(|t| {
+                t.due_date.is_some_and(|due| match day {
+                    Some(day) => due.date_naive() == day,
+                    None => due.date_naive() < today,
+                })
+            })
+            .collect()
+    }
+
+    fn render_section(
+        &self,
+        id: String,
+        label: String,
+        tasks: Vec<&Task>,
+        highlight: Option<Rgba>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let entity = cx.entity().downgrade();
+        let count = tasks.len();
+
+        div()
+            .id(ElementId::Name(id.into()))
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(highlight.unwrap_or(Theme::text_secondary()))
+                    .child(label)
+                    .when(count > 0, |this| this.child(count.to_string())),
+            )
+            .when(count == 0, |this| {
+                this.child(
+                    div()
+                        .px(px(Theme::PADDING_SM))
+                        .text_xs()
+                        .text_color(Theme::text_secondary())
+                        .opacity(0.6)
+                        .child("—"),
+                )
+            })
+            .children(tasks.into_iter().map(move |task| {
+                let entity = entity.clone();
+                TaskItem::new(task)
+                    .compact(true)
+                    .on_complete(move |id, _window, cx| {
+                        let _ = entity.update(cx, |_view, cx| {
+                            cx.emit(AgendaEvent::Complete(id));
+                        });
+                    })
+            }))
+    }
+}
+
+impl EventEmitter<AgendaEvent> for AgendaView {}
+
+impl Render for AgendaView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let now: DateTime<Local> = Local::now();
+        let today = now.date_naive();
+
+        let open_tasks = self
+            .task_service
+            .read(cx)
+            .sorted_pending(SortMode::DueDateAsc, now);
+        let past_due = Self::tasks_for(&open_tasks, None, today);
+        let someday: Vec<&Task> = open_tasks
+            .iter()
+            .copied()
+            .filter(|t| t.due_date.is_none())
+            .collect();
+
+        let mut agenda = div()
+            .id("agenda-view")
+            .w_full()
+            .flex_1()
+            .overflow_y_scroll()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_3();
+
+        if !past_due.is_empty() {
+            agenda = agenda.child(self.render_section(
+                "agenda-past-due".to_string(),
+                "Past due".to_string(),
+                past_due,
+                Some(Theme::accent_error()),
+                cx,
+            ));
+        }
+
+        for offset in 0..Self::DAYS {
+            let day = today + Duration::days(offset);
+            let tasks = Self::tasks_for(&open_tasks, Some(day), today);
+            let highlight = (offset == 0).then(Theme::text_accent);
+            agenda = agenda.child(self.render_section(
+                format!("agenda-{}", day),
+                Self::day_label(day, today),
+                tasks,
+                highlight,
+                cx,
+            ));
+        }
+
+        if !someday.is_empty() {
+            agenda = agenda.child(self.render_section(
+                "agenda-someday".to_string(),
+                "Someday".to_string(),
+                someday,
+                None,
+                cx,
+            ));
+        }
+
+        agenda
+    }
+}
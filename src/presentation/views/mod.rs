@@ -1,3 +1,11 @@
+mod agenda;
+mod plan;
+mod processing;
+mod settings;
 mod task_list;
 
+pub use agenda::*;
+pub use plan::*;
+pub use processing::*;
+pub use settings::*;
 pub use task_list::*;
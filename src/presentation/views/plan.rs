@@ -0,0 +1,179 @@
+use crate::application::TaskService;
+use crate::domain::{PlanEvent, SortMode, Task, TaskId};
+use crate::presentation::components::TaskItem;
+use crate::presentation::theme::Theme;
+use chrono::{Local, NaiveDate};
+use gpui::prelude::*;
+use gpui::*;
+
+/// A task being dragged between the plan's panes
+#[derive(Clone)]
+struct DraggedTask {
+    id: TaskId,
+    content: SharedString,
+}
+
+/// What follows the cursor while a task is dragged
+impl Render for DraggedTask {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .max_w(px(220.0))
+            .px(px(Theme::PADDING_SM))
+            .py_1()
+            .rounded(px(Theme::RADIUS_MD))
+            .bg(Theme::surface())
+            .border_1()
+            .border_color(Theme::accent_primary())
+            .shadow_lg()
+            .opacity(0.9)
+            .text_sm()
+            .text_color(Theme::text_primary())
+            .child(self.content.clone())
+    }
+}
+
+/// Day planning view
+/// Splits the open tasks into a backlog of undated and later tasks and what's
+/// due today, overdue tasks included. Dragging a task into Today makes it due
+/// today; dragging it back to the backlog clears its due date.
+pub struct PlanView {
+    task_service: Entity<TaskService>,
+    _service_observer: Subscription,
+}
+
+impl PlanView {
+    pub fn new(task_service: Entity<TaskService>, cx: &mut Context<Self>) -> Self {
+        let _service_observer = cx.observe(&task_service, |_this, _service, cx| cx.notify());
+        Self {
+            task_service,
+            _service_observer,
+        }
+    }
+
+    /// Whether a task belongs in the Today pane rather than the backlog
+    fn is_today(task: &Task, today: NaiveDate) -> bool {
+        task.due_date.is_some_and(|due| due.date_naive() <= today)
+    }
+
+    /// One pane, listing its tasks and taking tasks dropped from the other
+    fn render_pane(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        today_pane: bool,
+        open_tasks: &[&Task],
+        today: NaiveDate,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let tasks: Vec<&Task> = open_tasks
+            .iter()
+            .copied()
+            .filter(|t| Self::is_today(t, today) == today_pane)
+            .collect();
+        let hint = if today_pane {
+            "Drag tasks here to plan your day"
+        } else {
+            "Nothing waiting"
+        };
+
+        div()
+            .id(id)
+            .flex_1()
+            .min_w_0()
+            .overflow_y_scroll()
+            .p(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .rounded(px(Theme::RADIUS_MD))
+            .border_1()
+            .border_color(Theme::border())
+            .drag_over::<DraggedTask>(|style, _dragged, _window, _cx| {
+                style
+                    .bg(Theme::surface_hover())
+                    .border_color(Theme::accent_primary())
+            })
+            .on_drop(
+                cx.listener(move |this, dragged: &DraggedTask, _window, cx| {
+                    let today = Local::now().date_naive();
+                    let already_here = this
+                        .task_service
+                        .read(cx)
+                        .pending_tasks()
+                        .any(|t| t.id == dragged.id && Self::is_today(t, today) == today_pane);
+                    if !already_here {
+                        cx.emit(if today_pane {
+                            PlanEvent::Schedule(dragged.id)
+                        } else {
+                            PlanEvent::Unschedule(dragged.id)
+                        });
+                    }
+                }),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(if today_pane {
+                        Theme::text_accent()
+                    } else {
+                        Theme::text_secondary()
+                    })
+                    .child(label)
+                    .when(!tasks.is_empty(), |this| {
+                        this.child(tasks.len().to_string())
+                    }),
+            )
+            .when(tasks.is_empty(), |this| {
+                this.child(
+                    div()
+                        .py(px(Theme::PADDING_MD))
+                        .text_xs()
+                        .text_center()
+                        .text_color(Theme::text_secondary())
+                        .opacity(0.6)
+                        .child(hint),
+                )
+            })
+            .children(tasks.into_iter().map(|task| {
+                let dragged = DraggedTask {
+                    id: task.id,
+                    content: task.content.clone(),
+                };
+                div()
+                    .id(ElementId::NamedInteger("plan-task".into(), task.id.0))
+                    .cursor_grab()
+                    .on_drag(dragged, |dragged, _offset, _window, cx| {
+                        cx.new(|_cx| dragged.clone())
+                    })
+                    .child(TaskItem::new(task).compact(true))
+            }))
+    }
+}
+
+impl EventEmitter<PlanEvent> for PlanView {}
+
+impl Render for PlanView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let now = Local::now();
+        let today = now.date_naive();
+        let open_tasks = self
+            .task_service
+            .read(cx)
+            .sorted_pending(SortMode::DueDateAsc, now);
+
+        div()
+            .id("plan-view")
+            .w_full()
+            .flex_1()
+            .min_h_0()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_SM))
+            .flex()
+            .gap_2()
+            .child(self.render_pane("plan-backlog", "Backlog", false, &open_tasks, today, cx))
+            .child(self.render_pane("plan-today", "Today", true, &open_tasks, today, cx))
+    }
+}
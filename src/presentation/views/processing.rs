@@ -0,0 +1,170 @@
+use crate::domain::{ProcessingEvent, Task, TaskId};
+use crate::presentation::animations::{AnimationConfig, WaloyoAnimations};
+use crate::presentation::components::TaskItem;
+use crate::presentation::theme::Theme;
+use gpui::prelude::*;
+use gpui::*;
+
+/// Inbox processing view
+/// Presents pending tasks one at a time, asking for a single decision on each:
+/// Do, Defer, Delegate or Delete. The decision is emitted and the next task is shown.
+pub struct ProcessingView {
+    focus_handle: FocusHandle,
+    queue: Vec<Task>,
+    position: usize,
+}
+
+impl ProcessingView {
+    pub fn new(queue: Vec<Task>, cx: &mut Context<Self>) -> Self {
+        let view = Self {
+            focus_handle: cx.focus_handle(),
+            queue,
+            position: 0,
+        };
+        if view.current().is_none() {
+            view.schedule_exit(cx);
+        }
+        view
+    }
+
+    pub fn focus_handle(&self) -> &FocusHandle {
+        &self.focus_handle
+    }
+
+    fn current(&self) -> Option<&Task> {
+        self.queue.get(self.position)
+    }
+
+    fn decide(&mut self, decision: fn(TaskId) -> ProcessingEvent, cx: &mut Context<Self>) {
+        if let Some(task) = self.current() {
+            cx.emit(decision(task.id));
+            self.position += 1;
+            if self.current().is_none() {
+                self.schedule_exit(cx);
+            }
+            cx.notify();
+        }
+    }
+
+    /// Let the clear sky flourish play out before leaving
+    fn schedule_exit(&self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Theme::anim_delay(AnimationConfig::get().clear_sky_ms))
+                .await;
+
+            let _ = this.update(cx, |_view, cx| {
+                cx.emit(ProcessingEvent::Exit);
+            });
+        })
+        .detach();
+    }
+
+    fn render_header(&self) -> impl IntoElement {
+        let total = self.queue.len();
+        let current = (self.position + 1).min(total);
+
+        div()
+            .w_full()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_MD))
+            .flex()
+            .items_center()
+            .justify_between()
+            .child(
+                div()
+                    .text_xl()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(Theme::text_primary())
+                    .child("Inbox"),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(Theme::text_secondary())
+                    .child(format!("{} / {}", current, total)),
+            )
+    }
+
+    fn render_task(&self, task: &Task) -> impl IntoElement {
+        div()
+            .w_full()
+            .flex_1()
+            .px(px(Theme::PADDING_LG))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .gap(px(Theme::PADDING_MD))
+            .child(TaskItem::new(task))
+            .when_some(task.notes.clone(), |this, notes| {
+                this.child(
+                    div()
+                        .px(px(Theme::PADDING_MD))
+                        .text_sm()
+                        .text_color(Theme::text_secondary())
+                        .child(notes),
+                )
+            })
+            .child(
+                div()
+                    .flex()
+                    .justify_center()
+                    .gap(px(Theme::PADDING_MD))
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child("D · Do")
+                    .child("F · Defer")
+                    .child("G · Delegate")
+                    .child("X · Delete")
+                    .child("Esc · Exit"),
+            )
+    }
+
+    fn render_clear_sky(&self) -> impl IntoElement {
+        div()
+            .id("processing-clear-sky")
+            .w_full()
+            .flex_1()
+            .flex()
+            .items_center()
+            .justify_center()
+            .child(
+                div()
+                    .text_color(Theme::clear_sky_accent())
+                    .text_center()
+                    .child("☀️ Inbox processed. Clear skies!"),
+            )
+            .clear_sky("processing-clear-sky-anim", true)
+    }
+}
+
+impl EventEmitter<ProcessingEvent> for ProcessingView {}
+
+impl Render for ProcessingView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let body = match self.current() {
+            Some(task) => self.render_task(task).into_any_element(),
+            None => self.render_clear_sky().into_any_element(),
+        };
+
+        div()
+            .id("processing-view")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(Theme::background())
+            .flex()
+            .flex_col()
+            .child(self.render_header())
+            .child(body)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                match event.keystroke.key.as_str() {
+                    "d" | "enter" => this.decide(ProcessingEvent::Do, cx),
+                    "f" => this.decide(ProcessingEvent::Defer, cx),
+                    "g" => this.decide(ProcessingEvent::Delegate, cx),
+                    "x" | "delete" => this.decide(ProcessingEvent::Delete, cx),
+                    "escape" => cx.emit(ProcessingEvent::Exit),
+                    _ => {}
+                }
+            }))
+    }
+}
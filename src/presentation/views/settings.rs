@@ -0,0 +1,216 @@
+use crate::domain::{AnimationSpeed, GroupMode, Settings, SettingsEvent, SortMode, ThemeVariant};
+use crate::presentation::theme::Theme;
+use gpui::prelude::*;
+use gpui::*;
+
+/// Everything the settings view lets the user change
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SettingsValues {
+    pub theme: ThemeVariant,
+    pub sort_mode: SortMode,
+    pub group_mode: GroupMode,
+    pub reduced_motion: bool,
+    pub focus_mode: bool,
+    pub settings: Settings,
+}
+
+/// Toggles and selectors for the persisted preferences
+/// The view only asks for changes through `SettingsEvent`; the owning view
+/// applies and saves them, then hands the new values back.
+pub struct SettingsView {
+    values: SettingsValues,
+}
+
+impl SettingsView {
+    pub fn new(values: SettingsValues) -> Self {
+        Self { values }
+    }
+
+    /// Replace the values shown
+    /// Called by the owning view as it renders, so no notify is needed.
+    pub fn set_values(&mut self, values: SettingsValues) {
+        self.values = values;
+    }
+
+    /// A labelled line holding one control
+    fn render_row(label: &'static str, control: impl IntoElement) -> impl IntoElement {
+        div()
+            .w_full()
+            .flex()
+            .items_center()
+            .justify_between()
+            .gap_2()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(Theme::text_primary())
+                    .child(label),
+            )
+            .child(control)
+    }
+
+    /// One choice of a selector, highlighted when it's the current one
+    fn render_option(
+        label: &'static str,
+        selected: bool,
+        event: SettingsEvent,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .px(px(Theme::PADDING_SM))
+            .py_0p5()
+            .rounded(px(Theme::RADIUS_SM))
+            .text_xs()
+            .cursor_pointer()
+            .text_color(if selected {
+                Theme::accent_primary()
+            } else {
+                Theme::text_secondary()
+            })
+            .when(selected, |this| this.bg(Theme::surface_hover()))
+            .hover(|s| s.bg(Theme::surface_hover()))
+            .child(label)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |_this, _event: &MouseDownEvent, _window, cx| {
+                    cx.emit(event.clone());
+                }),
+            )
+    }
+
+    fn render_selector<T: Copy + PartialEq + 'static>(
+        options: &[(T, &'static str)],
+        current: T,
+        event: fn(T) -> SettingsEvent,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div().flex().items_center().gap_1().children(
+            options.iter().map(|&(value, label)| {
+                Self::render_option(label, value == current, event(value), cx)
+            }),
+        )
+    }
+
+    /// An Off/On pair, for settings that are either on or off
+    fn render_toggle(
+        on: bool,
+        event: fn(bool) -> SettingsEvent,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Self::render_selector(&[(false, "Off"), (true, "On")], on, event, cx)
+    }
+}
+
+impl EventEmitter<SettingsEvent> for SettingsView {}
+
+impl Render for SettingsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let values = self.values;
+
+        div()
+            .id("settings-view")
+            .w_full()
+            .flex_1()
+            .overflow_y_scroll()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_lg()
+                            .font_weight(FontWeight::SEMIBOLD)
+                            .text_color(Theme::text_primary())
+                            .child("Settings"),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child("✕ Done")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|_this, _event: &MouseDownEvent, _window, cx| {
+                                    cx.emit(SettingsEvent::Exit);
+                                }),
+                            ),
+                    ),
+            )
+            .child(Self::render_row(
+                "Theme",
+                Self::render_selector(
+                    &[(ThemeVariant::Dark, "Dark"), (ThemeVariant::Light, "Light")],
+                    values.theme,
+                    SettingsEvent::SetTheme,
+                    cx,
+                ),
+            ))
+            .child(Self::render_row(
+                "Sort by",
+                Self::render_selector(
+                    &[
+                        (SortMode::Manual, "Manual"),
+                        (SortMode::PriorityDesc, "Priority"),
+                        (SortMode::DueDateAsc, "Due date"),
+                        (SortMode::CreatedAsc, "Oldest"),
+                    ],
+                    values.sort_mode,
+                    SettingsEvent::SetSortMode,
+                    cx,
+                ),
+            ))
+            .child(Self::render_row(
+                "Group by",
+                Self::render_selector(
+                    &[
+                        (GroupMode::None, "None"),
+                        (GroupMode::ByTag, "Tag"),
+                        (GroupMode::ByPriority, "Priority"),
+                        (GroupMode::ByDueDate, "Due date"),
+                    ],
+                    values.group_mode,
+                    SettingsEvent::SetGroupMode,
+                    cx,
+                ),
+            ))
+            .child(Self::render_row(
+                "Focus mode",
+                Self::render_toggle(values.focus_mode, SettingsEvent::SetFocusMode, cx),
+            ))
+            .child(Self::render_row(
+                "Reduce motion",
+                Self::render_toggle(values.reduced_motion, SettingsEvent::SetReducedMotion, cx),
+            ))
+            .child(Self::render_row(
+                "Animation speed",
+                Self::render_selector(
+                    &[
+                        (AnimationSpeed::Slow, "Slow"),
+                        (AnimationSpeed::Normal, "Normal"),
+                        (AnimationSpeed::Fast, "Fast"),
+                    ],
+                    values.settings.animation_speed,
+                    SettingsEvent::SetAnimationSpeed,
+                    cx,
+                ),
+            ))
+            .child(Self::render_row(
+                "Reminders",
+                Self::render_toggle(
+                    values.settings.notifications,
+                    SettingsEvent::SetNotifications,
+                    cx,
+                ),
+            ))
+    }
+}
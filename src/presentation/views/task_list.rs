@@ -1,26 +1,202 @@
-use crate::application::TaskService;
-use crate::domain::{TaskId, TaskSubmitted};
-use crate::presentation::animations::WaloyoAnimations;
-use crate::presentation::components::{TaskInput, TaskItem};
+use crate::application::{TaskService, UndoOutcome};
+use crate::domain::{
+    format_effort, humanize_due, humanize_relative, local_datetime, AgendaEvent, DatePickerEvent,
+    GroupMode, PlanEvent, ProcessingEvent, SearchQueryChanged, Settings, SettingsChanged,
+    SettingsEvent, SortMode, Task, TaskEvent, TaskId, TaskPatch, TaskPriority, TaskSubmitted,
+    ThemeVariant,
+};
+use crate::presentation::animations::{clear_sky_rays, AnimationConfig, WaloyoAnimations};
+use crate::presentation::components::{
+    text_input_layer, DatePicker, Fling, FlingEvent, SearchInput, TaskInput, TaskItem, TextBuffer,
+};
 use crate::presentation::theme::Theme;
+use crate::presentation::views::{
+    AgendaView, PlanView, ProcessingView, SettingsValues, SettingsView,
+};
+use chrono::Datelike;
+use gpui::prelude::*;
 use gpui::*;
+use std::collections::HashSet;
+use std::ops::Range;
 use std::time::Duration;
 
 /// The main task list view
+/// The fields of a task being edited, committed together on Enter
+#[derive(Default)]
+struct EditDraft {
+    content: TextBuffer,
+    notes: TextBuffer,
+    priority: TaskPriority,
+    due_date: Option<chrono::DateTime<chrono::Local>>,
+    due_has_time: bool,
+    /// Whether typing goes to the notes rather than the content
+    notes_active: bool,
+}
+
+impl EditDraft {
+    fn from_task(task: &Task) -> Self {
+        Self {
+            content: TextBuffer::new(task.content.to_string()),
+            notes: TextBuffer::new(task.notes.clone().unwrap_or_default()),
+            priority: task.priority,
+            due_date: task.due_date,
+            due_has_time: task.has_due_time(),
+            notes_active: false,
+        }
+    }
+
+    fn active_buffer(&mut self) -> &mut TextBuffer {
+        if self.notes_active {
+            &mut self.notes
+        } else {
+            &mut self.content
+        }
+    }
+
+    fn active_text(&self) -> &TextBuffer {
+        if self.notes_active {
+            &self.notes
+        } else {
+            &self.content
+        }
+    }
+}
+
+/// A submenu unfolded inside the task context menu
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ContextSubmenu {
+    Priority,
+    Due,
+    Snooze,
+}
+
+/// The due date picker, open on a task
+struct DatePickerPopover {
+    task_id: TaskId,
+    /// Where the date chip was clicked, in window coordinates
+    position: Point<Pixels>,
+    picker: Entity<DatePicker>,
+}
+
+/// What the export menu copies to the clipboard
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Markdown,
+    Csv,
+    Ics,
+    /// A recap of what was overcome this week
+    WeeklyReview,
+}
+
+/// A press on a pending task that may become a fling to delete it
+struct FlingGesture {
+    task_id: TaskId,
+    /// Where the press was, in window x coordinates
+    start_x: f32,
+    /// How far right of the press the pointer is now
+    offset: f32,
+}
+
+/// The right-click menu open on a task
+struct ContextMenu {
+    task_id: TaskId,
+    /// Where the card was clicked, in window coordinates
+    position: Point<Pixels>,
+    submenu: Option<ContextSubmenu>,
+}
+
 /// Displays pending tasks at the top and completed tasks at the bottom
 pub struct TaskListView {
-    task_service: TaskService,
+    /// Shared with whatever else shows the tasks; changes go through `update_service`
+    task_service: Entity<TaskService>,
+    /// Focused when no input is, so arrow keys drive the task selection
+    focus_handle: FocusHandle,
     task_input: Entity<TaskInput>,
+    search_input: Entity<SearchInput>,
+    search_query: SharedString,
     #[allow(dead_code)]
     completing_task: Option<TaskId>,
     clear_sky_celebration: bool,
     editing_task: Option<TaskId>,
-    editing_buffer: SharedString,
+    selected_task: Option<TaskId>,
+    /// Tasks picked with Shift/Ctrl-click for the batch action bar
+    selected: HashSet<TaskId>,
+    edit_draft: EditDraft,
     edit_focus_handle: FocusHandle,
+    editing_notes_task: Option<TaskId>,
+    editing_notes_buffer: TextBuffer,
+    notes_focus_handle: FocusHandle,
+    expanded_task: Option<TaskId>,
+    adding_subtask_task: Option<TaskId>,
+    subtask_buffer: TextBuffer,
+    subtask_focus_handle: FocusHandle,
+    /// A task being completed with a note on how it was overcome
+    note_prompt_task: Option<TaskId>,
+    note_prompt_buffer: TextBuffer,
+    note_prompt_focus_handle: FocusHandle,
+    processing_view: Option<Entity<ProcessingView>>,
+    /// Week-at-a-glance shown in place of the list while open
+    agenda_view: Option<Entity<AgendaView>>,
+    /// Backlog and today side by side for planning the day, shown in place of
+    /// the list while open
+    plan_view: Option<Entity<PlanView>>,
+    /// Preferences and settings, shown in place of the list while open
+    settings_view: Option<Entity<SettingsView>>,
+    /// App settings kept apart from the tasks; the app saves them on `SettingsChanged`
+    settings: Settings,
+    /// A task whose delete button has been clicked once. Deleting takes a second
+    /// click within `Theme::DELETE_CONFIRM_TIMEOUT`; any other click disarms it.
+    pending_delete: Option<TaskId>,
+    /// Right-click menu, if one is open
+    context_menu: Option<ContextMenu>,
+    /// Where the export menu was opened from, while it's open
+    export_menu: Option<Point<Pixels>>,
+    date_picker: Option<DatePickerPopover>,
+    /// The task just added, playing its gust-in entrance; it starts swaying once
+    /// the entrance ends, so the two animations run one after the other
+    entering_task: Option<TaskId>,
+    /// The pending task held down for a fling, if any
+    fling: Option<FlingGesture>,
+    /// A task let go mid-fling, while it springs back or blows away
+    settling_fling: Option<(TaskId, Fling)>,
+    /// Whether the Overcome section hides its tasks
+    completed_collapsed: bool,
+    /// How many tasks the Overcome section lists before offering to show more
+    completed_shown: usize,
+    /// Narrow the pending list to tasks due today or overdue
+    today_filter: bool,
+    /// Narrow the pending list to a single priority
+    priority_filter: Option<TaskPriority>,
+    /// Show the stats strip under the header
+    show_stats: bool,
+    /// Hide everything but the single most pressing task
+    focus_mode: bool,
+    /// How the pending list is split into sections
+    group_mode: GroupMode,
+    /// Completion ratio the progress bar slides from, and the one it slides to
+    progress: (f32, f32),
+    /// Background color the sky shades from, and the one it shades to
+    sky: (Rgba, Rgba),
+    /// How many times the sky has changed, which keeps each shading its own id
+    sky_shifts: u64,
+    /// Service revision a debounced flush is waiting on
+    scheduled_save: Option<u64>,
+    /// Brief message at the bottom of the window, with a count that tells one
+    /// toast from the one replacing it
+    toast: Option<(SharedString, u64)>,
+    toast_count: u64,
 }
 
 impl TaskListView {
-    pub fn new(cx: &mut Context<Self>) -> Self {
+    pub fn new(task_service: Entity<TaskService>, cx: &mut Context<Self>) -> Self {
+        // Re-render whenever the tasks change, whoever changed them, and save
+        // once the changes settle
+        cx.observe(&task_service, |this, _service, cx| {
+            this.schedule_save(cx);
+            cx.notify();
+        })
+        .detach();
+
         // Create the task input
         let task_input = cx.new(|cx| TaskInput::new(cx));
 
@@ -30,59 +206,708 @@ impl TaskListView {
         })
         .detach();
 
-        // Load tasks from storage (or create demo tasks if empty)
-        let service = TaskService::default();
+        // Create the search input and follow its query
+        let search_input = cx.new(|cx| SearchInput::new(cx));
+        cx.subscribe(
+            &search_input,
+            |this, _input, event: &SearchQueryChanged, cx| {
+                this.search_query = event.0.clone().into();
+                cx.notify();
+            },
+        )
+        .detach();
+
+        // The sky clears once the last task is overcome, however that came about
+        cx.subscribe(&task_service, |this, _service, event: &TaskEvent, cx| {
+            if *event == TaskEvent::AllOvercome && !this.clear_sky_celebration {
+                this.clear_sky_celebration = true;
+                cx.notify();
+            }
+        })
+        .detach();
+
+        // Pick up edits other programs make to the task file, and keep the
+        // counts subscribers hear about current
+        cx.spawn(async move |this, cx| loop {
+            cx.background_executor()
+                .timer(Duration::from_millis(Theme::EXTERNAL_CHANGE_POLL))
+                .await;
+            let reloaded = this.update(cx, |this, cx| {
+                // Most polls find nothing new, and those shouldn't redraw anything
+                let changed = this.task_service.update(cx, |service, cx| {
+                    let changed = service.reload_if_changed();
+                    if changed {
+                        cx.notify();
+                    }
+                    // Also catches tasks turning overdue as the clock moves on
+                    service.emit_events(cx);
+                    changed
+                });
+                if changed {
+                    Theme::set_tag_colors(this.task_service.read(cx).tag_colors());
+                    this.forget_missing_tasks(cx);
+                }
+            });
+            if reloaded.is_err() {
+                break;
+            }
+        })
+        .detach();
+
+        // Finish a rain drop still falling and write anything pending before the app exits
+        cx.on_app_quit(|this, cx| {
+            this.task_service.update(cx, |service, _cx| {
+                service.finish_all_completing();
+                service.flush();
+            });
+            async {}
+        })
+        .detach();
+
+        let service = task_service.read(cx);
+        Theme::set_variant(service.theme_variant());
+        Theme::set_tag_colors(service.tag_colors());
+        // WALOYO_REDUCED_MOTION=1 turns animations off regardless of the saved setting
+        let reduced_motion_env = std::env::var("WALOYO_REDUCED_MOTION")
+            .is_ok_and(|v| !v.is_empty() && v != "0" && v != "false");
+        Theme::set_reduced_motion(service.reduced_motion() || reduced_motion_env);
+        let completed_collapsed = Self::initial_completed_collapsed(service);
+        let focus_mode = service.focus_mode();
+        let group_mode = service.group_mode();
+        let completion_ratio = service.completion_ratio();
+        let sky = Self::sky_color(service);
         let edit_focus_handle = cx.focus_handle();
+        let notes_focus_handle = cx.focus_handle();
+        let subtask_focus_handle = cx.focus_handle();
+        let note_prompt_focus_handle = cx.focus_handle();
 
         Self {
-            task_service: service,
+            task_service,
+            focus_handle: cx.focus_handle(),
             task_input,
+            search_input,
+            search_query: "".into(),
             completing_task: None,
             clear_sky_celebration: false,
             editing_task: None,
-            editing_buffer: "".into(),
+            selected_task: None,
+            selected: HashSet::new(),
+            edit_draft: EditDraft::default(),
             edit_focus_handle,
+            editing_notes_task: None,
+            editing_notes_buffer: TextBuffer::default(),
+            notes_focus_handle,
+            expanded_task: None,
+            adding_subtask_task: None,
+            subtask_buffer: TextBuffer::default(),
+            subtask_focus_handle,
+            note_prompt_task: None,
+            note_prompt_buffer: TextBuffer::default(),
+            note_prompt_focus_handle,
+            processing_view: None,
+            agenda_view: None,
+            plan_view: None,
+            settings_view: None,
+            settings: Settings::default(),
+            pending_delete: None,
+            context_menu: None,
+            export_menu: None,
+            date_picker: None,
+            entering_task: None,
+            fling: None,
+            settling_fling: None,
+            completed_collapsed,
+            completed_shown: Theme::COMPLETED_PAGE_SIZE,
+            today_filter: false,
+            priority_filter: None,
+            show_stats: false,
+            focus_mode,
+            group_mode,
+            progress: (completion_ratio, completion_ratio),
+            sky: (sky, sky),
+            sky_shifts: 0,
+            scheduled_save: None,
+            toast: None,
+            toast_count: 0,
+        }
+    }
+
+    /// Start with the settings loaded from disk
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        AnimationConfig::set_speed(settings.animation_speed);
+        self.settings = settings;
+        self
+    }
+
+    /// The current app settings
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Flush changes once they have settled for `Theme::SAVE_DEBOUNCE`,
+    /// coalescing bursts of edits into a single write
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        let revision = self.task_service.read(cx).revision();
+        if !self.task_service.read(cx).is_dirty() || self.scheduled_save == Some(revision) {
+            return;
         }
+        self.scheduled_save = Some(revision);
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(Theme::SAVE_DEBOUNCE))
+                .await;
+
+            let _ = this.update(cx, |view, cx| {
+                // Newer changes have their own flush scheduled
+                let service = view.task_service.read(cx);
+                if service.revision() == revision {
+                    service.flush();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Change the tasks, letting every view observing the service know and
+    /// sending its subscribers the events the change caused
+    fn update_service<R>(
+        &self,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut TaskService) -> R,
+    ) -> R {
+        self.task_service.update(cx, |service, cx| {
+            let result = f(service);
+            service.emit_events(cx);
+            cx.notify();
+            result
+        })
     }
 
     fn add_task(&mut self, content: String, cx: &mut Context<Self>) {
-        self.task_service.add_task(content);
+        let task_id = self.update_service(cx, |service| service.add_task(content));
         // Adding a task means we're no longer in clear sky
         self.clear_sky_celebration = false;
+        self.gust_in(task_id, cx);
+    }
+
+    /// Move a done task back to pending: the wind picks back up
+    fn reopen_task(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        if self.update_service(cx, |service| service.reopen_task(task_id)) {
+            self.clear_sky_celebration = false;
+            self.gust_in(task_id, cx);
+        }
+    }
+
+    /// Play the gust-in entrance for a task arriving in the pending list
+    fn gust_in(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        self.entering_task = Some(task_id);
+        cx.notify();
+
+        // Hand over to the wind sway once the entrance has played
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Theme::anim_delay(Theme::ANIM_NORMAL))
+                .await;
+
+            let _ = this.update(cx, |view, cx| {
+                if view.entering_task == Some(task_id) {
+                    view.entering_task = None;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Put the cursor in the quick-add input, ready to type
+    /// Leaves focus mode, which hides the input.
+    pub fn focus_task_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.focus_mode {
+            self.toggle_focus_mode(cx);
+        }
+        self.agenda_view = None;
+        self.plan_view = None;
+        self.settings_view = None;
+        self.task_input.read(cx).focus(window);
         cx.notify();
     }
 
-    fn start_editing(&mut self, task_id: TaskId, content: SharedString, cx: &mut Context<Self>) {
+    /// Tasks that have come due since the last call, for reminders
+    pub fn take_due_reminders(
+        &mut self,
+        now: chrono::DateTime<chrono::Local>,
+        cx: &mut Context<Self>,
+    ) -> Vec<Task> {
+        // Only which reminders have gone out changes, which nothing shows, but
+        // observers still hear of it so it gets saved
+        self.task_service.update(cx, |service, cx| {
+            let due = service.take_due_reminders(now);
+            if !due.is_empty() {
+                cx.notify();
+            }
+            due
+        })
+    }
+
+    /// The text input that has focus, which typed and composed text goes to
+    /// Falls back to the edit draft, whose content and notes share one focus handle.
+    fn focused_buffer(&mut self, window: &Window) -> &mut TextBuffer {
+        if self.notes_focus_handle.is_focused(window) {
+            &mut self.editing_notes_buffer
+        } else if self.subtask_focus_handle.is_focused(window) {
+            &mut self.subtask_buffer
+        } else if self.note_prompt_focus_handle.is_focused(window) {
+            &mut self.note_prompt_buffer
+        } else {
+            self.edit_draft.active_buffer()
+        }
+    }
+
+    fn focused_text(&self, window: &Window) -> &TextBuffer {
+        if self.notes_focus_handle.is_focused(window) {
+            &self.editing_notes_buffer
+        } else if self.subtask_focus_handle.is_focused(window) {
+            &self.subtask_buffer
+        } else if self.note_prompt_focus_handle.is_focused(window) {
+            &self.note_prompt_buffer
+        } else {
+            self.edit_draft.active_text()
+        }
+    }
+
+    fn start_editing(&mut self, task_id: TaskId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(task) = self.task_service.read(cx).get_task(task_id) else {
+            return;
+        };
+        self.edit_draft = EditDraft::from_task(task);
         self.editing_task = Some(task_id);
-        self.editing_buffer = content;
+        self.selected_task = Some(task_id);
+        self.edit_focus_handle.focus(window);
         cx.notify();
     }
 
     fn cancel_editing(&mut self, cx: &mut Context<Self>) {
         self.editing_task = None;
-        self.editing_buffer = "".into();
+        self.edit_draft = EditDraft::default();
         cx.notify();
     }
 
     fn save_editing(&mut self, cx: &mut Context<Self>) {
         if let Some(task_id) = self.editing_task {
-            if !self.editing_buffer.is_empty() {
-                self.task_service
-                    .update_task_content(task_id, self.editing_buffer.clone());
-            }
+            let draft = &self.edit_draft;
+            let patch = TaskPatch {
+                content: Some(draft.content.text().to_string().into()),
+                priority: Some(draft.priority),
+                due_date: Some(draft.due_date),
+                due_has_time: Some(draft.due_has_time),
+                notes: Some(Some(draft.notes.text().to_string())),
+                ..TaskPatch::default()
+            };
+            self.update_service(cx, |service| service.update_task(task_id, patch));
         }
         self.cancel_editing(cx);
     }
 
+    /// Open the due date picker for a task at `position`
+    /// While the task is being edited the picker starts from the draft's date.
+    fn open_date_picker(
+        &mut self,
+        task_id: TaskId,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (due, has_time) = if self.editing_task == Some(task_id) {
+            (self.edit_draft.due_date, self.edit_draft.due_has_time)
+        } else {
+            self.task_service
+                .read(cx)
+                .get_task(task_id)
+                .map_or((None, false), |t| (t.due_date, t.has_due_time()))
+        };
+        let picker = cx.new(|cx| DatePicker::new(due, has_time, cx));
+        cx.subscribe_in(
+            &picker,
+            window,
+            move |this, _picker, event: &DatePickerEvent, window, cx| {
+                if let DatePickerEvent::Picked(due, has_time) = *event {
+                    this.set_due_date(task_id, due, has_time, cx);
+                }
+                this.close_date_picker(window, cx);
+            },
+        )
+        .detach();
+        picker.read(cx).focus(window);
+        self.date_picker = Some(DatePickerPopover {
+            task_id,
+            position,
+            picker,
+        });
+        cx.notify();
+    }
+
+    /// Close the picker, handing focus back to the edit input it was opened from
+    fn close_date_picker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(popover) = self.date_picker.take() {
+            if self.editing_task == Some(popover.task_id) {
+                self.edit_focus_handle.focus(window);
+            }
+            cx.notify();
+        }
+    }
+
+    /// Set a task's due date, keeping an open edit draft in step
+    fn set_due_date(
+        &mut self,
+        task_id: TaskId,
+        due: Option<chrono::DateTime<chrono::Local>>,
+        has_time: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let has_time = due.is_some() && has_time;
+        self.update_service(cx, |service| {
+            service.update_task(
+                task_id,
+                TaskPatch {
+                    due_date: Some(due),
+                    due_has_time: Some(has_time),
+                    ..TaskPatch::default()
+                },
+            )
+        });
+        if self.editing_task == Some(task_id) {
+            self.edit_draft.due_date = due;
+            self.edit_draft.due_has_time = has_time;
+        }
+    }
+
+    /// The open due date picker, drawn above everything where it was opened
+    fn render_date_picker(&self) -> Option<impl IntoElement> {
+        let popover = self.date_picker.as_ref()?;
+        Some(deferred(
+            anchored()
+                .position(popover.position)
+                .snap_to_window()
+                .child(popover.picker.clone()),
+        ))
+    }
+
+    fn start_editing_notes(
+        &mut self,
+        task_id: TaskId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let notes = self
+            .task_service
+            .read(cx)
+            .get_task(task_id)
+            .and_then(|t| t.notes.clone())
+            .unwrap_or_default();
+        self.editing_notes_task = Some(task_id);
+        self.editing_notes_buffer = TextBuffer::new(notes);
+        self.notes_focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn cancel_editing_notes(&mut self, cx: &mut Context<Self>) {
+        self.editing_notes_task = None;
+        self.editing_notes_buffer.clear();
+        cx.notify();
+    }
+
+    fn save_editing_notes(&mut self, cx: &mut Context<Self>) {
+        if let Some(task_id) = self.editing_notes_task {
+            self.update_service(cx, |service| {
+                service
+                    .update_task_notes(task_id, Some(self.editing_notes_buffer.text().to_string()))
+            });
+        }
+        self.cancel_editing_notes(cx);
+    }
+
+    fn toggle_expanded(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        if self.expanded_task == Some(task_id) {
+            self.expanded_task = None;
+            self.adding_subtask_task = None;
+        } else {
+            self.expanded_task = Some(task_id);
+        }
+        self.selected_task = Some(task_id);
+        cx.notify();
+    }
+
+    fn start_adding_subtask(
+        &mut self,
+        task_id: TaskId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.adding_subtask_task = Some(task_id);
+        self.subtask_buffer.clear();
+        self.subtask_focus_handle.focus(window);
+        cx.notify();
+    }
+
+    fn cancel_adding_subtask(&mut self, cx: &mut Context<Self>) {
+        self.adding_subtask_task = None;
+        self.subtask_buffer.clear();
+        cx.notify();
+    }
+
+    fn save_subtask(&mut self, cx: &mut Context<Self>) {
+        if let Some(task_id) = self.adding_subtask_task {
+            // Stay open so several steps can be added in a row
+            self.update_service(cx, |service| {
+                service.add_subtask(task_id, self.subtask_buffer.text().to_string())
+            });
+            self.subtask_buffer.clear();
+            cx.notify();
+        }
+    }
+
+    fn request_delete(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        if self.pending_delete == Some(task_id) {
+            self.pending_delete = None;
+            self.delete_task(task_id, cx);
+            return;
+        }
+
+        self.pending_delete = Some(task_id);
+        cx.notify();
+
+        // Disarm if the second click doesn't come in time
+        let entity = cx.entity().downgrade();
+        cx.spawn(async move |_weak_entity, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(Theme::DELETE_CONFIRM_TIMEOUT))
+                .await;
+
+            let _ = entity.update(cx, |view, cx| {
+                if view.pending_delete == Some(task_id) {
+                    view.pending_delete = None;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Follow a task being dragged to the right; let go far enough and the wind
+    /// takes it, deleting it as undoably as the delete button does
+    fn handle_fling(&mut self, task_id: TaskId, event: FlingEvent, cx: &mut Context<Self>) {
+        match event {
+            FlingEvent::Press(x) => {
+                self.fling = Some(FlingGesture {
+                    task_id,
+                    start_x: x,
+                    offset: 0.0,
+                });
+            }
+            FlingEvent::Move(x) => {
+                if let Some(fling) = self.fling.as_mut().filter(|f| f.task_id == task_id) {
+                    fling.offset = (x - fling.start_x).max(0.0);
+                    cx.notify();
+                }
+            }
+            FlingEvent::Release => {
+                // Every card hears a release; only the one held down acts on it
+                let Some(fling) = self.fling.take_if(|f| f.task_id == task_id) else {
+                    return;
+                };
+                if fling.offset < Theme::FLING_START_DISTANCE {
+                    return;
+                }
+                let blow_away = fling.offset >= Theme::FLING_DELETE_DISTANCE;
+                let settling = if blow_away {
+                    Fling::BlowingAway(fling.offset)
+                } else {
+                    Fling::Returning(fling.offset)
+                };
+                self.settling_fling = Some((task_id, settling));
+                cx.notify();
+
+                cx.spawn(async move |this, cx| {
+                    cx.background_executor()
+                        .timer(Theme::anim_delay(Theme::ANIM_NORMAL))
+                        .await;
+
+                    let _ = this.update(cx, |view, cx| {
+                        if view.settling_fling == Some((task_id, settling)) {
+                            view.settling_fling = None;
+                            if blow_away {
+                                view.blow_away(task_id, cx);
+                            }
+                            cx.notify();
+                        }
+                    });
+                })
+                .detach();
+            }
+        }
+    }
+
+    /// Where a task is in a fling, for its card
+    fn fling_of(&self, task_id: TaskId) -> Option<Fling> {
+        match &self.fling {
+            Some(fling)
+                if fling.task_id == task_id && fling.offset >= Theme::FLING_START_DISTANCE =>
+            {
+                Some(Fling::Dragging(fling.offset))
+            }
+            _ => self
+                .settling_fling
+                .filter(|(id, _)| *id == task_id)
+                .map(|(_, fling)| fling),
+        }
+    }
+
+    /// Delete a flung task and say how to get it back
+    fn blow_away(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        let Some(content) = self
+            .task_service
+            .read(cx)
+            .get_task(task_id)
+            .map(|t| t.content.clone())
+        else {
+            return;
+        };
+        self.delete_task(task_id, cx);
+        self.forget_missing_tasks(cx);
+        self.show_toast(format!("Blew away '{}' · Ctrl+Z to undo", content), cx);
+    }
+
+    /// Show a message for `Theme::TOAST_DURATION`, replacing any toast still up
+    fn show_toast(&mut self, message: impl Into<SharedString>, cx: &mut Context<Self>) {
+        self.toast_count += 1;
+        let count = self.toast_count;
+        self.toast = Some((message.into(), count));
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(Theme::TOAST_DURATION))
+                .await;
+
+            let _ = this.update(cx, |view, cx| {
+                if view.toast.as_ref().is_some_and(|(_, c)| *c == count) {
+                    view.toast = None;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Undo or redo the last action and say what it did
+    fn step_history(&mut self, undo: bool, cx: &mut Context<Self>) {
+        let outcome: Option<UndoOutcome> = if undo {
+            self.update_service(cx, |service| service.undo())
+        } else {
+            self.update_service(cx, |service| service.redo())
+        };
+        if let Some(outcome) = outcome {
+            self.forget_missing_tasks(cx);
+            let verb = if undo { "Undid" } else { "Redid" };
+            self.show_toast(format!("{}: {}", verb, outcome.describe()), cx);
+        }
+    }
+
+    fn render_toast(&self) -> Option<impl IntoElement> {
+        let (message, count) = self.toast.clone()?;
+        let toast = div()
+            .px(px(Theme::PADDING_MD))
+            .py(px(Theme::PADDING_SM))
+            .bg(Theme::surface())
+            .border_1()
+            .border_color(Theme::border())
+            .rounded(px(Theme::RADIUS_MD))
+            .shadow_lg()
+            .text_sm()
+            .text_color(Theme::text_primary())
+            .child(message);
+        let toast = if Theme::reduced_motion() {
+            toast.into_any_element()
+        } else {
+            // Fully visible for most of its time, fading out over the last part
+            toast
+                .with_animation(
+                    ElementId::NamedInteger("toast".into(), count),
+                    Animation::new(Duration::from_millis(Theme::TOAST_DURATION)),
+                    |toast, delta| toast.opacity(((1.0 - delta) * 4.0).min(1.0)),
+                )
+                .into_any_element()
+        };
+
+        Some(
+            div()
+                .absolute()
+                .bottom(px(Theme::PADDING_LG * 3.0))
+                .left_0()
+                .right_0()
+                .flex()
+                .justify_center()
+                .child(toast),
+        )
+    }
+
+    fn cancel_pending_delete(&mut self, cx: &mut Context<Self>) {
+        if self.pending_delete.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn clear_completed(&mut self, cx: &mut Context<Self>) {
+        // An emptied list keeps whatever sky it had; nothing is left to overcome
+        self.update_service(cx, |service| service.clear_completed());
+        cx.notify();
+    }
+
     fn delete_task(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
-        self.task_service.remove_task(task_id);
-        self.check_clear_sky(cx);
+        self.update_service(cx, |service| service.remove_task(task_id));
+        cx.notify();
+    }
+
+    /// Add a task to the batch selection, or drop it if it's already there
+    fn toggle_select(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
+        if !self.selected.remove(&task_id) {
+            self.selected.insert(task_id);
+        }
+        cx.notify();
+    }
+
+    /// The batch selection in list order
+    fn selected_ids(&self, cx: &App) -> Vec<TaskId> {
+        self.task_service
+            .read(cx)
+            .all_tasks()
+            .iter()
+            .filter(|t| self.selected.contains(&t.id))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    /// Complete every selected task as one undoable step
+    fn complete_selected(&mut self, cx: &mut Context<Self>) {
+        let ids = self.selected_ids(cx);
+        self.selected.clear();
+        self.update_service(cx, |service| service.complete_many(&ids));
+        cx.notify();
+    }
+
+    /// Delete every selected task as one undoable step
+    fn delete_selected(&mut self, cx: &mut Context<Self>) {
+        let ids = self.selected_ids(cx);
+        self.selected.clear();
+        self.update_service(cx, |service| service.remove_many(&ids));
+        self.forget_missing_tasks(cx);
         cx.notify();
     }
 
     fn handle_task_click(&mut self, task_id: TaskId, cx: &mut Context<Self>) {
         // Start the completing animation
-        if self.task_service.begin_completing(task_id) {
+        if self.update_service(cx, |service| service.begin_completing(task_id)) {
             self.completing_task = Some(task_id);
             cx.notify();
 
@@ -90,13 +915,12 @@ impl TaskListView {
             let entity = cx.entity().downgrade();
             cx.spawn(async move |_weak_entity, cx| {
                 cx.background_executor()
-                    .timer(Duration::from_millis(Theme::ANIM_RAIN_DROP))
+                    .timer(Theme::anim_delay(AnimationConfig::get().rain_ms))
                     .await;
 
                 let _ = entity.update(cx, |view, cx| {
-                    view.task_service.finish_completing(task_id);
+                    view.update_service(cx, |service| service.finish_completing(task_id));
                     view.completing_task = None;
-                    view.check_clear_sky(cx);
                     cx.notify();
                 });
             })
@@ -104,225 +928,2193 @@ impl TaskListView {
         }
     }
 
-    fn check_clear_sky(&mut self, cx: &mut Context<Self>) {
-        if self.task_service.all_overcome() && !self.clear_sky_celebration {
-            self.clear_sky_celebration = true;
-            cx.notify();
+    /// Complete a task and ask how it was overcome; for a task already done,
+    /// just edit its note. Leaving the prompt with Esc keeps the task completed.
+    fn complete_with_note_prompt(
+        &mut self,
+        task_id: TaskId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(task) = self.task_service.read(cx).get_task(task_id) else {
+            return;
+        };
+        let note = task.completion_note.clone().unwrap_or_default();
+        if task.is_pending() {
+            self.handle_task_click(task_id, cx);
         }
+        self.note_prompt_task = Some(task_id);
+        self.note_prompt_buffer = TextBuffer::new(note);
+        self.note_prompt_focus_handle.focus(window);
+        cx.notify();
     }
 
-    fn render_header(&self) -> impl IntoElement {
-        let pending = self.task_service.pending_count();
-        let completed = self.task_service.completed_count();
-        let all_done = self.task_service.all_overcome();
-
-        div()
-            .w_full()
-            .px(px(Theme::PADDING_LG))
-            .py(px(Theme::PADDING_MD))
-            .flex()
-            .flex_col()
-            .gap_2()
-            .child(
-                div()
-                    .flex()
-                    .items_center()
-                    .gap_2()
-                    .child(
-                        div()
-                            .text_2xl()
-                            .font_weight(FontWeight::BOLD)
-                            .text_color(if all_done {
-                                Theme::clear_sky_accent()
-                            } else {
-                                Theme::text_primary()
-                            })
-                            .child("Waloyo"),
-                    )
-                    .child(
-                        div()
-                            .text_sm()
-                            .text_color(Theme::text_accent())
-                            .child("We Overcome"),
-                    ),
-            )
-            .child(div().text_sm().text_color(Theme::text_secondary()).child(
-                if all_done && completed > 0 {
-                    format!("🎉 All {} tasks overcome! Clear skies ahead!", completed)
-                } else {
-                    format!("{} pending · {} overcome", pending, completed)
-                },
-            ))
+    fn close_note_prompt(&mut self, cx: &mut Context<Self>) {
+        self.note_prompt_task = None;
+        self.note_prompt_buffer.clear();
+        cx.notify();
     }
 
-    fn render_edit_input(&self, cx: &mut Context<Self>) -> impl IntoElement {
+    fn save_completion_note(&mut self, cx: &mut Context<Self>) {
+        if let Some(task_id) = self.note_prompt_task {
+            self.update_service(cx, |service| {
+                service
+                    .complete_with_note(task_id, Some(self.note_prompt_buffer.text().to_string()))
+            });
+        }
+        self.close_note_prompt(cx);
+    }
+
+    fn start_processing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let queue: Vec<_> = self
+            .task_service
+            .read(cx)
+            .all_tasks()
+            .iter()
+            .filter(|t| t.is_pending())
+            .cloned()
+            .collect();
+
+        let processing_view = cx.new(|cx| ProcessingView::new(queue, cx));
+        cx.subscribe(
+            &processing_view,
+            |this, _view, event: &ProcessingEvent, cx| {
+                this.handle_processing_event(event, cx);
+            },
+        )
+        .detach();
+
+        processing_view.read(cx).focus_handle().focus(window);
+        self.processing_view = Some(processing_view);
+        cx.notify();
+    }
+
+    fn handle_processing_event(&mut self, event: &ProcessingEvent, cx: &mut Context<Self>) {
+        match *event {
+            ProcessingEvent::Do(task_id) => {
+                self.handle_task_click(task_id, cx);
+            }
+            ProcessingEvent::Defer(task_id) => {
+                // Move to the day after the later of the due date and today, keeping any due time
+                let today = chrono::Local::now().date_naive();
+                let (due, has_time) = self
+                    .task_service
+                    .read(cx)
+                    .get_task(task_id)
+                    .map_or((None, false), |t| (t.due_date, t.has_due_time()));
+                let day = due.map_or(today, |due| due.date_naive().max(today));
+                let time = due
+                    .filter(|_| has_time)
+                    .map_or(chrono::NaiveTime::MIN, |due| due.time());
+                if let Some(deferred) = day.succ_opt().and_then(|next| local_datetime(next, time)) {
+                    self.update_service(cx, |service| {
+                        service.update_task_due_date(task_id, Some(deferred), has_time)
+                    });
+                }
+            }
+            ProcessingEvent::Delegate(task_id) => {
+                let notes = match self
+                    .task_service
+                    .read(cx)
+                    .get_task(task_id)
+                    .and_then(|t| t.notes.clone())
+                {
+                    Some(notes) => format!("{}\nDelegated", notes),
+                    None => "Delegated".to_string(),
+                };
+                self.update_service(cx, |service| {
+                    service.update_task_notes(task_id, Some(notes))
+                });
+            }
+            ProcessingEvent::Delete(task_id) => {
+                self.delete_task(task_id, cx);
+            }
+            ProcessingEvent::Exit => {
+                self.processing_view = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn move_selected_task(&mut self, up: bool, cx: &mut Context<Self>) {
+        if let Some(task_id) = self.editing_task.or(self.selected_task) {
+            let moved = if up {
+                self.update_service(cx, |service| service.move_task_up(task_id))
+            } else {
+                self.update_service(cx, |service| service.move_task_down(task_id))
+            };
+            // A manual move is only visible in the manual order
+            if moved && self.task_service.read(cx).sort_mode() != SortMode::Manual {
+                self.update_service(cx, |service| service.set_sort_mode(SortMode::Manual));
+            }
+            cx.notify();
+        }
+    }
+
+    fn cycle_sort_mode(&mut self, cx: &mut Context<Self>) {
+        let mode = self.task_service.read(cx).sort_mode().next();
+        self.update_service(cx, |service| service.set_sort_mode(mode));
+        cx.notify();
+    }
+
+    fn cycle_group_mode(&mut self, cx: &mut Context<Self>) {
+        self.group_mode = self.group_mode.next();
+        self.update_service(cx, |service| service.set_group_mode(self.group_mode));
+        cx.notify();
+    }
+
+    fn cycle_profile(&mut self, cx: &mut Context<Self>) {
+        let profiles = TaskService::profiles();
+        let current = profiles
+            .iter()
+            .position(|p| p == self.task_service.read(cx).profile())
+            .unwrap_or(0);
+        let next = &profiles[(current + 1) % profiles.len()];
+
+        if let Err(e) = self.update_service(cx, |service| service.switch_profile(next)) {
+            eprintln!("Failed to switch profile: {}", e);
+        }
+
+        // Per-task state refers to the old list
+        self.editing_task = None;
+        self.editing_notes_task = None;
+        self.note_prompt_task = None;
+        self.expanded_task = None;
+        self.adding_subtask_task = None;
+        self.selected_task = None;
+        self.selected.clear();
+        self.pending_delete = None;
+        self.clear_sky_celebration = false;
+        self.completed_collapsed = Self::initial_completed_collapsed(self.task_service.read(cx));
+        self.completed_shown = Theme::COMPLETED_PAGE_SIZE;
+        self.focus_mode = self.task_service.read(cx).focus_mode();
+        self.group_mode = self.task_service.read(cx).group_mode();
+        Theme::set_tag_colors(self.task_service.read(cx).tag_colors());
+        cx.notify();
+    }
+
+    /// Drop per-task state pointing at tasks that are no longer in the list
+    fn forget_missing_tasks(&mut self, cx: &App) {
+        let service = self.task_service.read(cx);
+        for task in [
+            &mut self.editing_task,
+            &mut self.editing_notes_task,
+            &mut self.note_prompt_task,
+            &mut self.expanded_task,
+            &mut self.adding_subtask_task,
+            &mut self.selected_task,
+            &mut self.pending_delete,
+            &mut self.entering_task,
+        ] {
+            if task.is_some_and(|id| service.get_task(id).is_none()) {
+                *task = None;
+            }
+        }
+        self.selected.retain(|id| service.get_task(*id).is_some());
+    }
+
+    /// The saved choice, or folded by default once the section gets long
+    fn initial_completed_collapsed(service: &TaskService) -> bool {
+        service
+            .completed_collapsed()
+            .unwrap_or(service.completed_count() > Theme::COMPLETED_COLLAPSE_THRESHOLD)
+    }
+
+    fn toggle_stats(&mut self, cx: &mut Context<Self>) {
+        self.show_stats = !self.show_stats;
+        cx.notify();
+    }
+
+    fn toggle_focus_mode(&mut self, cx: &mut Context<Self>) {
+        self.focus_mode = !self.focus_mode;
+        self.update_service(cx, |service| service.set_focus_mode(self.focus_mode));
+        cx.notify();
+    }
+
+    fn toggle_agenda(&mut self, cx: &mut Context<Self>) {
+        if self.agenda_view.take().is_none() {
+            let task_service = self.task_service.clone();
+            let agenda_view = cx.new(|cx| AgendaView::new(task_service, cx));
+            cx.subscribe(
+                &agenda_view,
+                |this, _view, event: &AgendaEvent, cx| match *event {
+                    AgendaEvent::Complete(task_id) => this.handle_task_click(task_id, cx),
+                },
+            )
+            .detach();
+            self.agenda_view = Some(agenda_view);
+            self.plan_view = None;
+        }
+        cx.notify();
+    }
+
+    fn toggle_plan(&mut self, cx: &mut Context<Self>) {
+        if self.plan_view.take().is_none() {
+            let task_service = self.task_service.clone();
+            let plan_view = cx.new(|cx| PlanView::new(task_service, cx));
+            cx.subscribe(
+                &plan_view,
+                |this, _view, event: &PlanEvent, cx| match *event {
+                    PlanEvent::Schedule(task_id) => {
+                        this.set_due_day(task_id, Some(chrono::Local::now().date_naive()), cx)
+                    }
+                    PlanEvent::Unschedule(task_id) => this.set_due_day(task_id, None, cx),
+                },
+            )
+            .detach();
+            self.plan_view = Some(plan_view);
+            self.agenda_view = None;
+        }
+        cx.notify();
+    }
+
+    fn toggle_settings(&mut self, cx: &mut Context<Self>) {
+        if self.settings_view.take().is_none() {
+            let values = self.settings_values(cx);
+            let settings_view = cx.new(|_cx| SettingsView::new(values));
+            cx.subscribe(&settings_view, |this, _view, event: &SettingsEvent, cx| {
+                this.apply_setting(event.clone(), cx);
+            })
+            .detach();
+            self.settings_view = Some(settings_view);
+        }
+        cx.notify();
+    }
+
+    fn settings_values(&self, cx: &App) -> SettingsValues {
+        let service = self.task_service.read(cx);
+        SettingsValues {
+            theme: service.theme_variant(),
+            sort_mode: service.sort_mode(),
+            group_mode: self.group_mode,
+            reduced_motion: Theme::reduced_motion(),
+            focus_mode: self.focus_mode,
+            settings: self.settings,
+        }
+    }
+
+    /// Apply a change from the settings view right away; preferences are saved
+    /// with the tasks, settings by whoever listens for `SettingsChanged`
+    fn apply_setting(&mut self, event: SettingsEvent, cx: &mut Context<Self>) {
+        match event {
+            SettingsEvent::SetTheme(variant) => {
+                if variant != self.task_service.read(cx).theme_variant() {
+                    self.toggle_theme(cx);
+                }
+            }
+            SettingsEvent::SetSortMode(mode) => {
+                self.update_service(cx, |service| service.set_sort_mode(mode));
+                cx.notify();
+            }
+            SettingsEvent::SetGroupMode(mode) => {
+                self.group_mode = mode;
+                self.update_service(cx, |service| service.set_group_mode(mode));
+                cx.notify();
+            }
+            SettingsEvent::SetReducedMotion(reduced_motion) => {
+                if reduced_motion != Theme::reduced_motion() {
+                    self.toggle_reduced_motion(cx);
+                }
+            }
+            SettingsEvent::SetFocusMode(focus_mode) => {
+                if focus_mode != self.focus_mode {
+                    self.toggle_focus_mode(cx);
+                }
+            }
+            SettingsEvent::SetAnimationSpeed(speed) => {
+                AnimationConfig::set_speed(speed);
+                self.update_settings(
+                    Settings {
+                        animation_speed: speed,
+                        ..self.settings
+                    },
+                    cx,
+                );
+            }
+            SettingsEvent::SetNotifications(notifications) => {
+                self.update_settings(
+                    Settings {
+                        notifications,
+                        ..self.settings
+                    },
+                    cx,
+                );
+            }
+            SettingsEvent::Exit => {
+                self.settings_view = None;
+                cx.notify();
+            }
+        }
+    }
+
+    fn update_settings(&mut self, settings: Settings, cx: &mut Context<Self>) {
+        if settings != self.settings {
+            self.settings = settings;
+            cx.emit(SettingsChanged(settings));
+            cx.notify();
+        }
+    }
+
+    /// Show only tasks of `priority`, or everything again if that's the current filter
+    fn toggle_priority_filter(&mut self, priority: TaskPriority, cx: &mut Context<Self>) {
+        self.priority_filter = (self.priority_filter != Some(priority)).then_some(priority);
+        cx.notify();
+    }
+
+    fn clear_filters(&mut self, cx: &mut Context<Self>) {
+        self.today_filter = false;
+        self.priority_filter = None;
+        self.search_input.update(cx, |search, cx| search.clear(cx));
+        cx.notify();
+    }
+
+    fn toggle_today_filter(&mut self, cx: &mut Context<Self>) {
+        self.today_filter = !self.today_filter;
+        cx.notify();
+    }
+
+    fn toggle_completed_collapsed(&mut self, cx: &mut Context<Self>) {
+        self.completed_collapsed = !self.completed_collapsed;
+        self.update_service(cx, |service| {
+            service.set_completed_collapsed(self.completed_collapsed)
+        });
+        cx.notify();
+    }
+
+    /// Flip the Overcome section between newest and oldest completion first
+    fn toggle_completed_order(&mut self, cx: &mut Context<Self>) {
+        let oldest_first = !self.task_service.read(cx).completed_oldest_first();
+        self.update_service(cx, |service| {
+            service.set_completed_oldest_first(oldest_first)
+        });
+        cx.notify();
+    }
+
+    fn toggle_reduced_motion(&mut self, cx: &mut Context<Self>) {
+        let reduced_motion = !Theme::reduced_motion();
+        self.update_service(cx, |service| service.set_reduced_motion(reduced_motion));
+        Theme::set_reduced_motion(reduced_motion);
+        cx.notify();
+    }
+
+    fn toggle_theme(&mut self, cx: &mut Context<Self>) {
+        let variant = self.task_service.read(cx).theme_variant().toggled();
+        self.update_service(cx, |service| service.set_theme_variant(variant));
+        Theme::set_variant(variant);
+        cx.notify();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let pending = self.task_service.read(cx).pending_count();
+        let now = chrono::Local::now();
+        let overdue = self.task_service.read(cx).overdue_count(now);
+        let due_today = self.task_service.read(cx).due_today_count(now);
+        let today_filter = self.today_filter;
+        let completed = self.task_service.read(cx).completed_count();
+        let all_done = self.task_service.read(cx).all_overcome();
+        let remaining_effort = self.task_service.read(cx).remaining_effort();
+        let sort_label = match self.task_service.read(cx).sort_mode() {
+            SortMode::Manual => "Manual",
+            SortMode::PriorityDesc => "Priority",
+            SortMode::DueDateAsc => "Due date",
+            SortMode::CreatedAsc => "Oldest",
+        };
+        let group_label = match self.group_mode {
+            GroupMode::None => "No groups",
+            GroupMode::ByTag => "Tag",
+            GroupMode::ByPriority => "Priority",
+            GroupMode::ByDueDate => "Due date",
+        };
+
+        div()
+            .w_full()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_MD))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_2xl()
+                            .font_weight(FontWeight::BOLD)
+                            .text_color(if all_done {
+                                Theme::clear_sky_accent()
+                            } else {
+                                Theme::text_primary()
+                            })
+                            .child("Waloyo"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(Theme::text_accent())
+                            .child("We Overcome"),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child(format!("▤ {}", self.task_service.read(cx).profile()))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.cycle_profile(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .when(!self.focus_mode, |this| this.opacity(0.4))
+                            .child("◎")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_focus_mode(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .when(self.agenda_view.is_none(), |this| this.opacity(0.4))
+                            .child("🗓")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_agenda(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .when(self.plan_view.is_none(), |this| this.opacity(0.4))
+                            .child("◧")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_plan(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .when(!self.show_stats, |this| this.opacity(0.4))
+                            .child("📈")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_stats(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            // Faint while animations are off
+                            .when(Theme::reduced_motion(), |this| this.opacity(0.4))
+                            .child("≋")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_reduced_motion(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child(match Theme::variant() {
+                                ThemeVariant::Dark => "☀",
+                                ThemeVariant::Light => "☾",
+                            })
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_theme(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child("⇪")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseDownEvent, _window, cx| {
+                                    this.export_menu = Some(event.position);
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .when(self.settings_view.is_none(), |this| this.opacity(0.4))
+                            .child("⚙")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_settings(cx);
+                                }),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .text_sm()
+                            .text_color(Theme::text_secondary())
+                            .child(if all_done && completed > 0 {
+                                format!("🎉 All {} tasks overcome! Clear skies ahead!", completed)
+                            } else {
+                                format!("{} pending", pending)
+                            })
+                            .when(overdue > 0, |this| {
+                                this.child("·").child(
+                                    div()
+                                        .text_color(Theme::accent_error())
+                                        .child(format!("{} overdue", overdue)),
+                                )
+                            })
+                            .when(!all_done || completed == 0, |this| {
+                                this.child(format!("· {} overcome", completed))
+                            })
+                            .when(remaining_effort > 0, |this| {
+                                this.child(format!(
+                                    "· ~{} to clear the skies",
+                                    format_effort(remaining_effort)
+                                ))
+                            }),
+                    )
+                    .child(div().flex_1())
+                    .child(self.render_priority_filter(cx))
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(if today_filter {
+                                Theme::accent_primary()
+                            } else {
+                                Theme::text_secondary()
+                            })
+                            .when(today_filter, |this| this.bg(Theme::surface_hover()))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child(format!("📅 {} today", due_today))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_today_filter(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child(format!("⇅ {}", sort_label))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.cycle_sort_mode(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| s.bg(Theme::surface_hover()))
+                            .child(format!("▦ {}", group_label))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.cycle_group_mode(cx);
+                                }),
+                            ),
+                    ),
+            )
+            .when(self.show_stats, |this| {
+                this.child(self.render_stats(now, cx))
+            })
+    }
+
+    /// A thin bar filling up as tasks are overcome, turning from storm blue to
+    /// sunrise gold as the sky clears
+    fn render_progress(&self) -> impl IntoElement {
+        let (from, to) = self.progress;
+        let fill = div().h_full().rounded_full().bg(Theme::mix(
+            Theme::accent_primary(),
+            Theme::clear_sky_accent(),
+            to,
+        ));
+        let fill = if from == to || Theme::reduced_motion() {
+            fill.w(relative(to)).into_any_element()
+        } else {
+            fill.with_animation(
+                ElementId::Name(format!("progress-{}", to.to_bits()).into()),
+                Animation::new(Duration::from_millis(Theme::ANIM_NORMAL)).with_easing(ease_in_out),
+                move |bar, delta| bar.w(relative(from + (to - from) * delta)),
+            )
+            .into_any_element()
+        };
+
+        div()
+            .mx(px(Theme::PADDING_LG))
+            .h(px(3.0))
+            .rounded_full()
+            .bg(Theme::surface())
+            .child(fill)
+    }
+
+    /// A compact strip of counts and the current completion streak
+    fn render_stats(&self, now: chrono::DateTime<chrono::Local>, cx: &App) -> impl IntoElement {
+        let stats = self.task_service.read(cx).stats(now);
+
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded(px(Theme::RADIUS_SM))
+            .bg(Theme::surface())
+            .text_xs()
+            .text_color(Theme::text_secondary())
+            .child(format!("{} total", stats.total))
+            .child("·")
+            .child(format!("{} pending", stats.pending))
+            .child("·")
+            .child(format!("{} overdue", stats.overdue))
+            .child("·")
+            .child(format!("{} overcome", stats.completed))
+            .child("·")
+            .child(format!("{} today", stats.completed_today))
+            .child(div().flex_1())
+            .child(
+                div()
+                    .text_color(if stats.daily_streak > 0 {
+                        Theme::text_accent()
+                    } else {
+                        Theme::text_secondary()
+                    })
+                    .child(format!("🔥 {}-day streak", stats.daily_streak)),
+            )
+    }
+
+    fn render_edit_input(&self, cx: &Context<Self>) -> impl IntoElement {
         let focus_handle = self.edit_focus_handle.clone();
+        let draft = &self.edit_draft;
+        let (priority_label, priority_color) = match draft.priority {
+            TaskPriority::Urgent => ("Urgent", Theme::priority_urgent()),
+            TaskPriority::High => ("High", Theme::priority_high()),
+            TaskPriority::Medium => ("Medium", Theme::priority_medium()),
+            TaskPriority::Low => ("Low", Theme::priority_low()),
+        };
+        let due_label = match draft.due_date {
+            Some(due) => humanize_due(due, draft.due_has_time, chrono::Local::now()).0,
+            None => "Add date".to_string(),
+        };
+        let chip = || {
+            div()
+                .px_1()
+                .rounded(px(Theme::RADIUS_SM))
+                .text_xs()
+                .cursor_pointer()
+                .hover(|s| s.bg(Theme::surface_hover()))
+        };
 
         div()
             .w_full()
             .px(px(Theme::PADDING_MD))
             .py(px(Theme::PADDING_SM))
-            .bg(Theme::surface())
-            .rounded(px(Theme::RADIUS_MD))
-            .border_1()
-            .border_color(Theme::accent_primary())
+            .bg(Theme::surface())
+            .rounded(px(Theme::RADIUS_MD))
+            .border_1()
+            .border_color(Theme::accent_primary())
+            .track_focus(&focus_handle)
+            .flex()
+            .flex_col()
+            .gap_1()
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                // While an input method is composing, its keys are its own
+                if this.edit_draft.active_text().is_composing() {
+                    return;
+                }
+                match event.keystroke.key.as_str() {
+                    "enter" if !event.keystroke.modifiers.shift => this.save_editing(cx),
+                    "escape" => this.cancel_editing(cx),
+                    "tab" => {
+                        this.edit_draft.notes_active = !this.edit_draft.notes_active;
+                        cx.notify();
+                    }
+                    _ => {
+                        if this.edit_draft.active_buffer().handle_key(&event.keystroke) {
+                            cx.stop_propagation();
+                            cx.notify();
+                        }
+                    }
+                }
+            }))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap(px(Theme::PADDING_SM))
+                    .child(
+                        div()
+                            .w(px(12.0))
+                            .h(px(12.0))
+                            .rounded_full()
+                            .bg(Theme::state_pending())
+                            .opacity(0.5),
+                    )
+                    .child(
+                        div().flex_1().text_color(Theme::text_primary()).child(
+                            draft
+                                .content
+                                .render_with_caret(!draft.notes_active)
+                                .relative()
+                                .when(!draft.notes_active, |this| {
+                                    this.child(text_input_layer(focus_handle.clone(), cx.entity()))
+                                }),
+                        ),
+                    ),
+            )
+            .child(
+                div()
+                    .pl(px(12.0 + Theme::PADDING_SM))
+                    .text_sm()
+                    .text_color(Theme::text_secondary())
+                    .when(draft.notes.is_empty() && !draft.notes_active, |this| {
+                        this.opacity(0.6).child("Notes (Tab)")
+                    })
+                    .when(!draft.notes.is_empty() || draft.notes_active, |this| {
+                        this.child(
+                            draft
+                                .notes
+                                .render_with_caret(draft.notes_active)
+                                .relative()
+                                .when(draft.notes_active, |this| {
+                                    this.child(text_input_layer(focus_handle.clone(), cx.entity()))
+                                }),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .pl(px(12.0 + Theme::PADDING_SM))
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        chip()
+                            .text_color(priority_color)
+                            .child(format!("◆ {}", priority_label))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.edit_draft.priority = this.edit_draft.priority.next();
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .child(
+                        chip()
+                            .text_color(Theme::text_secondary())
+                            .child(format!("📅 {}", due_label))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                                    if let Some(task_id) = this.editing_task {
+                                        this.open_date_picker(task_id, event.position, window, cx);
+                                    }
+                                }),
+                            ),
+                    )
+                    .when(draft.due_date.is_some(), |this| {
+                        this.child(
+                            chip()
+                                .text_color(Theme::text_secondary())
+                                .child("✕")
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                        this.edit_draft.due_date = None;
+                                        this.edit_draft.due_has_time = false;
+                                        cx.notify();
+                                    }),
+                                ),
+                        )
+                    })
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .opacity(0.6)
+                            .child("Enter save · Shift+Enter new line · Esc cancel"),
+                    ),
+            )
+    }
+
+    fn render_notes_editor(&self, cx: &Context<Self>) -> impl IntoElement {
+        let focus_handle = self.notes_focus_handle.clone();
+        let is_empty = self.editing_notes_buffer.is_empty();
+
+        div()
+            .track_focus(&focus_handle)
+            .w_full()
+            .min_h(px(64.0))
+            .px(px(Theme::PADDING_MD))
+            .py(px(Theme::PADDING_SM))
+            .bg(Theme::surface())
+            .rounded(px(Theme::RADIUS_MD))
+            .border_1()
+            .border_color(Theme::accent_primary())
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex_1()
+                    .relative()
+                    .text_sm()
+                    .text_color(if is_empty {
+                        Theme::text_secondary()
+                    } else {
+                        Theme::text_primary()
+                    })
+                    .child(if is_empty {
+                        div()
+                            .child("Deeper context, thoughts, or reflections...")
+                            .into_any_element()
+                    } else {
+                        self.editing_notes_buffer
+                            .render_with_caret(true)
+                            .into_any_element()
+                    })
+                    .child(text_input_layer(focus_handle.clone(), cx.entity())),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child("Enter for a new line · Ctrl+Enter to save · Esc to cancel"),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                // While an input method is composing, its keys are its own
+                if this.editing_notes_buffer.is_composing() {
+                    return;
+                }
+                match event.keystroke.key.as_str() {
+                    "enter" if event.keystroke.modifiers.control => this.save_editing_notes(cx),
+                    "enter" => {
+                        this.editing_notes_buffer.insert("\n");
+                        cx.stop_propagation();
+                        cx.notify();
+                    }
+                    "escape" => this.cancel_editing_notes(cx),
+                    _ => {
+                        if this.editing_notes_buffer.handle_key(&event.keystroke) {
+                            cx.stop_propagation();
+                            cx.notify();
+                        }
+                    }
+                }
+            }))
+    }
+
+    fn render_subtask_input(&self, cx: &Context<Self>) -> impl IntoElement {
+        let focus_handle = self.subtask_focus_handle.clone();
+        let is_empty = self.subtask_buffer.is_empty();
+
+        div()
+            .track_focus(&focus_handle)
+            .w_full()
+            .px(px(Theme::PADDING_MD))
+            .py(px(Theme::PADDING_SM))
+            .bg(Theme::surface())
+            .rounded(px(Theme::RADIUS_MD))
+            .border_1()
+            .border_color(Theme::accent_primary())
+            .flex()
+            .items_center()
+            .gap(px(Theme::PADDING_SM))
+            .text_sm()
+            .child(div().text_color(Theme::text_secondary()).child("☐"))
+            .child(
+                div()
+                    .flex_1()
+                    .relative()
+                    .text_color(if is_empty {
+                        Theme::text_secondary()
+                    } else {
+                        Theme::text_primary()
+                    })
+                    .child(if is_empty {
+                        div().child("Add a step...").into_any_element()
+                    } else {
+                        self.subtask_buffer
+                            .render_with_caret(true)
+                            .into_any_element()
+                    })
+                    .child(text_input_layer(focus_handle.clone(), cx.entity())),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                if this.subtask_buffer.is_composing() {
+                    return;
+                }
+                match event.keystroke.key.as_str() {
+                    "enter" => this.save_subtask(cx),
+                    "escape" => this.cancel_adding_subtask(cx),
+                    _ => {
+                        if this.subtask_buffer.handle_key(&event.keystroke) {
+                            cx.stop_propagation();
+                            cx.notify();
+                        }
+                    }
+                }
+            }))
+    }
+
+    fn render_note_prompt(&self, cx: &Context<Self>) -> impl IntoElement {
+        let focus_handle = self.note_prompt_focus_handle.clone();
+        let is_empty = self.note_prompt_buffer.is_empty();
+
+        div()
+            .track_focus(&focus_handle)
+            .w_full()
+            .px(px(Theme::PADDING_MD))
+            .py(px(Theme::PADDING_SM))
+            .bg(Theme::surface())
+            .rounded(px(Theme::RADIUS_MD))
+            .border_1()
+            .border_color(Theme::clear_sky_accent())
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_sm()
+            .child(
+                div()
+                    .relative()
+                    .text_color(if is_empty {
+                        Theme::text_secondary()
+                    } else {
+                        Theme::text_primary()
+                    })
+                    .child(if is_empty {
+                        div().child("How did you overcome it?").into_any_element()
+                    } else {
+                        self.note_prompt_buffer
+                            .render_with_caret(true)
+                            .into_any_element()
+                    })
+                    .child(text_input_layer(focus_handle.clone(), cx.entity())),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .opacity(0.6)
+                    .child("Enter save · Esc skip"),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+                if this.note_prompt_buffer.is_composing() {
+                    return;
+                }
+                match event.keystroke.key.as_str() {
+                    "enter" => this.save_completion_note(cx),
+                    "escape" => this.close_note_prompt(cx),
+                    _ => {
+                        if this.note_prompt_buffer.handle_key(&event.keystroke) {
+                            cx.stop_propagation();
+                            cx.notify();
+                        }
+                    }
+                }
+            }))
+    }
+
+    /// Whether a pending task passes the today and priority filters
+    fn is_visible(&self, task: &Task, now: chrono::DateTime<chrono::Local>) -> bool {
+        (!self.today_filter || task.is_due_on(now.date_naive()) || task.is_overdue(now))
+            && self.priority_filter.is_none_or(|p| task.priority == p)
+    }
+
+    /// Whether any filter or search is narrowing the pending list
+    fn is_filtered(&self) -> bool {
+        self.today_filter || self.priority_filter.is_some() || !self.search_query.trim().is_empty()
+    }
+
+    /// The pending tasks as listed: the service's search for the query, best
+    /// matches first, narrowed by the today and priority filters
+    fn visible_pending<'a>(&'a self, cx: &'a App) -> Vec<&'a Task> {
+        let now = chrono::Local::now();
+        self.task_service
+            .read(cx)
+            .search(self.search_query.trim())
+            .into_iter()
+            .filter(|t| self.is_visible(t, now))
+            .collect()
+    }
+
+    /// The visible pending tasks in sections for the current group mode
+    fn visible_groups<'a>(&'a self, cx: &'a App) -> Vec<(SharedString, Vec<&'a Task>)> {
+        let now = chrono::Local::now();
+        let visible = self.visible_pending(cx);
+        self.task_service
+            .read(cx)
+            .grouped_pending(self.group_mode, now)
+            .into_iter()
+            .map(|(header, members)| {
+                let tasks: Vec<_> = visible
+                    .iter()
+                    .copied()
+                    .filter(|t| members.iter().any(|m| m.id == t.id))
+                    .collect();
+                (header, tasks)
+            })
+            .filter(|(_, tasks)| !tasks.is_empty())
+            .collect()
+    }
+
+    /// Move the keyboard selection through the pending list, stopping at the ends
+    fn move_selection(&mut self, down: bool, cx: &mut Context<Self>) {
+        let visible: Vec<TaskId> = self.visible_pending(cx).iter().map(|t| t.id).collect();
+        if visible.is_empty() {
+            return;
+        }
+        let current = self
+            .selected_task
+            .and_then(|id| visible.iter().position(|&v| v == id));
+        let next = match current {
+            Some(index) if down => (index + 1).min(visible.len() - 1),
+            Some(index) => index.saturating_sub(1),
+            None if down => 0,
+            None => visible.len() - 1,
+        };
+        self.selected_task = Some(visible[next]);
+        cx.notify();
+    }
+
+    /// Complete the selected task and move the selection to its neighbour
+    fn complete_selected_task(&mut self, cx: &mut Context<Self>) {
+        let Some(task_id) = self.selected_task else {
+            return;
+        };
+        let visible: Vec<TaskId> = self.visible_pending(cx).iter().map(|t| t.id).collect();
+        if let Some(index) = visible.iter().position(|&id| id == task_id) {
+            self.selected_task = visible
+                .get(index + 1)
+                .or_else(|| index.checked_sub(1).and_then(|i| visible.get(i)))
+                .copied();
+        }
+        self.handle_task_click(task_id, cx);
+    }
+
+    /// Do the next thing: complete the task at the top of the list as shown (or
+    /// the one focus mode shows), rain drop and all, as clicking its indicator would
+    fn complete_top_task(&mut self, cx: &mut Context<Self>) {
+        let top = if self.focus_mode {
+            self.task_service.read(cx).top_task(chrono::Local::now())
+        } else {
+            // Skip tasks whose rain drop is already falling
+            self.visible_pending(cx)
+                .into_iter()
+                .find(|t| t.is_pending())
+        };
+        let Some(task_id) = top.filter(|t| t.is_pending()).map(|t| t.id) else {
+            return;
+        };
+        if self.selected_task == Some(task_id) {
+            self.complete_selected_task(cx);
+        } else {
+            self.handle_task_click(task_id, cx);
+        }
+    }
+
+    fn edit_selected_task(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(task) = self
+            .selected_task
+            .and_then(|id| self.task_service.read(cx).get_task(id))
+        {
+            let task_id = task.id;
+            self.start_editing(task_id, window, cx);
+        }
+    }
+
+    /// Give the selected task a priority from the keyboard, as one undoable step
+    fn set_selected_priority(
+        &mut self,
+        priority: TaskPriority,
+        label: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(task_id) = self.selected_task else {
+            return;
+        };
+        let patch = TaskPatch {
+            priority: Some(priority),
+            ..TaskPatch::default()
+        };
+        if self.update_service(cx, |service| service.update_task(task_id, patch)) {
+            self.show_toast(format!("Priority set to {}", label), cx);
+        }
+    }
+
+    /// Make the selected task due `days` from today, keeping any time of day
+    fn set_selected_due(&mut self, days: u64, label: &str, cx: &mut Context<Self>) {
+        let Some(task_id) = self.selected_task else {
+            return;
+        };
+        let day = chrono::Local::now()
+            .date_naive()
+            .checked_add_days(chrono::Days::new(days));
+        self.set_due_day(task_id, day, cx);
+        self.show_toast(format!("Due {}", label), cx);
+    }
+
+    /// A dot per priority; clicking one narrows the list to it
+    fn render_priority_filter(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let filter = self.priority_filter;
+        div().flex().items_center().gap_1().children(
+            [
+                (TaskPriority::Urgent, Theme::priority_urgent()),
+                (TaskPriority::High, Theme::priority_high()),
+                (TaskPriority::Medium, Theme::priority_medium()),
+                (TaskPriority::Low, Theme::priority_low()),
+            ]
+            .into_iter()
+            .map(|(priority, color)| {
+                let active = filter == Some(priority);
+                div()
+                    .size(px(10.0))
+                    .rounded_full()
+                    .bg(color)
+                    .border_1()
+                    .border_color(if active { Theme::text_primary() } else { color })
+                    .when(filter.is_some() && !active, |this| this.opacity(0.3))
+                    .cursor_pointer()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.toggle_priority_filter(priority, cx);
+                        }),
+                    )
+            }),
+        )
+    }
+
+    /// How many tasks the active filters let through, with a way to drop them all
+    fn render_filter_summary(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let matches = self.visible_pending(cx).len();
+        div()
+            .w_full()
+            .px(px(Theme::PADDING_LG))
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_xs()
+            .text_color(Theme::text_secondary())
+            .child(match matches {
+                1 => "1 match".to_string(),
+                n => format!("{} matches", n),
+            })
+            .child(
+                div()
+                    .px_1()
+                    .rounded(px(Theme::RADIUS_SM))
+                    .cursor_pointer()
+                    .hover(|s| {
+                        s.bg(Theme::surface_hover())
+                            .text_color(Theme::text_accent())
+                    })
+                    .child("✕ Clear filters")
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                            this.clear_filters(cx);
+                        }),
+                    ),
+            )
+    }
+
+    fn render_task_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.search_query.trim();
+
+        let groups = self.visible_groups(cx);
+
+        if groups.is_empty() {
+            let never_used = query.is_empty()
+                && self.priority_filter.is_none()
+                && !self.today_filter
+                && self.task_service.read(cx).all_tasks().is_empty();
+            return div()
+                .id("task-list-container")
+                .w_full()
+                .flex_1()
+                // ... (abbreviated, keeping original logic)
+                .flex()
+                .flex_col()
+                .items_center()
+                .justify_center()
+                .gap_2()
+                .child(
+                    div()
+                        .text_color(Theme::text_secondary())
+                        .text_center()
+                        .child(if !query.is_empty() {
+                            format!("No matches for '{}'", query)
+                        } else if self.priority_filter.is_some() {
+                            "No tasks at this priority".to_string()
+                        } else if self.today_filter {
+                            "Nothing due today — breathe easy".to_string()
+                        } else if self.task_service.read(cx).completed_count() > 0 {
+                            "☀️ Clear skies! All tasks overcome.".to_string()
+                        } else {
+                            "No tasks yet. Add one above!".to_string()
+                        }),
+                )
+                .when(never_used, |this| {
+                    this.child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| {
+                                s.bg(Theme::surface_hover())
+                                    .text_color(Theme::text_primary())
+                            })
+                            .child("Load examples")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.update_service(cx, |service| service.seed_demo_tasks());
+                                    this.show_toast("Added 5 example tasks", cx);
+                                }),
+                            ),
+                    )
+                })
+                .into_any_element();
+        }
+
+        // A task listed under several tags gets its inline editors only the first time
+        let mut listed = HashSet::new();
+        let mut list = div()
+            .id("task-list-container")
+            .w_full()
+            .flex_1()
+            .overflow_y_scroll()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_SM))
+            .flex()
+            .flex_col()
+            .gap_2();
+
+        for (header, tasks) in groups {
+            let rows: Vec<AnyElement> = tasks
+                .into_iter()
+                .map(|task| {
+                    let first = listed.insert(task.id);
+                    self.render_task_row(task, first, cx)
+                })
+                .collect();
+
+            list = list.child(
+                // Ids under the section keep a task listed twice from clashing with itself
+                div()
+                    .id(ElementId::Name(format!("group-{}", header).into()))
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .when(!header.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .pt_1()
+                                .text_xs()
+                                .text_color(Theme::text_secondary())
+                                .child(format!("{} ({})", header, rows.len())),
+                        )
+                    })
+                    .children(rows),
+            );
+        }
+
+        list.into_any_element()
+    }
+
+    /// One pending task, or its edit panel while it is being edited
+    /// `with_editors` places the notes and step inputs under the task.
+    fn render_task_row(&self, task: &Task, with_editors: bool, cx: &Context<Self>) -> AnyElement {
+        let entity = cx.entity().downgrade();
+        let entity_complete = entity.clone();
+        let entity_delete = entity.clone();
+        let entity_edit = entity.clone();
+        let entity_notes = entity.clone();
+        let entity_expand = entity.clone();
+        let entity_add_subtask = entity.clone();
+        let entity_toggle_subtask = entity.clone();
+        let entity_remove_subtask = entity.clone();
+        let entity_select = entity.clone();
+        let entity_pin = entity.clone();
+        let entity_menu = entity.clone();
+        let entity_fling = entity.clone();
+        let entity_priority = entity;
+        let task_id = task.id;
+
+        if with_editors && Some(task_id) == self.editing_task {
+            return self.render_edit_input(cx).into_any_element();
+        }
+
+        let item = TaskItem::new(task)
+            .highlight(self.search_query.clone())
+            .on_complete(move |id, _window, cx| {
+                let _ = entity_complete.update(cx, |view, cx| {
+                    view.handle_task_click(id, cx);
+                });
+            })
+            .selected(Some(task_id) == self.selected_task)
+            .checked(self.selected.contains(&task_id))
+            .selecting(!self.selected.is_empty())
+            .on_toggle_select(move |id, _window, cx| {
+                let _ = entity_select.update(cx, |view, cx| {
+                    view.toggle_select(id, cx);
+                });
+            })
+            .on_toggle_pin(move |id, _window, cx| {
+                let _ = entity_pin.update(cx, |view, cx| {
+                    view.update_service(cx, |service| service.toggle_pin(id));
+                    cx.notify();
+                });
+            })
+            .on_context_menu(move |id, position, _window, cx| {
+                let _ = entity_menu.update(cx, |view, cx| {
+                    view.open_context_menu(id, position, cx);
+                });
+            })
+            .on_cycle_priority(move |id, up, _window, cx| {
+                let _ = entity_priority.update(cx, |view, cx| {
+                    if up {
+                        view.update_service(cx, |service| service.cycle_priority(id));
+                    } else {
+                        view.update_service(cx, |service| service.cycle_priority_back(id));
+                    }
+                    cx.notify();
+                });
+            })
+            .entering(Some(task_id) == self.entering_task)
+            .expanded(Some(task_id) == self.expanded_task)
+            .on_click_content(move |id, _window, cx| {
+                let _ = entity_expand.update(cx, |view, cx| {
+                    view.toggle_expanded(id, cx);
+                });
+            })
+            .on_edit(move |id, window, cx| {
+                let _ = entity_edit.update(cx, |view, cx| {
+                    view.start_editing(id, window, cx);
+                });
+            })
+            .on_add_subtask(move |id, window, cx| {
+                let _ = entity_add_subtask.update(cx, |view, cx| {
+                    view.start_adding_subtask(id, window, cx);
+                });
+            })
+            .on_toggle_subtask(move |id, subtask_id, _window, cx| {
+                let _ = entity_toggle_subtask.update(cx, |view, cx| {
+                    view.update_service(cx, |service| service.toggle_subtask(id, subtask_id));
+                    cx.notify();
+                });
+            })
+            .on_remove_subtask(move |id, subtask_id, _window, cx| {
+                let _ = entity_remove_subtask.update(cx, |view, cx| {
+                    view.update_service(cx, |service| service.remove_subtask(id, subtask_id));
+                    cx.notify();
+                });
+            })
+            .confirming_delete(Some(task_id) == self.pending_delete)
+            .on_delete(move |id, _window, cx| {
+                let _ = entity_delete.update(cx, |view, cx| {
+                    view.request_delete(id, cx);
+                });
+            })
+            .fling(self.fling_of(task_id))
+            .on_fling(move |id, event, _window, cx| {
+                let _ = entity_fling.update(cx, |view, cx| {
+                    view.handle_fling(id, event, cx);
+                });
+            })
+            .on_click_notes(move |id, window, cx| {
+                let _ = entity_notes.update(cx, |view, cx| {
+                    view.start_editing_notes(id, window, cx);
+                });
+            });
+
+        let editing_notes = with_editors && Some(task_id) == self.editing_notes_task;
+        let adding_subtask = with_editors && Some(task_id) == self.adding_subtask_task;
+        let noting = with_editors && Some(task_id) == self.note_prompt_task;
+        if editing_notes || adding_subtask || noting {
+            div()
+                .w_full()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(item)
+                .when(adding_subtask, |this| {
+                    this.child(self.render_subtask_input(cx))
+                })
+                .when(editing_notes, |this| {
+                    this.child(self.render_notes_editor(cx))
+                })
+                .when(noting, |this| this.child(self.render_note_prompt(cx)))
+                .into_any_element()
+        } else {
+            item.into_any_element()
+        }
+    }
+
+    fn render_completed_section(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let oldest_first = self.task_service.read(cx).completed_oldest_first();
+        let completed_tasks = self.task_service.read(cx).completed_sorted(!oldest_first);
+
+        if completed_tasks.is_empty() {
+            return div().into_any_element();
+        }
+
+        div()
+            .w_full()
+            .px(px(Theme::PADDING_LG))
+            .py(px(Theme::PADDING_SM))
             .flex()
-            .items_center()
-            .gap(px(Theme::PADDING_SM))
-            .child(
-                div()
-                    .w(px(12.0))
-                    .h(px(12.0))
-                    .rounded_full()
-                    .bg(Theme::state_pending())
-                    .opacity(0.5),
-            )
+            .flex_col()
+            .gap_2()
             .child(
                 div()
-                    .flex_1()
-                    .track_focus(&focus_handle)
-                    .text_color(Theme::text_primary())
-                    .child(self.editing_buffer.clone())
-                    .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
-                        match &event.keystroke.key {
-                            key if key == "enter" => {
-                                this.save_editing(cx);
-                            }
-                            key if key == "escape" => {
-                                this.cancel_editing(cx);
-                            }
-                            key if key == "backspace" => {
-                                let mut s = this.editing_buffer.to_string();
-                                s.pop();
-                                this.editing_buffer = s.into();
-                                cx.notify();
-                            }
-                            key if key == "space" => {
-                                let mut s = this.editing_buffer.to_string();
-                                s.push(' ');
-                                this.editing_buffer = s.into();
-                                cx.notify();
-                            }
-                            key if key.len() == 1 => {
-                                let mut s = this.editing_buffer.to_string();
-                                if event.keystroke.modifiers.shift {
-                                    s.push_str(&key.to_uppercase());
+                    .mb_2()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child(
+                        div()
+                            .cursor_pointer()
+                            .hover(|s| s.text_color(Theme::text_primary()))
+                            .child(format!(
+                                "{} ✓ Overcome ({})",
+                                if self.completed_collapsed {
+                                    "▸"
                                 } else {
-                                    s.push_str(key);
-                                }
-                                this.editing_buffer = s.into();
-                                cx.notify();
-                            }
-                            _ => {}
-                        }
-                    })), // Save on blur - wait, on_blur triggers when clicking ANYTHING else, including save button if we had one.
-                         // But here clicking outside cancels? Or saves?
-                         // Typically click outside saves.
-                         // .on_blur(cx.listener(|this, _, _, cx| {
-                         // this.save_editing(cx);
-                         // }))
+                                    "▾"
+                                },
+                                completed_tasks.len()
+                            ))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_completed_collapsed(cx);
+                                }),
+                            ),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .cursor_pointer()
+                            .hover(|s| {
+                                s.bg(Theme::surface_hover())
+                                    .text_color(Theme::text_primary())
+                            })
+                            .child(if oldest_first {
+                                "↑ Oldest first"
+                            } else {
+                                "↓ Newest first"
+                            })
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.toggle_completed_order(cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .cursor_pointer()
+                            .hover(|s| {
+                                s.bg(Theme::surface_hover())
+                                    .text_color(Theme::text_primary())
+                            })
+                            .child("Clear all")
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.clear_completed(cx);
+                                }),
+                            ),
+                    ),
             )
+            .when(!self.completed_collapsed, |this| {
+                let entity = cx.entity().downgrade();
+                // Only a page at a time, so months of history don't slow every frame;
+                // a task whose note is being written stays listed wherever it falls
+                let hidden = completed_tasks.len().saturating_sub(self.completed_shown);
+                let shown = completed_tasks.into_iter().enumerate().filter(|(i, task)| {
+                    *i < self.completed_shown || Some(task.id) == self.note_prompt_task
+                });
+                let now = chrono::Local::now();
+                this.children(shown.map(|(_, task)| {
+                    let entity_reopen = entity.clone();
+                    let entity_menu = entity.clone();
+                    let noting = Some(task.id) == self.note_prompt_task;
+                    // Completing a task is the last change made to it
+                    let overcome_ago = div()
+                        .pl(px(Theme::PADDING_SM))
+                        .text_xs()
+                        .text_color(Theme::text_secondary())
+                        .opacity(0.6)
+                        .child(format!(
+                            "overcome {}",
+                            humanize_relative(task.updated_at, now)
+                        ));
+                    let item = TaskItem::new(task)
+                        .on_reopen(move |id, _window, cx| {
+                            let _ = entity_reopen.update(cx, |view, cx| {
+                                view.reopen_task(id, cx);
+                            });
+                        })
+                        .on_context_menu(move |id, position, _window, cx| {
+                            let _ = entity_menu.update(cx, |view, cx| {
+                                view.open_context_menu(id, position, cx);
+                            });
+                        });
+                    div()
+                        .w_full()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(item)
+                        .child(overcome_ago)
+                        .when(noting, |this| this.child(self.render_note_prompt(cx)))
+                        .into_any_element()
+                }))
+                .when(hidden > 0, |this| {
+                    this.child(
+                        div()
+                            .px_1()
+                            .rounded(px(Theme::RADIUS_SM))
+                            .text_xs()
+                            .text_color(Theme::text_secondary())
+                            .cursor_pointer()
+                            .hover(|s| {
+                                s.bg(Theme::surface_hover())
+                                    .text_color(Theme::text_primary())
+                            })
+                            .child(format!(
+                                "Show {} more ({} hidden)",
+                                hidden.min(Theme::COMPLETED_PAGE_SIZE),
+                                hidden
+                            ))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.completed_shown += Theme::COMPLETED_PAGE_SIZE;
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                })
+            })
+            .into_any_element()
     }
 
-    fn render_task_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        let tasks: Vec<_> = self.task_service.all_tasks().to_vec();
-        let entity = cx.entity().downgrade();
-
-        let pending_tasks: Vec<_> = tasks.iter().filter(|t| !t.is_done()).cloned().collect();
+    /// The top task alone, large and centered
+    /// Each new top task gusts in, so completing one reveals the next.
+    fn render_focus_task(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let now = chrono::Local::now();
+        let container = div()
+            .id("focus-container")
+            .w_full()
+            .flex_1()
+            .px(px(Theme::PADDING_LG))
+            .flex()
+            .flex_col()
+            .items_center()
+            .justify_center()
+            .gap_3();
 
-        if pending_tasks.is_empty() {
-            return div()
-                .id("task-list-container")
-                .w_full()
-                .flex_1()
-                // ... (abbreviated, keeping original logic)
-                .flex()
-                .items_center()
-                .justify_center()
+        let Some(task) = self.task_service.read(cx).top_task(now) else {
+            return container
                 .child(
                     div()
                         .text_color(Theme::text_secondary())
-                        .text_center()
-                        .child(if self.task_service.completed_count() > 0 {
-                            "☀️ Clear skies! All tasks overcome."
-                        } else {
-                            "No tasks yet. Add one above!"
-                        }),
+                        .child("☀️ Clear skies! Nothing left to focus on."),
                 )
                 .into_any_element();
+        };
+
+        let task_id = task.id;
+        // The top task itself doesn't count, whether or not it is still completing
+        let waiting = self.task_service.read(cx).pending_count() - usize::from(task.is_pending());
+        let entity = cx.entity().downgrade();
+        let entity_delete = entity.clone();
+        let item = TaskItem::new(task)
+            .on_complete(move |id, _window, cx| {
+                let _ = entity.update(cx, |view, cx| {
+                    view.handle_task_click(id, cx);
+                });
+            })
+            .confirming_delete(Some(task_id) == self.pending_delete)
+            .on_delete(move |id, _window, cx| {
+                let _ = entity_delete.update(cx, |view, cx| {
+                    view.request_delete(id, cx);
+                });
+            });
+
+        container
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(Theme::text_secondary())
+                    .child(if waiting > 0 {
+                        format!("One thing at a time · {} more waiting", waiting)
+                    } else {
+                        "One thing at a time".to_string()
+                    }),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .max_w(px(480.0))
+                    .text_xl()
+                    .child(item)
+                    .gust_in(ElementId::Name(format!("focus-{}", task_id.0).into()), true),
+            )
+            .into_any_element()
+    }
+
+    fn open_context_menu(
+        &mut self,
+        task_id: TaskId,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        self.pending_delete = None;
+        self.context_menu = Some(ContextMenu {
+            task_id,
+            position,
+            submenu: None,
+        });
+        cx.notify();
+    }
+
+    fn close_context_menu(&mut self, cx: &mut Context<Self>) {
+        if self.context_menu.take().is_some() {
+            cx.notify();
         }
+    }
 
-        div()
-            .id("task-list-container")
-            .w_full()
-            .flex_1()
-            .overflow_y_scroll()
-            .px(px(Theme::PADDING_LG))
-            .py(px(Theme::PADDING_SM))
+    /// Unfold a submenu, or fold it again if it's already open
+    fn toggle_context_submenu(&mut self, submenu: ContextSubmenu, cx: &mut Context<Self>) {
+        if let Some(menu) = &mut self.context_menu {
+            menu.submenu = (menu.submenu != Some(submenu)).then_some(submenu);
+            cx.notify();
+        }
+    }
+
+    /// Move a task's due date to `day`, keeping any time of day
+    fn set_due_day(
+        &mut self,
+        task_id: TaskId,
+        day: Option<chrono::NaiveDate>,
+        cx: &mut Context<Self>,
+    ) {
+        let time = self
+            .task_service
+            .read(cx)
+            .get_task(task_id)
+            .filter(|t| t.has_due_time())
+            .and_then(|t| t.due_date)
+            .map(|due| due.time());
+        let due_date =
+            day.and_then(|day| local_datetime(day, time.unwrap_or(chrono::NaiveTime::MIN)));
+        self.update_service(cx, |service| {
+            service.update_task(
+                task_id,
+                TaskPatch {
+                    due_date: Some(due_date),
+                    due_has_time: Some(time.is_some()),
+                    ..TaskPatch::default()
+                },
+            )
+        });
+    }
+
+    /// The right-click menu, drawn above everything at the cursor
+    fn render_context_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let menu = self.context_menu.as_ref()?;
+        let task = self.task_service.read(cx).get_task(menu.task_id)?;
+        let task_id = task.id;
+
+        fn item(label: impl Into<SharedString>) -> Div {
+            div()
+                .px(px(Theme::PADDING_SM))
+                .py_1()
+                .rounded(px(Theme::RADIUS_SM))
+                .text_sm()
+                .text_color(Theme::text_primary())
+                .cursor_pointer()
+                .hover(|s| s.bg(Theme::surface_hover()))
+                .child(label.into())
+        }
+
+        let mut items = div()
+            .id("context-menu")
+            .min_w(px(180.0))
+            .p_1()
             .flex()
             .flex_col()
-            .gap_2()
-            .children(pending_tasks.into_iter().map({
-                let entity = entity.clone();
-                move |task| {
-                    let entity_complete = entity.clone();
-                    let entity_delete = entity.clone();
-                    let entity_edit = entity.clone();
-
-                    if Some(task.id) == self.editing_task {
-                        self.render_edit_input(cx).into_any_element()
+            .bg(Theme::surface())
+            .border_1()
+            .border_color(Theme::border())
+            .rounded(px(Theme::RADIUS_MD))
+            .shadow_lg()
+            .occlude()
+            .on_mouse_down_out(cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                this.close_context_menu(cx);
+            }));
+
+        if task.is_pending() {
+            items = items
+                .child(item("Complete").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.context_menu = None;
+                        this.handle_task_click(task_id, cx);
+                    }),
+                ))
+                .child(item("Complete with note…").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, window, cx| {
+                        this.context_menu = None;
+                        this.complete_with_note_prompt(task_id, window, cx);
+                    }),
+                ))
+                .child(item("Edit").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, window, cx| {
+                        this.context_menu = None;
+                        this.start_editing(task_id, window, cx);
+                    }),
+                ));
+        }
+        if task.is_done() {
+            let label = if task.completion_note.is_some() {
+                "Edit completion note…"
+            } else {
+                "Add completion note…"
+            };
+            items = items.child(item(label).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event: &MouseDownEvent, window, cx| {
+                    this.context_menu = None;
+                    this.complete_with_note_prompt(task_id, window, cx);
+                }),
+            ));
+        }
+
+        items = items.child(item("Duplicate").on_mouse_down(
+            MouseButton::Left,
+            cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                this.context_menu = None;
+                this.update_service(cx, |service| service.duplicate_task(task_id));
+                cx.notify();
+            }),
+        ));
+
+        if task.is_pending() {
+            items = items.child(item("Set priority ▸").on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.toggle_context_submenu(ContextSubmenu::Priority, cx);
+                }),
+            ));
+            if menu.submenu == Some(ContextSubmenu::Priority) {
+                for (priority, label) in [
+                    (TaskPriority::Urgent, "Urgent"),
+                    (TaskPriority::High, "High"),
+                    (TaskPriority::Medium, "Medium"),
+                    (TaskPriority::Low, "Low"),
+                ] {
+                    let label = if task.priority == priority {
+                        format!("✓ {}", label)
                     } else {
-                        TaskItem::new(task.clone())
-                            .on_complete(move |id, _window, cx| {
-                                let _ = entity_complete.update(cx, |view, cx| {
-                                    view.handle_task_click(id, cx);
-                                });
-                            })
-                            .on_click_content(move |id, _window, cx| {
-                                let _ = entity_edit.update(cx, |view, cx| {
-                                    view.start_editing(id, task.content.clone(), cx);
-                                });
-                            })
-                            .on_delete(move |id, _window, cx| {
-                                let _ = entity_delete.update(cx, |view, cx| {
-                                    view.delete_task(id, cx);
-                                });
-                            })
-                            .into_any_element()
-                    }
+                        label.to_string()
+                    };
+                    items = items.child(item(label).pl(px(Theme::PADDING_LG)).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.context_menu = None;
+                            this.update_service(cx, |service| {
+                                service.update_task(
+                                    task_id,
+                                    TaskPatch {
+                                        priority: Some(priority),
+                                        ..TaskPatch::default()
+                                    },
+                                )
+                            });
+                            cx.notify();
+                        }),
+                    ));
                 }
-            }))
-            .into_any_element()
-    }
+            }
 
-    fn render_completed_section(&self) -> impl IntoElement {
-        let completed_tasks: Vec<_> = self
-            .task_service
-            .all_tasks()
-            .iter()
-            .filter(|t| t.is_done())
-            .cloned()
-            .collect();
+            items = items.child(item("Set due ▸").on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.toggle_context_submenu(ContextSubmenu::Due, cx);
+                }),
+            ));
+            if menu.submenu == Some(ContextSubmenu::Due) {
+                let today = chrono::Local::now().date_naive();
+                for (label, day) in [
+                    ("Today", Some(today)),
+                    ("Tomorrow", today.succ_opt()),
+                    ("Next week", today.checked_add_days(chrono::Days::new(7))),
+                    ("No date", None),
+                ] {
+                    items = items.child(item(label).pl(px(Theme::PADDING_LG)).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.context_menu = None;
+                            this.set_due_day(task_id, day, cx);
+                            cx.notify();
+                        }),
+                    ));
+                }
+            }
 
-        if completed_tasks.is_empty() {
-            return div().into_any_element();
+            items = items.child(item("Snooze ▸").on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.toggle_context_submenu(ContextSubmenu::Snooze, cx);
+                }),
+            ));
+            if menu.submenu == Some(ContextSubmenu::Snooze) {
+                for (label, duration) in [
+                    ("+1 day", chrono::Duration::days(1)),
+                    ("+1 week", chrono::Duration::weeks(1)),
+                ] {
+                    items = items.child(item(label).pl(px(Theme::PADDING_LG)).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                            this.context_menu = None;
+                            this.update_service(cx, |service| {
+                                service.snooze_task(task_id, duration)
+                            });
+                            cx.notify();
+                        }),
+                    ));
+                }
+                // Monday of next week, a full week ahead when today is Monday
+                let today = chrono::Local::now().date_naive();
+                let days_to_monday = 7 - u64::from(today.weekday().num_days_from_monday());
+                let next_monday = today.checked_add_days(chrono::Days::new(days_to_monday));
+                items = items.child(item("Next Monday").pl(px(Theme::PADDING_LG)).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.context_menu = None;
+                        this.set_due_day(task_id, next_monday, cx);
+                        cx.notify();
+                    }),
+                ));
+            }
+
+            items = items.child(
+                item(if task.pinned { "Unpin" } else { "Pin" }).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.context_menu = None;
+                        this.update_service(cx, |service| service.toggle_pin(task_id));
+                        cx.notify();
+                    }),
+                ),
+            );
         }
 
-        div()
-            .w_full()
-            .px(px(Theme::PADDING_LG))
-            .py(px(Theme::PADDING_SM))
+        items = items.child(
+            item("Delete")
+                .text_color(Theme::accent_error())
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.context_menu = None;
+                        this.delete_task(task_id, cx);
+                    }),
+                ),
+        );
+
+        Some(deferred(
+            anchored()
+                .position(menu.position)
+                .snap_to_window()
+                .child(items),
+        ))
+    }
+
+    /// Copy an export of the list to the clipboard and say what was copied
+    fn copy_export(&mut self, format: ExportFormat, cx: &mut Context<Self>) {
+        self.export_menu = None;
+        let (text, what) = match format {
+            ExportFormat::Markdown => (
+                self.task_service.read(cx).export_markdown(),
+                "Markdown checklist",
+            ),
+            ExportFormat::Csv => (self.task_service.read(cx).export_csv(), "CSV"),
+            ExportFormat::Ics => (self.task_service.read(cx).export_ics(), "iCalendar feed"),
+            ExportFormat::WeeklyReview => (
+                self.task_service
+                    .read(cx)
+                    .weekly_summary(chrono::Local::now().date_naive())
+                    .render_markdown(),
+                "this week's review",
+            ),
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+        self.show_toast(format!("Copied {} to the clipboard", what), cx);
+    }
+
+    /// The export menu, drawn above everything where the header button was clicked
+    fn render_export_menu(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let position = self.export_menu?;
+        let item = |label: &'static str, format: ExportFormat, cx: &mut Context<Self>| {
+            div()
+                .px(px(Theme::PADDING_SM))
+                .py_1()
+                .rounded(px(Theme::RADIUS_SM))
+                .text_sm()
+                .text_color(Theme::text_primary())
+                .cursor_pointer()
+                .hover(|s| s.bg(Theme::surface_hover()))
+                .child(label)
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
+                        this.copy_export(format, cx);
+                    }),
+                )
+        };
+
+        let items = div()
+            .id("export-menu")
+            .min_w(px(180.0))
+            .p_1()
             .flex()
             .flex_col()
-            .gap_2()
+            .bg(Theme::surface())
+            .border_1()
+            .border_color(Theme::border())
+            .rounded(px(Theme::RADIUS_MD))
+            .shadow_lg()
+            .occlude()
+            .on_mouse_down_out(cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                this.export_menu = None;
+                cx.notify();
+            }))
+            .child(item("Copy as Markdown", ExportFormat::Markdown, cx))
+            .child(item("Copy as CSV", ExportFormat::Csv, cx))
+            .child(item("Copy as iCalendar", ExportFormat::Ics, cx))
+            .child(item("Copy weekly review", ExportFormat::WeeklyReview, cx));
+
+        Some(deferred(
+            anchored().position(position).snap_to_window().child(items),
+        ))
+    }
+
+    /// Floating bar with the actions for the batch selection
+    fn render_batch_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let count = self.selected.len();
+        let button = |label: String, color: Rgba, hover_bg: Rgba| {
+            div()
+                .px(px(Theme::PADDING_SM))
+                .py_1()
+                .rounded(px(Theme::RADIUS_SM))
+                .cursor_pointer()
+                .text_color(color)
+                .hover(move |s| s.bg(hover_bg))
+                .child(label)
+        };
+
+        div()
+            .absolute()
+            .bottom(px(Theme::PADDING_LG))
+            .left_0()
+            .right_0()
+            .flex()
+            .justify_center()
             .child(
                 div()
-                    .text_xs()
-                    .text_color(Theme::text_secondary())
-                    .mb_2()
-                    .child(format!("✓ Overcome ({})", completed_tasks.len())),
+                    .px(px(Theme::PADDING_MD))
+                    .py(px(Theme::PADDING_SM))
+                    .bg(Theme::surface())
+                    .rounded(px(Theme::RADIUS_MD))
+                    .border_1()
+                    .border_color(Theme::accent_primary())
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .text_sm()
+                    .child(
+                        div()
+                            .text_color(Theme::text_secondary())
+                            .child(format!("{} selected", count)),
+                    )
+                    .child(
+                        button(
+                            format!("✓ Complete ({})", count),
+                            Theme::state_done(),
+                            Theme::surface_hover(),
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                cx.stop_propagation();
+                                this.complete_selected(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        button(
+                            format!("× Delete ({})", count),
+                            Theme::accent_error(),
+                            Theme::accent_error_bg(),
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                cx.stop_propagation();
+                                this.delete_selected(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        button(
+                            "Cancel".to_string(),
+                            Theme::text_secondary(),
+                            Theme::surface_hover(),
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                cx.stop_propagation();
+                                this.selected.clear();
+                                cx.notify();
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// The background for the current load: the clear sky once nothing is left
+    /// pending, otherwise a night sky that grows stormier with more, weightier
+    /// and overdue tasks
+    /// An empty list keeps the plain night sky.
+    fn sky_color(service: &TaskService) -> Rgba {
+        if service.all_tasks().is_empty() {
+            Theme::background()
+        } else if service.pending_count() == 0 {
+            Theme::clear_sky_background()
+        } else {
+            Theme::storm_background(service.storm_intensity(chrono::Local::now()))
+        }
+    }
+
+    /// The sky behind everything, shading from its last color to the current one
+    fn render_sky(&self) -> impl IntoElement {
+        let (from, to) = self.sky;
+        let layer = div().absolute().inset_0();
+        if from == to || Theme::reduced_motion() {
+            return layer.bg(to).into_any_element();
+        }
+        layer
+            .with_animation(
+                ElementId::NamedInteger("sky".into(), self.sky_shifts),
+                Animation::new(Duration::from_millis(Theme::ANIM_SLOW)).with_easing(ease_in_out),
+                move |layer, delta| layer.bg(Theme::mix(from, to, delta)),
             )
-            .children(completed_tasks.into_iter().map(TaskItem::new))
             .into_any_element()
     }
 
@@ -331,39 +3123,265 @@ impl TaskListView {
             .id("clear-sky-celebration")
             .absolute()
             .inset_0()
+            .child(clear_sky_rays(self.clear_sky_celebration))
             .clear_sky("clear-sky-anim", self.clear_sky_celebration)
     }
 }
 
+/// Typed and composed text for whichever of the list's inputs has focus: the
+/// edit input, going to the content or notes as Tab chose, the notes editor,
+/// the step input or the completion note; see `text_input_layer`
+impl EntityInputHandler for TaskListView {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        adjusted_range: &mut Option<Range<usize>>,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let (text, range) = self.focused_text(window).text_for_range(range_utf16);
+        *adjusted_range = Some(range);
+        Some(text)
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.focused_text(window).selected_range(),
+            reversed: false,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.focused_text(window).marked_range()
+    }
+
+    fn unmark_text(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.focused_buffer(window).unmark();
+        cx.notify();
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        text: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.focused_buffer(window).replace_text(range_utf16, text);
+        cx.notify();
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.focused_buffer(window).replace_and_mark_text(
+            range_utf16,
+            new_text,
+            new_selected_range_utf16,
+        );
+        cx.notify();
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        element_bounds: Bounds<Pixels>,
+        window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        Some(
+            self.focused_text(window)
+                .bounds_for_range(range_utf16, element_bounds, window),
+        )
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        _point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        None
+    }
+}
+impl EventEmitter<SettingsChanged> for TaskListView {}
+
 impl Render for TaskListView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let all_done = self.task_service.all_overcome();
+        if let Some(processing_view) = &self.processing_view {
+            return div().size_full().child(processing_view.clone());
+        }
 
-        // Background color with Clear Sky mode
-        let bg = if all_done && self.clear_sky_celebration {
-            Theme::clear_sky_background()
-        } else {
-            Theme::background()
-        };
+        if let Some(settings_view) = &self.settings_view {
+            let values = self.settings_values(cx);
+            settings_view.update(cx, |view, _cx| view.set_values(values));
+        }
+
+        let ratio = self.task_service.read(cx).completion_ratio();
+        if ratio != self.progress.1 {
+            self.progress = (self.progress.1, ratio);
+        }
+        let sky = Self::sky_color(self.task_service.read(cx));
+        if sky != self.sky.1 {
+            self.sky = (self.sky.1, sky);
+            self.sky_shifts += 1;
+        }
 
         div()
+            .track_focus(&self.focus_handle)
             .size_full()
-            .bg(bg)
+            .bg(sky)
             .relative()
             .flex()
             .flex_col()
+            .child(self.render_sky())
             .child(self.render_clear_sky_celebration())
-            .child(self.render_header())
-            .child(self.task_input.clone())
-            .child(self.render_task_list(cx))
-            .child(self.render_completed_section())
-            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _window, cx| {
+            .child(self.render_header(cx))
+            .when(!self.task_service.read(cx).all_tasks().is_empty(), |this| {
+                this.child(self.render_progress())
+            })
+            .when_some(self.settings_view.clone(), |this, settings_view| {
+                this.child(settings_view)
+            })
+            .when(self.focus_mode && self.settings_view.is_none(), |this| {
+                this.child(self.render_focus_task(cx))
+            })
+            .when_some(
+                self.agenda_view
+                    .clone()
+                    .filter(|_| !self.focus_mode && self.settings_view.is_none()),
+                |this, agenda_view| this.child(agenda_view),
+            )
+            .when_some(
+                self.plan_view
+                    .clone()
+                    .filter(|_| !self.focus_mode && self.settings_view.is_none()),
+                |this, plan_view| this.child(plan_view),
+            )
+            .when(
+                !self.focus_mode
+                    && self.agenda_view.is_none()
+                    && self.plan_view.is_none()
+                    && self.settings_view.is_none(),
+                |this| {
+                    this.child(self.search_input.clone())
+                        .child(self.task_input.clone())
+                        .when(self.is_filtered(), |this| {
+                            this.child(self.render_filter_summary(cx))
+                        })
+                        .child(self.render_task_list(cx))
+                        .child(self.render_completed_section(cx))
+                },
+            )
+            .when(!self.selected.is_empty(), |this| {
+                this.child(self.render_batch_bar(cx))
+            })
+            .children(self.render_toast())
+            .children(self.render_context_menu(cx))
+            .children(self.render_export_menu(cx))
+            .children(self.render_date_picker())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                    this.cancel_pending_delete(cx);
+                }),
+            )
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                let modifiers = &event.keystroke.modifiers;
+                let key = event.keystroke.key.as_str();
+
+                // Ctrl+Enter completes the top task, from the list or the empty task input
+                if modifiers.control && key == "enter" {
+                    let from_list = this.focus_handle.is_focused(window)
+                        || window.focused(cx).is_none()
+                        || this.task_input.read(cx).is_focused(window);
+                    if from_list {
+                        this.complete_top_task(cx);
+                    }
+                    return;
+                }
+                // Ctrl+Shift+D copies the current state for a bug report; Ctrl+Alt+D
+                // copies it with the text of every task masked
+                if modifiers.control && key == "d" && (modifiers.shift || modifiers.alt) {
+                    let state = if modifiers.alt {
+                        this.task_service.read(cx).export_state_json_redacted()
+                    } else {
+                        this.task_service.read(cx).export_state_json()
+                    };
+                    cx.write_to_clipboard(ClipboardItem::new_string(state));
+                    return;
+                }
+                // Ctrl+I to process the inbox one task at a time
+                if modifiers.control && key == "i" {
+                    this.start_processing(window, cx);
+                    return;
+                }
+                // Alt+Up / Alt+Down to reorder the selected task
+                if modifiers.alt && (key == "up" || key == "down") {
+                    this.move_selected_task(key == "up", cx);
+                    return;
+                }
+                // Ctrl+Y or Ctrl+Shift+Z for Redo
+                if modifiers.control && (key == "y" || (modifiers.shift && key == "z")) {
+                    this.step_history(false, cx);
+                    return;
+                }
                 // Ctrl+Z for Undo
-                if event.keystroke.modifiers.control && event.keystroke.key == "z" {
-                    if this.task_service.undo() {
-                        this.check_clear_sky(cx);
-                        cx.notify();
+                if modifiers.control && key == "z" {
+                    this.step_history(true, cx);
+                    return;
+                }
+                // Escape leaves an input for the list and drops the batch selection
+                if key == "escape" {
+                    this.selected.clear();
+                    this.context_menu = None;
+                    this.export_menu = None;
+                    this.focus_handle.focus(window);
+                    cx.notify();
+                    return;
+                }
+
+                // Keyboard navigation, unless an input has the keys
+                let list_focused =
+                    this.focus_handle.is_focused(window) || window.focused(cx).is_none();
+                if !list_focused || modifiers.control || modifiers.alt {
+                    return;
+                }
+                match key {
+                    "up" => this.move_selection(false, cx),
+                    "down" => this.move_selection(true, cx),
+                    "enter" | "space" => this.complete_selected_task(cx),
+                    "e" => this.edit_selected_task(window, cx),
+                    "f" => this.toggle_focus_mode(cx),
+                    "a" => this.toggle_agenda(cx),
+                    "p" => this.toggle_plan(cx),
+                    "," => this.toggle_settings(cx),
+                    "1" => this.set_selected_priority(TaskPriority::Low, "Low", cx),
+                    "2" => this.set_selected_priority(TaskPriority::Medium, "Medium", cx),
+                    "3" => this.set_selected_priority(TaskPriority::High, "High", cx),
+                    "t" => this.set_selected_due(0, "today", cx),
+                    "m" => this.set_selected_due(1, "tomorrow", cx),
+                    "w" => this.set_selected_due(7, "next week", cx),
+                    "delete" => {
+                        if let Some(task_id) = this.selected_task {
+                            this.request_delete(task_id, cx);
+                        }
                     }
+                    _ => {}
                 }
             }))
     }